@@ -73,6 +73,9 @@ impl AppService {
                     home_dir: app_by_id.home_dir,
                     // TODO: Get this from db
                     flags: Default::default(),
+                    udfs: vec![],
+                    sql: None,
+                    sql_dialect: None,
                 }),
             })
         } else {
@@ -1,4 +1,4 @@
-use dozer_types::models::api_endpoint::ApiEndpoint;
+use dozer_types::models::{api_endpoint::ApiEndpoint, sql_dialect::SqlDialect};
 
 use crate::{
     db::{persistable::Persistable, pool::DbPool},
@@ -32,7 +32,9 @@ impl EndpointService {
             path: request.path.to_owned(),
             sql: Some(request.sql),
             index: request.index,
+            sql_dialect: None,
         };
+        validate_endpoint_sql(&endpoint_info)?;
         endpoint_info
             .upsert(self.db_pool.to_owned())
             .map_err(|op| ErrorResponse {
@@ -120,6 +122,7 @@ impl EndpointService {
         if let Some(index) = request.index {
             endpoint_by_id.index = Some(index);
         }
+        validate_endpoint_sql(&endpoint_by_id)?;
         endpoint_by_id
             .upsert(self.db_pool.to_owned())
             .map_err(|op| ErrorResponse {
@@ -130,3 +133,16 @@ impl EndpointService {
         })
     }
 }
+
+/// Rejects endpoints whose SQL can't be built, with the error pointing at the bad token
+fn validate_endpoint_sql(endpoint: &ApiEndpoint) -> Result<(), ErrorResponse> {
+    match endpoint.sql.as_deref() {
+        Some(sql) if !sql.trim().is_empty() => {
+            let dialect = endpoint.get_sql_dialect(SqlDialect::Ansi);
+            dozer_orchestrator::validate_sql(sql, dialect).map_err(|e| ErrorResponse {
+                message: e.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
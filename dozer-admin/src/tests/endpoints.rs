@@ -68,6 +68,25 @@ mod grpc_service {
         assert_eq!(result.info.unwrap().name, request.name);
     }
 
+    #[test]
+    pub fn create_with_invalid_sql() {
+        let test_db_connection = database_url_for_test_env();
+        let db_pool = establish_test_connection(test_db_connection);
+        let endpoint_service = EndpointService::new(db_pool);
+        let setup_ids = get_setup_ids();
+        let request = CreateEndpointRequest {
+            app_id: setup_ids.app_id,
+            name: "invalid_endpoint".to_owned(),
+            path: "/invalid_endpoint".to_owned(),
+            sql: "select block_number\nfrom eth_logs wher id = 1".to_owned(),
+            index: None,
+        };
+        let error = endpoint_service.create_endpoint(request).unwrap_err();
+        assert!(error
+            .message
+            .ends_with("2 | from eth_logs wher id = 1\n  |                    ^^"));
+    }
+
     #[test]
     pub fn update() {
         let test_db_connection = database_url_for_test_env();
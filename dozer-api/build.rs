@@ -15,6 +15,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             ".dozer.internal.ApplicationDetail",
             "dozer_types::models::app_config::Config",
         )
+        .extern_path(
+            ".dozer.internal.UdfConfig",
+            "dozer_types::models::udf_config::UdfConfig",
+        )
         .extern_path(
             ".dozer.internal.ApiIndex",
             "dozer_types::models::api_endpoint::ApiIndex",
@@ -10,6 +10,7 @@ pub mod epoch;
 pub mod errors;
 pub mod executor;
 mod executor_utils;
+pub mod explain;
 pub mod forwarder;
 pub mod node;
 pub mod record_store;
@@ -141,4 +141,10 @@ pub enum JoinError {
 pub enum SourceError {
     #[error("Failed to find table in Source: {0:?}")]
     PortError(String),
+    #[error("Field {0} added to keep the history of the source already exists in it")]
+    HistoryFieldExists(String),
+    #[error("Field {0} used to expire the records of the source is not a timestamp or a date")]
+    InvalidTimestampField(String),
+    #[error("Sequence number {0} of the operation is too large to number the operations forwarded by the histories of the source")]
+    SeqOutOfRange(u64),
 }
@@ -0,0 +1,298 @@
+use crate::dag::dag::{Dag, Edge, NodeType, DEFAULT_PORT_HANDLE};
+use crate::dag::dag_schemas::DagSchemaManager;
+use crate::dag::errors::ExecutionError;
+use crate::dag::node::{NodeHandle, OutputPortDef, PortHandle};
+use dozer_types::types::Schema;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// Output format of [`explain_dag`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExplainFormat {
+    /// Indented text tree, starting from the sources
+    Tree,
+    /// Graphviz DOT graph
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+impl FromStr for ExplainFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "tree" => Ok(ExplainFormat::Tree),
+            "dot" => Ok(ExplainFormat::Dot),
+            "mermaid" => Ok(ExplainFormat::Mermaid),
+            _ => Err(format!(
+                "Unknown explain format {s}. Expected one of: tree, dot, mermaid"
+            )),
+        }
+    }
+}
+
+struct OutputDescription {
+    port: OutputPortDef,
+    schema: Option<Schema>,
+}
+
+struct NodeDescription {
+    handle: NodeHandle,
+    kind: &'static str,
+    type_name: String,
+    input_ports: Vec<PortHandle>,
+    outputs: Vec<OutputDescription>,
+}
+
+impl NodeDescription {
+    fn header(&self) -> String {
+        format!("{} {} {}", self.handle, self.kind, self.type_name)
+    }
+
+    fn ports(&self) -> String {
+        let inputs = self.input_ports.iter().map(|p| port_name(*p));
+        let outputs = self.outputs.iter().map(|o| port_name(o.port.handle));
+        format!(
+            "in: [{}], out: [{}]",
+            inputs.collect::<Vec<_>>().join(", "),
+            outputs.collect::<Vec<_>>().join(", ")
+        )
+    }
+
+    fn schema_lines(&self) -> Vec<String> {
+        self.outputs
+            .iter()
+            .map(|output| {
+                let schema = match &output.schema {
+                    Some(schema) => describe_schema(schema),
+                    None => "(no schema)".to_string(),
+                };
+                format!(
+                    "{} ({}): {}",
+                    port_name(output.port.handle),
+                    output.port.typ,
+                    schema
+                )
+            })
+            .collect()
+    }
+}
+
+/// Describes the nodes of `dag`, their ports and the schemas flowing between them
+pub fn explain_dag<T: Clone>(
+    dag: &Dag<T>,
+    format: ExplainFormat,
+) -> Result<String, ExecutionError> {
+    let schemas = DagSchemaManager::new(dag)?;
+
+    let mut nodes = vec![];
+    for (handle, node) in &dag.nodes {
+        let (kind, type_name, input_ports, output_ports) = match node {
+            NodeType::Source(s) => ("source", s.type_name(), vec![], s.get_output_ports()?),
+            NodeType::Processor(p) => (
+                "processor",
+                p.type_name(),
+                p.get_input_ports(),
+                p.get_output_ports(),
+            ),
+            NodeType::Sink(s) => ("sink", s.type_name(), s.get_input_ports(), vec![]),
+        };
+        let output_schemas = schemas.get_node_output_schemas(handle)?;
+        let outputs = output_ports
+            .into_iter()
+            .map(|port| OutputDescription {
+                schema: output_schemas.get(&port.handle).map(|(s, _)| s.clone()),
+                port,
+            })
+            .collect();
+        nodes.push(NodeDescription {
+            handle: handle.clone(),
+            kind,
+            type_name: short_type_name(type_name),
+            input_ports,
+            outputs,
+        });
+    }
+    nodes.sort_by_key(|n| n.handle.to_string());
+
+    let mut edges: Vec<&Edge> = dag.edges.iter().collect();
+    edges.sort_by_key(|e| (e.from.node.to_string(), e.from.port, e.to.node.to_string()));
+
+    Ok(match format {
+        ExplainFormat::Tree => explain_tree(&nodes, &edges),
+        ExplainFormat::Dot => explain_dot(&nodes, &edges),
+        ExplainFormat::Mermaid => explain_mermaid(&nodes, &edges),
+    })
+}
+
+fn explain_tree(nodes: &[NodeDescription], edges: &[&Edge]) -> String {
+    let mut out = String::new();
+    let mut visited = HashSet::new();
+    for node in nodes.iter().filter(|n| n.kind == "source") {
+        out.push_str(&node.header());
+        out.push('\n');
+        write_tree_node(&mut out, nodes, edges, node, "", &mut visited);
+    }
+    out
+}
+
+fn write_tree_node<'a>(
+    out: &mut String,
+    nodes: &'a [NodeDescription],
+    edges: &[&Edge],
+    node: &'a NodeDescription,
+    prefix: &str,
+    visited: &mut HashSet<&'a NodeHandle>,
+) {
+    visited.insert(&node.handle);
+
+    let children: Vec<&Edge> = edges
+        .iter()
+        .filter(|e| e.from.node == node.handle)
+        .copied()
+        .collect();
+    let detail_prefix = if children.is_empty() {
+        format!("{prefix}   ")
+    } else {
+        format!("{prefix}│  ")
+    };
+    let _ = writeln!(out, "{detail_prefix}{}", node.ports());
+    for line in node.schema_lines() {
+        let _ = writeln!(out, "{detail_prefix}{line}");
+    }
+
+    for (idx, edge) in children.iter().enumerate() {
+        let last = idx == children.len() - 1;
+        let (branch, next_prefix) = if last {
+            ("└─", format!("{prefix}   "))
+        } else {
+            ("├─", format!("{prefix}│  "))
+        };
+        let Some(child) = nodes.iter().find(|n| n.handle == edge.to.node) else {
+            continue;
+        };
+        let _ = write!(
+            out,
+            "{prefix}{branch} [{} -> {}] {}",
+            port_name(edge.from.port),
+            port_name(edge.to.port),
+            child.header()
+        );
+        if visited.contains(&child.handle) {
+            out.push_str(" (see above)\n");
+        } else {
+            out.push('\n');
+            write_tree_node(out, nodes, edges, child, &next_prefix, visited);
+        }
+    }
+}
+
+fn explain_dot(nodes: &[NodeDescription], edges: &[&Edge]) -> String {
+    let mut out = String::from("digraph dag {\n    node [shape=box];\n");
+    for node in nodes {
+        let mut label = vec![node.header(), node.ports()];
+        label.extend(node.schema_lines());
+        let label = label
+            .iter()
+            .map(|l| escape_dot(l))
+            .collect::<Vec<_>>()
+            .join("\\l");
+        let _ = writeln!(
+            out,
+            "    \"{}\" [label=\"{label}\\l\"];",
+            escape_dot(&node.handle.to_string())
+        );
+    }
+    for edge in edges {
+        let _ = writeln!(
+            out,
+            "    \"{}\" -> \"{}\" [label=\"{} -> {}\"];",
+            escape_dot(&edge.from.node.to_string()),
+            escape_dot(&edge.to.node.to_string()),
+            port_name(edge.from.port),
+            port_name(edge.to.port)
+        );
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn explain_mermaid(nodes: &[NodeDescription], edges: &[&Edge]) -> String {
+    // Handles can contain characters Mermaid does not accept in identifiers
+    let id = |handle: &NodeHandle| {
+        let idx = nodes.iter().position(|n| &n.handle == handle);
+        format!("n{}", idx.unwrap_or(nodes.len()))
+    };
+
+    let mut out = String::from("flowchart TD\n");
+    for node in nodes {
+        let mut label = vec![node.header(), node.ports()];
+        label.extend(node.schema_lines());
+        let label = label
+            .iter()
+            .map(|l| escape_mermaid(l))
+            .collect::<Vec<_>>()
+            .join("<br/>");
+        let _ = writeln!(out, "    {}[\"{label}\"]", id(&node.handle));
+    }
+    for edge in edges {
+        let _ = writeln!(
+            out,
+            "    {} -->|\"{}\"| {}",
+            id(&edge.from.node),
+            escape_mermaid(&format!(
+                "{} -> {}",
+                port_name(edge.from.port),
+                port_name(edge.to.port)
+            )),
+            id(&edge.to.node)
+        );
+    }
+    out
+}
+
+fn port_name(port: PortHandle) -> String {
+    if port == DEFAULT_PORT_HANDLE {
+        "default".to_string()
+    } else {
+        port.to_string()
+    }
+}
+
+fn describe_schema(schema: &Schema) -> String {
+    schema
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            let mut desc = format!("{} {}", field.name, field.typ);
+            if schema.primary_index.contains(&idx) {
+                desc.push_str(" PK");
+            }
+            if field.nullable {
+                desc.push_str(" NULL");
+            }
+            desc
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Turns `crate::module::ProductProcessorFactory<T>` into `ProductProcessor`
+fn short_type_name(type_name: &str) -> String {
+    let name = type_name.split('<').next().unwrap_or(type_name);
+    let name = name.rsplit("::").next().unwrap_or(name);
+    name.strip_suffix("Factory").unwrap_or(name).to_string()
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(s: &str) -> String {
+    s.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
@@ -113,6 +113,11 @@ pub trait SourceFactory<T: Clone>: Send + Sync + Debug {
         &self,
         output_schemas: HashMap<PortHandle, Schema>,
     ) -> Result<Box<dyn Source>, ExecutionError>;
+
+    /// Name of the implementing type, used to describe the node when explaining a DAG
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 pub trait Source: Debug {
@@ -141,6 +146,11 @@ pub trait ProcessorFactory<T: Clone>: Send + Sync + Debug {
         input_schemas: HashMap<PortHandle, Schema>,
         output_schemas: HashMap<PortHandle, Schema>,
     ) -> Result<Box<dyn Processor>, ExecutionError>;
+
+    /// Name of the implementing type, used to describe the node when explaining a DAG
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 pub trait Processor: Debug {
@@ -170,6 +180,11 @@ pub trait SinkFactory<T: Clone>: Send + Sync + Debug {
         &self,
         input_schemas: HashMap<PortHandle, Schema>,
     ) -> Result<Box<dyn Sink>, ExecutionError>;
+
+    /// Name of the implementing type, used to describe the node when explaining a DAG
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 pub trait Sink: Debug {
@@ -21,6 +21,8 @@ mod dag_recordreader_update;
 #[cfg(test)]
 mod dag_schemas;
 #[cfg(test)]
+mod explain;
+#[cfg(test)]
 mod node;
 #[cfg(test)]
 mod record_store;
@@ -0,0 +1,78 @@
+use crate::chk;
+use crate::dag::dag::{Dag, Endpoint, NodeType, DEFAULT_PORT_HANDLE};
+use crate::dag::explain::{explain_dag, ExplainFormat};
+use crate::dag::node::NodeHandle;
+use crate::dag::tests::app::NoneContext;
+use crate::dag::tests::dag_base_run::NoopProcessorFactory;
+use crate::dag::tests::sinks::{CountingSinkFactory, COUNTING_SINK_INPUT_PORT};
+use crate::dag::tests::sources::{GeneratorSourceFactory, GENERATOR_SOURCE_OUTPUT_PORT};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn get_dag() -> Dag<NoneContext> {
+    let latch = Arc::new(AtomicBool::new(true));
+    let mut dag = Dag::new();
+
+    let source_handle = NodeHandle::new(None, "source".to_string());
+    let proc_handle = NodeHandle::new(Some(1), "proc".to_string());
+    let sink_handle = NodeHandle::new(Some(1), "sink".to_string());
+
+    dag.add_node(
+        NodeType::Source(Arc::new(GeneratorSourceFactory::new(
+            1,
+            latch.clone(),
+            false,
+        ))),
+        source_handle.clone(),
+    );
+    dag.add_node(
+        NodeType::Processor(Arc::new(NoopProcessorFactory {})),
+        proc_handle.clone(),
+    );
+    dag.add_node(
+        NodeType::Sink(Arc::new(CountingSinkFactory::new(1, latch))),
+        sink_handle.clone(),
+    );
+
+    chk!(dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    ));
+    chk!(dag.connect(
+        Endpoint::new(proc_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    ));
+    dag
+}
+
+#[test]
+fn test_explain_tree() {
+    let explained = chk!(explain_dag(&get_dag(), ExplainFormat::Tree));
+    let expected = "\
+r_source source GeneratorSource
+│  in: [], out: [100]
+│  100 (Stateless): id string PK, value string
+└─ [100 -> default] 1_proc processor NoopProcessor
+   │  in: [default], out: [default]
+   │  default (Stateless): id string PK, value string
+   └─ [default -> 90] 1_sink sink CountingSink
+         in: [90], out: []
+";
+    assert_eq!(explained, expected);
+}
+
+#[test]
+fn test_explain_graphs() {
+    let dot = chk!(explain_dag(&get_dag(), ExplainFormat::Dot));
+    assert!(dot.starts_with("digraph dag {"));
+    assert!(dot.contains("\"1_proc\" [label=\"1_proc processor NoopProcessor\\l"));
+    assert!(dot.contains("\"r_source\" -> \"1_proc\" [label=\"100 -> default\"];"));
+
+    let mermaid = chk!(explain_dag(&get_dag(), ExplainFormat::Mermaid));
+    assert!(mermaid.starts_with("flowchart TD"));
+    assert!(mermaid.contains("n0[\"1_proc processor NoopProcessor<br/>"));
+    assert!(mermaid.contains("n2 -->|\"100 -#gt; default\"| n0"));
+
+    assert_eq!("Mermaid".parse(), Ok(ExplainFormat::Mermaid));
+    assert!("json".parse::<ExplainFormat>().is_err());
+}
@@ -11,6 +11,7 @@ use crate::cli::{init_dozer, load_config};
 use crate::errors::OrchestrationError;
 use crossterm::{cursor, terminal, ExecutableCommand};
 use dozer_cache::cache::index::get_primary_key;
+use dozer_core::dag::explain::ExplainFormat;
 use dozer_types::crossbeam::channel;
 use dozer_types::log::{debug, error, info};
 use dozer_types::prettytable::color;
@@ -25,6 +26,7 @@ use rustyline_derive::{Completer, Helper, Highlighter, Hinter};
 const HELP: &str = r#"
 Enter your SQL below. 
 Use semicolon at the end to run the query or Ctrl-C to cancel. 
+Prefix a query with EXPLAIN [TREE|DOT|MERMAID] to show its DAG instead of running it.
 
 "#;
 #[derive(Completer, Helper, Highlighter, Hinter)]
@@ -72,7 +74,11 @@ pub fn editor(config_path: &String, running: Arc<AtomicBool>) -> Result<(), Orch
                         .execute(cursor::Hide)
                         .map_err(CliError::TerminalError)?;
 
-                    query(line, config_path, running.clone(), &mut stdout)?;
+                    if let Some((format, sql)) = parse_explain(&line) {
+                        explain(sql, format, config_path)?;
+                    } else {
+                        query(line, config_path, running.clone(), &mut stdout)?;
+                    }
                     stdout
                         .execute(cursor::Show)
                         .map_err(CliError::TerminalError)?;
@@ -98,6 +104,44 @@ pub fn editor(config_path: &String, running: Arc<AtomicBool>) -> Result<(), Orch
     Ok(())
 }
 
+/// Splits `EXPLAIN [TREE|DOT|MERMAID] <query>` into the format and the query
+fn parse_explain(line: &str) -> Option<(ExplainFormat, &str)> {
+    let line = line.trim_start();
+    let keyword = "EXPLAIN";
+    if !line
+        .get(..keyword.len())
+        .map_or(false, |k| k.eq_ignore_ascii_case(keyword))
+    {
+        return None;
+    }
+    let rest = &line[keyword.len()..];
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let rest = rest.trim_start();
+    match rest.split_once(char::is_whitespace) {
+        Some((format, sql)) => match format.parse() {
+            Ok(format) => Some((format, sql.trim_start())),
+            Err(_) => Some((ExplainFormat::Tree, rest)),
+        },
+        None => Some((ExplainFormat::Tree, rest)),
+    }
+}
+
+pub fn explain(
+    sql: &str,
+    format: ExplainFormat,
+    config_path: &String,
+) -> Result<(), OrchestrationError> {
+    use std::println as info;
+    let dozer = init_dozer(config_path.to_owned())?;
+    match dozer.explain(Some(sql.to_string()), None, format) {
+        Ok(explained) => info!("{explained}"),
+        Err(e) => error!("{}", e),
+    }
+    Ok(())
+}
+
 pub fn query(
     sql: String,
     config_path: &String,
@@ -193,6 +193,7 @@ fn test_source(connection: Connection) -> Source {
         columns: vec!["id".to_owned(), "email".to_owned(), "phone".to_owned()],
         connection: Some(connection),
         refresh_config: Some(RefreshConfig::default()),
+        history_type: None,
         ..Default::default()
     }
 }
@@ -1,4 +1,5 @@
 use clap::{Args, Parser, Subcommand};
+use dozer_core::dag::explain::ExplainFormat;
 
 use super::helper::{DESCRIPTION, LOGO};
 
@@ -39,6 +40,8 @@ pub enum Commands {
     Connector(Connector),
     #[command(about = "Initalize an app using a template.")]
     Init,
+    #[command(about = "Show the DAG generated for the SQL of the endpoints")]
+    Explain(Explain),
 }
 
 #[derive(Debug, Args)]
@@ -55,6 +58,23 @@ pub struct Migrate {
     pub force: Option<Option<String>>,
 }
 
+#[derive(Debug, Args)]
+pub struct Explain {
+    #[arg(
+        short,
+        long,
+        help = "Endpoint to explain. All the endpoints defined by SQL if omitted"
+    )]
+    pub endpoint: Option<String>,
+    #[arg(
+        short,
+        long,
+        default_value = "tree",
+        help = "Output format: tree, dot or mermaid"
+    )]
+    pub format: ExplainFormat,
+}
+
 #[derive(Debug, Args)]
 #[command(args_conflicts_with_subcommands = true)]
 pub struct App {
@@ -48,6 +48,8 @@ pub enum OrchestrationError {
     SourceValidationError,
     #[error("Pipeline validation failed")]
     PipelineValidationError,
+    #[error("No endpoint defined by SQL is named {0}")]
+    SqlEndpointNotFound(String),
 }
 
 #[derive(Error, Debug)]
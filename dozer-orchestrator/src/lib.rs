@@ -4,10 +4,16 @@ pub mod pipeline;
 pub mod simple;
 pub use dozer_api::grpc::internal_grpc;
 pub use dozer_api::grpc::internal_grpc::internal_pipeline_service_client;
+use dozer_core::dag::app::AppPipeline;
 use dozer_core::dag::errors::ExecutionError;
+use dozer_core::dag::explain::ExplainFormat;
+use dozer_sql::pipeline::builder::{shared_statement_to_pipeline, QueryContext};
+use dozer_sql::pipeline::errors::PipelineError;
+pub use dozer_sql::pipeline::{register_udf, ScalarUdf};
 use dozer_types::{
     crossbeam::channel::Sender,
     log::debug,
+    models::sql_dialect::SqlDialect,
     types::{Operation, SchemaWithChangesType},
 };
 use errors::OrchestrationError;
@@ -44,6 +50,12 @@ pub trait Orchestrator {
         sender: Sender<Operation>,
         running: Arc<AtomicBool>,
     ) -> Result<Schema, OrchestrationError>;
+    fn explain(
+        &self,
+        sql: Option<String>,
+        endpoint: Option<String>,
+        format: ExplainFormat,
+    ) -> Result<String, OrchestrationError>;
 }
 
 // Re-exports
@@ -75,6 +87,18 @@ pub fn validate_schema(
     get_connector(input)?.validate_schemas(tables)
 }
 
+/// Checks the syntax of `sql` and the parts of it that don't depend on the schemas of the
+/// sources. Errors are located in `sql`.
+pub fn validate_sql(sql: &str, dialect: SqlDialect) -> Result<(), PipelineError> {
+    shared_statement_to_pipeline(
+        sql,
+        dialect,
+        &mut AppPipeline::new(),
+        &mut QueryContext::default(),
+    )
+    .map(|_| ())
+}
+
 pub fn set_panic_hook() {
     panic::set_hook(Box::new(move |panic_info| {
         // All the orchestrator errors are captured here
@@ -82,6 +82,12 @@ fn run() -> Result<(), OrchestrationError> {
             }
             Commands::Configure => configure(cli.config_path, running),
             Commands::Init => init_simple_config_file_with_question(),
+            Commands::Explain(explain) => {
+                let dozer = init_dozer(cli.config_path)?;
+                let explained = dozer.explain(None, explain.endpoint, explain.format)?;
+                println!("{explained}");
+                Ok(())
+            }
         }
     } else {
         render_logo();
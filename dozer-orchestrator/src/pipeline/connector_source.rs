@@ -1,22 +1,39 @@
+use crate::pipeline::history::{ForwardedLog, SourceHistory};
 use dozer_core::dag::channels::SourceChannelForwarder;
 use dozer_core::dag::errors::ExecutionError::ReplicationTypeNotFound;
 use dozer_core::dag::errors::{ExecutionError, SourceError};
 use dozer_core::dag::node::{OutputPortDef, OutputPortType, PortHandle, Source, SourceFactory};
+use dozer_core::storage::lmdb_storage::{LmdbEnvironmentManager, SharedTransaction};
 use dozer_ingestion::connectors::{get_connector, TableInfo};
 use dozer_ingestion::errors::ConnectorError;
 use dozer_ingestion::ingestion::{IngestionIterator, Ingestor};
 use dozer_sql::pipeline::builder::SchemaSQLContext;
+use dozer_types::chrono::{DateTime, FixedOffset, Utc};
+use dozer_types::crossbeam::channel::RecvTimeoutError;
 use dozer_types::ingestion_types::IngestionOperation;
 use dozer_types::log::info;
 use dozer_types::models::connection::Connection;
+use dozer_types::models::source::HistoryType;
 use dozer_types::parking_lot::RwLock;
 use dozer_types::types::{
     Operation, ReplicationChangesTrackingType, Schema, SchemaIdentifier, SourceDefinition,
 };
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
+
+/// How often the records kept for a retention period are checked for expiry when the
+/// source does not receive any operation
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The histories of the tables may forward several operations for an ingested operation, and
+/// expire records between ingested operations. The sources with histories number each
+/// forwarded operation within the ingested operation preceding it, in the lower bits of
+/// `seq_in_tx`, so that their checkpoints still identify the ingested operations.
+const HISTORY_SEQ_BITS: u32 = 32;
 
 #[derive(Debug)]
 pub struct ConnectorSourceFactory {
@@ -29,6 +46,9 @@ pub struct ConnectorSourceFactory {
     pub tables: Vec<TableInfo>,
     pub connection: Connection,
     pub running: Arc<AtomicBool>,
+    pub history_types: HashMap<u16, HistoryType>,
+    /// Directory of the database keeping the state of the histories of the tables
+    pub pipeline_dir: PathBuf,
 }
 
 fn map_replication_type_to_output_port_type(
@@ -57,6 +77,8 @@ impl ConnectorSourceFactory {
         tables: Vec<TableInfo>,
         connection: Connection,
         running: Arc<AtomicBool>,
+        history_types: HashMap<u16, HistoryType>,
+        pipeline_dir: PathBuf,
     ) -> Self {
         let (schema_map, schema_port_map, replication_changes_type_map) =
             Self::get_schema_map(connection.clone(), tables.clone(), ports.clone());
@@ -70,6 +92,8 @@ impl ConnectorSourceFactory {
             tables,
             connection,
             running,
+            history_types,
+            pipeline_dir,
         }
     }
 
@@ -110,6 +134,13 @@ impl ConnectorSourceFactory {
 
         (schema_map, schema_port_map, replication_changes_type_map)
     }
+
+    fn get_history(&self, port: &PortHandle) -> Result<Option<SourceHistory>, ExecutionError> {
+        match (self.history_types.get(port), self.schema_map.get(port)) {
+            (Some(history_type), Some(schema)) => SourceHistory::new(history_type, schema),
+            _ => Ok(None),
+        }
+    }
 }
 
 impl SourceFactory<SchemaSQLContext> for ConnectorSourceFactory {
@@ -123,6 +154,9 @@ impl SourceFactory<SchemaSQLContext> for ConnectorSourceFactory {
             .map_or(Err(ExecutionError::PortNotFoundInSource(*port)), |s| {
                 Ok(s.clone())
             })?;
+        if let Some(history) = self.get_history(port)? {
+            schema = history.get_output_schema(schema);
+        }
 
         let table_name = self
             .ports
@@ -184,6 +218,29 @@ impl SourceFactory<SchemaSQLContext> for ConnectorSourceFactory {
         &self,
         _output_schemas: HashMap<PortHandle, Schema>,
     ) -> Result<Box<dyn Source>, ExecutionError> {
+        let mut histories = HashMap::new();
+        for port in self.ports.values() {
+            if let Some(history) = self.get_history(port)? {
+                histories.insert(*port, history);
+            }
+        }
+
+        let history_state = if histories.is_empty() {
+            None
+        } else {
+            let mut env = LmdbEnvironmentManager::create(
+                &self.pipeline_dir,
+                &format!("{}_history", self.connection.name),
+            )?;
+            for (name, port) in &self.ports {
+                if let Some(history) = histories.get_mut(port) {
+                    history.init(&mut env, name)?;
+                }
+            }
+            let log = ForwardedLog::new(&mut env)?;
+            Some((env.create_txn()?, log))
+        };
+
         Ok(Box::new(ConnectorSource {
             ingestor: self.ingestor.clone(),
             iterator: self.iterator.clone(),
@@ -191,6 +248,8 @@ impl SourceFactory<SchemaSQLContext> for ConnectorSourceFactory {
             tables: self.tables.clone(),
             connection: self.connection.clone(),
             running: self.running.clone(),
+            histories,
+            history_state,
         }))
     }
 }
@@ -203,6 +262,10 @@ pub struct ConnectorSource {
     tables: Vec<TableInfo>,
     connection: Connection,
     running: Arc<AtomicBool>,
+    histories: HashMap<u16, SourceHistory>,
+    /// Transaction on the state of the histories, committed after each ingested operation
+    /// and each expiry check, and the log of the operations they forwarded
+    history_state: Option<(SharedTransaction, ForwardedLog)>,
 }
 
 impl Source for ConnectorSource {
@@ -214,11 +277,17 @@ impl Source for ConnectorSource {
         let mut connector = get_connector(self.connection.to_owned())
             .map_err(|e| ExecutionError::ConnectorError(Box::new(e)))?;
 
+        let has_histories = !self.histories.is_empty();
+        let connector_seq = match from_seq {
+            Some((lsn, seq_in_tx)) if has_histories => Some((lsn, seq_in_tx >> HISTORY_SEQ_BITS)),
+            from_seq => from_seq,
+        };
+
         let ingestor = self.ingestor.clone();
         let tables = self.tables.clone();
         let con_fn = move || -> Result<(), ConnectorError> {
             connector.initialize(ingestor, Some(tables))?;
-            connector.start(from_seq)?;
+            connector.start(connector_seq)?;
             Ok(())
         };
         let running = self.running.clone();
@@ -230,8 +299,60 @@ impl Source for ConnectorSource {
             }
         });
 
+        let mut histories = self.histories.clone();
+        // Sequence number of the next operation forwarded by the histories
+        let mut next_seq = from_seq.map_or((0, 0), |(lsn, seq_in_tx)| (lsn, seq_in_tx + 1));
+        // The operations of the connector up to this sequence number are already applied to
+        // the histories, and the operations they forwarded are forwarded again from the log
+        let mut applied_seq = None;
+        if has_histories {
+            let (txn, log) = self.history_state()?;
+            let replay = {
+                let mut txn = txn.write();
+                let replay = log.replay(&mut txn, from_seq)?;
+                txn.commit_and_renew()?;
+                replay
+            };
+            for ((lsn, seq_in_tx), port, op) in replay.ops {
+                fw.send(lsn, seq_in_tx, op, port)?;
+            }
+            applied_seq = replay.last_seq;
+            if let Some(seq) = replay.next_seq {
+                next_seq = next_seq.max(seq);
+            }
+        }
         loop {
-            let msg = self.iterator.write().next();
+            let msg = if !has_histories {
+                self.iterator.write().next()
+            } else {
+                let received = self.iterator.write().rx.recv_timeout(EXPIRY_CHECK_INTERVAL);
+                match received {
+                    Ok(msg) => Some(msg),
+                    Err(RecvTimeoutError::Timeout) => {
+                        let now: DateTime<FixedOffset> = Utc::now().into();
+                        let (txn, log) = self.history_state()?;
+                        let mut ops = vec![];
+                        {
+                            let mut txn = txn.write();
+                            for (port, history) in histories.iter_mut() {
+                                for op in history.expire(&mut txn, now)? {
+                                    ops.push((*port, op));
+                                }
+                            }
+                            if !ops.is_empty() {
+                                log.append(&mut txn, next_seq, &ops)?;
+                            }
+                            txn.commit_and_renew()?;
+                        }
+                        for (port, op) in ops {
+                            fw.send(next_seq.0, next_seq.1, op, port)?;
+                            next_seq.1 += 1;
+                        }
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => None,
+                }
+            };
             if let Some(msg) = msg {
                 match msg {
                     ((lsn, seq_no), IngestionOperation::OperationEvent(op)) => {
@@ -247,7 +368,35 @@ impl Source for ConnectorSource {
                             ))),
                             Ok,
                         )?;
-                        fw.send(lsn, seq_no, op.operation.to_owned(), port.to_owned())?
+                        if !has_histories {
+                            fw.send(lsn, seq_no, op.operation, port.to_owned())?;
+                            continue;
+                        }
+
+                        let seq = (lsn, history_seq(seq_no)?);
+                        if matches!(applied_seq, Some(applied_seq) if seq <= applied_seq) {
+                            continue;
+                        }
+                        next_seq = seq;
+                        let (txn, log) = self.history_state()?;
+                        let ops = {
+                            let mut txn = txn.write();
+                            let ops = match histories.get_mut(port) {
+                                Some(history) => {
+                                    let now: DateTime<FixedOffset> = Utc::now().into();
+                                    history.process(&mut txn, op.operation, now)?
+                                }
+                                None => vec![op.operation],
+                            };
+                            let ops: Vec<_> = ops.into_iter().map(|op| (*port, op)).collect();
+                            log.append(&mut txn, next_seq, &ops)?;
+                            txn.commit_and_renew()?;
+                            ops
+                        };
+                        for (port, op) in ops {
+                            fw.send(next_seq.0, next_seq.1, op, port)?;
+                            next_seq.1 += 1;
+                        }
                     }
                 }
             } else {
@@ -261,6 +410,26 @@ impl Source for ConnectorSource {
     }
 }
 
+impl ConnectorSource {
+    fn history_state(&self) -> Result<&(SharedTransaction, ForwardedLog), ExecutionError> {
+        self.history_state
+            .as_ref()
+            .ok_or(ExecutionError::InvalidDatabase)
+    }
+}
+
+/// Sequence number in the transaction of the first operation forwarded by the histories for
+/// the ingested operation `seq_no`
+fn history_seq(seq_no: u64) -> Result<u64, ExecutionError> {
+    let out_of_range = || ExecutionError::SourceError(SourceError::SeqOutOfRange(seq_no));
+    if seq_no.leading_zeros() < HISTORY_SEQ_BITS {
+        return Err(out_of_range());
+    }
+    seq_no
+        .checked_shl(HISTORY_SEQ_BITS)
+        .ok_or_else(out_of_range)
+}
+
 fn get_schema_id(op_schema_id: Option<&SchemaIdentifier>) -> Result<u32, ExecutionError> {
     Ok(op_schema_id
         .map_or(Err(ExecutionError::SchemaNotInitialized), Ok)?
@@ -0,0 +1,1087 @@
+use dozer_core::dag::errors::{ExecutionError, SourceError};
+use dozer_core::dag::node::PortHandle;
+use dozer_core::storage::common::{Database, Seek};
+use dozer_core::storage::errors::StorageError;
+use dozer_core::storage::lmdb_storage::{LmdbEnvironmentManager, LmdbExclusiveTransaction};
+use dozer_types::bincode;
+use dozer_types::chrono::{DateTime, Duration, FixedOffset, TimeZone, Utc};
+use dozer_types::errors::types::{DeserializationError, SerializationError, TypeError};
+use dozer_types::models::source::{
+    AppendOnlyConfig, HistoryType, MasterHistoryConfig, MasterHistoryPolicy, RetainPartialConfig,
+    TransactionalHistoryConfig, TransactionalHistoryPolicy,
+};
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+
+/// Keeps the history of the records of a source table, as configured by its `history_type`
+#[derive(Debug, Clone)]
+pub enum SourceHistory {
+    AppendOnly(AppendOnlyHistory),
+    RetainPartial(RetainPartialHistory),
+}
+
+impl SourceHistory {
+    /// Returns `None` if the history type does not change the records of the source
+    pub fn new(
+        history_type: &HistoryType,
+        schema: &Schema,
+    ) -> Result<Option<Self>, ExecutionError> {
+        Ok(match history_type {
+            HistoryType::Master(MasterHistoryConfig {
+                policy: Some(MasterHistoryPolicy::AppendOnly(config)),
+            }) => Some(SourceHistory::AppendOnly(AppendOnlyHistory::new(
+                config, schema,
+            )?)),
+            HistoryType::Transactional(TransactionalHistoryConfig {
+                policy: Some(TransactionalHistoryPolicy::RetainPartial(config)),
+            }) => Some(SourceHistory::RetainPartial(RetainPartialHistory::new(
+                config, schema,
+            )?)),
+            HistoryType::Master(_) | HistoryType::Transactional(_) => None,
+        })
+    }
+
+    pub fn get_output_schema(&self, schema: Schema) -> Schema {
+        match self {
+            SourceHistory::AppendOnly(history) => history.get_output_schema(schema),
+            SourceHistory::RetainPartial(_) => schema,
+        }
+    }
+
+    /// Opens the databases keeping the state of the history of the source table `name`
+    pub fn init(
+        &mut self,
+        env: &mut LmdbEnvironmentManager,
+        name: &str,
+    ) -> Result<(), ExecutionError> {
+        match self {
+            SourceHistory::AppendOnly(history) => history.init(env, name),
+            SourceHistory::RetainPartial(history) => history.init(env, name),
+        }
+    }
+
+    /// Turns an operation of the source table into the operations to forward, `now` being
+    /// the time the operation is received at
+    pub fn process(
+        &mut self,
+        txn: &mut LmdbExclusiveTransaction,
+        op: Operation,
+        now: DateTime<FixedOffset>,
+    ) -> Result<Vec<Operation>, ExecutionError> {
+        match self {
+            SourceHistory::AppendOnly(history) => history.process(txn, op, now),
+            SourceHistory::RetainPartial(history) => history.process(txn, op, now),
+        }
+    }
+
+    /// Operations retracting the records expired at `now`, to forward even if the source
+    /// table does not change
+    pub fn expire(
+        &mut self,
+        txn: &mut LmdbExclusiveTransaction,
+        now: DateTime<FixedOffset>,
+    ) -> Result<Vec<Operation>, ExecutionError> {
+        match self {
+            SourceHistory::AppendOnly(_) => Ok(vec![]),
+            SourceHistory::RetainPartial(history) => history.expire(txn, now),
+        }
+    }
+}
+
+/// Slowly changing dimension of type 2: every version of a record is kept, with the dates it
+/// was opened and closed at. Updates close the current version and open a new one, deletes
+/// close the current version.
+#[derive(Debug, Clone)]
+pub struct AppendOnlyHistory {
+    unique_key_index: usize,
+    open_date_field: String,
+    closed_date_field: String,
+    /// Database storing the current version of each record, as forwarded, by unique key
+    db: Option<Database>,
+}
+
+impl AppendOnlyHistory {
+    pub fn new(config: &AppendOnlyConfig, schema: &Schema) -> Result<Self, ExecutionError> {
+        let (unique_key_index, _) = schema.get_field_index(&config.unique_key_field)?;
+        for name in [&config.open_date_field, &config.closed_date_field] {
+            if schema.get_field_index(name).is_ok() {
+                return Err(ExecutionError::SourceError(
+                    SourceError::HistoryFieldExists(name.to_string()),
+                ));
+            }
+        }
+
+        Ok(Self {
+            unique_key_index,
+            open_date_field: config.open_date_field.clone(),
+            closed_date_field: config.closed_date_field.clone(),
+            db: None,
+        })
+    }
+
+    fn init(&mut self, env: &mut LmdbEnvironmentManager, name: &str) -> Result<(), ExecutionError> {
+        self.db = Some(env.open_database(&format!("{name}_versions"), false)?);
+        Ok(())
+    }
+
+    /// Appends the open and closed dates to the fields of the source. A version is
+    /// identified by its unique key and its open date.
+    pub fn get_output_schema(&self, mut schema: Schema) -> Schema {
+        schema.primary_index = vec![self.unique_key_index];
+        schema
+            .field(
+                FieldDefinition::new(
+                    self.open_date_field.clone(),
+                    FieldType::Timestamp,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                true,
+            )
+            .field(
+                FieldDefinition::new(
+                    self.closed_date_field.clone(),
+                    FieldType::Timestamp,
+                    true,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            );
+        schema
+    }
+
+    pub fn process(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        op: Operation,
+        now: DateTime<FixedOffset>,
+    ) -> Result<Vec<Operation>, ExecutionError> {
+        let db = self.db.ok_or(ExecutionError::InvalidDatabase)?;
+        let mut ops = vec![];
+        match op {
+            Operation::Insert { new } => {
+                let now = self.close(txn, db, &new, now, &mut ops)?;
+                self.open(txn, db, new, now, &mut ops)?;
+            }
+            Operation::Update { old, new } => {
+                let now = self.close(txn, db, &old, now, &mut ops)?;
+                // The unique key itself may have been updated
+                let now = self.close(txn, db, &new, now, &mut ops)?;
+                self.open(txn, db, new, now, &mut ops)?;
+            }
+            Operation::Delete { old } => {
+                self.close(txn, db, &old, now, &mut ops)?;
+            }
+        }
+        Ok(ops)
+    }
+
+    /// Closes the current version of the record with the unique key of `record`, if any.
+    /// The version is retracted as it was forwarded, which may differ from `record`. Returns
+    /// the date the next version of the record can be opened at, which is after the open
+    /// date of the closed version.
+    fn close(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        db: Database,
+        record: &Record,
+        now: DateTime<FixedOffset>,
+        ops: &mut Vec<Operation>,
+    ) -> Result<DateTime<FixedOffset>, ExecutionError> {
+        let key = self.unique_key(record);
+        let Some(old) = txn.get(db, &key)?.map(decode_record).transpose()? else {
+            return Ok(now);
+        };
+        txn.del(db, &key, None)?;
+        // The open and closed dates are the last fields of a version
+        let open_date = match old.values.iter().rev().nth(1) {
+            Some(Field::Timestamp(open_date)) => *open_date,
+            _ => return Err(StorageError::InvalidRecord.into()),
+        };
+        // Timestamps are stored with a millisecond precision
+        let now = now.max(open_date + Duration::milliseconds(1));
+
+        let mut new = old.clone();
+        if let Some(closed_date) = new.values.last_mut() {
+            *closed_date = Field::Timestamp(now);
+        }
+        ops.push(Operation::Update { old, new });
+        Ok(now)
+    }
+
+    fn open(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        db: Database,
+        mut record: Record,
+        now: DateTime<FixedOffset>,
+        ops: &mut Vec<Operation>,
+    ) -> Result<(), ExecutionError> {
+        record.values.extend([Field::Timestamp(now), Field::Null]);
+        let version = bincode::serialize(&record)
+            .map_err(|e| TypeError::SerializationError(SerializationError::Bincode(e)))?;
+        txn.put(db, &self.unique_key(&record), &version)?;
+        ops.push(Operation::Insert { new: record });
+        Ok(())
+    }
+
+    fn unique_key(&self, record: &Record) -> Vec<u8> {
+        record.get_key(&vec![self.unique_key_index])
+    }
+}
+
+fn decode_record(value: &[u8]) -> Result<Record, TypeError> {
+    bincode::deserialize(value)
+        .map_err(|e| TypeError::DeserializationError(DeserializationError::Bincode(e)))
+}
+
+/// Only the records whose timestamp is within the retention period are kept. The older ones
+/// are deleted downstream once they expire, and ignored if they are already expired when
+/// received. Records without a timestamp never expire.
+#[derive(Debug, Clone)]
+pub struct RetainPartialHistory {
+    timestamp_index: usize,
+    primary_index: Vec<usize>,
+    retention_period: Duration,
+    /// Database storing the records forwarded and not deleted yet, by primary key
+    records_db: Option<Database>,
+    /// Database indexing the records by timestamp then primary key, in the order they expire
+    expiry_db: Option<Database>,
+}
+
+impl RetainPartialHistory {
+    pub fn new(config: &RetainPartialConfig, schema: &Schema) -> Result<Self, ExecutionError> {
+        let (timestamp_index, field) = schema.get_field_index(&config.timestamp_field)?;
+        if !matches!(field.typ, FieldType::Timestamp | FieldType::Date) {
+            return Err(ExecutionError::SourceError(
+                SourceError::InvalidTimestampField(config.timestamp_field.clone()),
+            ));
+        }
+
+        // Records without primary key are identified by all their values
+        let primary_index = if schema.primary_index.is_empty() {
+            (0..schema.fields.len()).collect()
+        } else {
+            schema.primary_index.clone()
+        };
+
+        Ok(Self {
+            timestamp_index,
+            primary_index,
+            retention_period: Duration::seconds(config.retention_period as i64),
+            records_db: None,
+            expiry_db: None,
+        })
+    }
+
+    fn init(&mut self, env: &mut LmdbEnvironmentManager, name: &str) -> Result<(), ExecutionError> {
+        self.records_db = Some(env.open_database(&format!("{name}_records"), false)?);
+        self.expiry_db = Some(env.open_database(&format!("{name}_expiry"), false)?);
+        Ok(())
+    }
+
+    pub fn process(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        op: Operation,
+        now: DateTime<FixedOffset>,
+    ) -> Result<Vec<Operation>, ExecutionError> {
+        let mut ops = vec![];
+        match op {
+            Operation::Insert { new } => {
+                if let Some(new) = self.retain(txn, new, now)? {
+                    ops.push(Operation::Insert { new });
+                }
+            }
+            Operation::Update { old, new } => {
+                // Updates move records in and out of the retention period
+                let old = self.forget(txn, &old)?;
+                match (old, self.retain(txn, new, now)?) {
+                    (Some(old), Some(new)) => ops.push(Operation::Update { old, new }),
+                    (Some(old), None) => ops.push(Operation::Delete { old }),
+                    (None, Some(new)) => ops.push(Operation::Insert { new }),
+                    (None, None) => (),
+                }
+            }
+            Operation::Delete { old } => {
+                if let Some(old) = self.forget(txn, &old)? {
+                    ops.push(Operation::Delete { old });
+                }
+            }
+        }
+        ops.extend(self.expire(txn, now)?);
+        Ok(ops)
+    }
+
+    pub fn expire(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        now: DateTime<FixedOffset>,
+    ) -> Result<Vec<Operation>, ExecutionError> {
+        let (records_db, expiry_db) = self.databases()?;
+        let mut ops = vec![];
+        loop {
+            let expiry_key = {
+                let cursor = txn.open_ro_cursor(expiry_db)?;
+                if !cursor.first()? {
+                    break;
+                }
+                let (key, _) = cursor.read()?.ok_or(StorageError::InvalidRecord)?;
+                key.to_vec()
+            };
+            // The key is made of the timestamp, on 8 bytes, and the primary key
+            let key = &expiry_key[8..];
+            if let Some(old) = self.get_record(txn, records_db, key)? {
+                if matches!(self.timestamp(&old), Some(timestamp) if !self.is_expired(timestamp, now))
+                {
+                    break;
+                }
+                txn.del(records_db, key, None)?;
+                ops.push(Operation::Delete { old });
+            }
+            txn.del(expiry_db, &expiry_key, None)?;
+        }
+        Ok(ops)
+    }
+
+    /// Keeps track of `record` and returns it, unless it is already expired
+    fn retain(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        record: Record,
+        now: DateTime<FixedOffset>,
+    ) -> Result<Option<Record>, ExecutionError> {
+        let timestamp = self.timestamp(&record);
+        if matches!(timestamp, Some(timestamp) if self.is_expired(timestamp, now)) {
+            return Ok(None);
+        }
+
+        self.forget(txn, &record)?;
+        let (records_db, expiry_db) = self.databases()?;
+        let key = record.get_key(&self.primary_index);
+        if let Some(timestamp) = timestamp {
+            txn.put(expiry_db, &expiry_key(timestamp, &key), &[])?;
+        }
+        let value = bincode::serialize(&record)
+            .map_err(|e| TypeError::SerializationError(SerializationError::Bincode(e)))?;
+        txn.put(records_db, &key, &value)?;
+        Ok(Some(record))
+    }
+
+    /// Stops tracking the record with the same primary key as `record`. Returns the version
+    /// of the record that was forwarded, if it was not deleted already.
+    fn forget(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        record: &Record,
+    ) -> Result<Option<Record>, ExecutionError> {
+        let (records_db, expiry_db) = self.databases()?;
+        let key = record.get_key(&self.primary_index);
+        let Some(old) = self.get_record(txn, records_db, &key)? else {
+            return Ok(None);
+        };
+        txn.del(records_db, &key, None)?;
+        if let Some(timestamp) = self.timestamp(&old) {
+            txn.del(expiry_db, &expiry_key(timestamp, &key), None)?;
+        }
+        Ok(Some(old))
+    }
+
+    fn get_record(
+        &self,
+        txn: &LmdbExclusiveTransaction,
+        records_db: Database,
+        key: &[u8],
+    ) -> Result<Option<Record>, ExecutionError> {
+        Ok(txn.get(records_db, key)?.map(decode_record).transpose()?)
+    }
+
+    fn databases(&self) -> Result<(Database, Database), ExecutionError> {
+        match (self.records_db, self.expiry_db) {
+            (Some(records_db), Some(expiry_db)) => Ok((records_db, expiry_db)),
+            _ => Err(ExecutionError::InvalidDatabase),
+        }
+    }
+
+    fn timestamp(&self, record: &Record) -> Option<DateTime<FixedOffset>> {
+        match record.values.get(self.timestamp_index)? {
+            Field::Timestamp(timestamp) => Some(*timestamp),
+            Field::Date(date) => Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?).into()),
+            _ => None,
+        }
+    }
+
+    fn is_expired(&self, timestamp: DateTime<FixedOffset>, now: DateTime<FixedOffset>) -> bool {
+        timestamp + self.retention_period < now
+    }
+}
+
+/// Returns the key of a record in the expiry index. Timestamps are encoded in milliseconds,
+/// with the sign bit flipped so that the keys sort by timestamp.
+fn expiry_key(timestamp: DateTime<FixedOffset>, key: &[u8]) -> Vec<u8> {
+    let millis = timestamp.timestamp_millis() as u64 ^ (1 << 63);
+    let mut expiry_key = millis.to_be_bytes().to_vec();
+    expiry_key.extend(key);
+    expiry_key
+}
+
+/// Operations forwarded by the histories of a source, by sequence number of the first of them.
+/// The state of the histories is committed after each operation, independently of the
+/// checkpoints of the pipeline. After a restart, the operations replayed by the connector are
+/// not applied to the histories again: the logged operations forwarded after the checkpoint
+/// are forwarded again instead.
+#[derive(Debug, Clone)]
+pub struct ForwardedLog {
+    db: Database,
+}
+
+/// Logged operations to forward again when the source starts
+#[derive(Debug, Default)]
+pub struct Replay {
+    /// Operations forwarded after the checkpoint, with their sequence numbers
+    pub ops: Vec<((u64, u64), PortHandle, Operation)>,
+    /// Sequence number of the last logged operations. The operations of the connector up to
+    /// it are already applied to the histories.
+    pub last_seq: Option<(u64, u64)>,
+    /// Sequence number following the last forwarded operation
+    pub next_seq: Option<(u64, u64)>,
+}
+
+impl ForwardedLog {
+    pub fn new(env: &mut LmdbEnvironmentManager) -> Result<Self, ExecutionError> {
+        Ok(Self {
+            db: env.open_database("forwarded_log", false)?,
+        })
+    }
+
+    /// Logs the operations forwarded from `seq`, in the transaction the histories are updated
+    /// in. Operations of the connector that forward nothing are logged too, so that they are
+    /// not applied again.
+    pub fn append(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        seq: (u64, u64),
+        ops: &[(PortHandle, Operation)],
+    ) -> Result<(), ExecutionError> {
+        let value = bincode::serialize(ops)
+            .map_err(|e| TypeError::SerializationError(SerializationError::Bincode(e)))?;
+        txn.put(self.db, &log_key(seq), &value)?;
+        Ok(())
+    }
+
+    /// Returns the operations forwarded after the checkpoint `from_seq`, and removes the ones
+    /// before it. The last logged operations are kept, to know where the histories are at.
+    pub fn replay(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        from_seq: Option<(u64, u64)>,
+    ) -> Result<Replay, ExecutionError> {
+        let mut replay = Replay::default();
+        let mut checkpointed = vec![];
+        {
+            let cursor = txn.open_ro_cursor(self.db)?;
+            let mut exists = cursor.first()?;
+            while exists {
+                let (key, value) = cursor.read()?.ok_or(StorageError::InvalidRecord)?;
+                let first_seq = decode_log_key(key)?;
+                let ops: Vec<(PortHandle, Operation)> =
+                    bincode::deserialize(value).map_err(|e| {
+                        TypeError::DeserializationError(DeserializationError::Bincode(e))
+                    })?;
+                let mut seq = first_seq;
+                let mut forwarded_after_checkpoint = false;
+                for (port, op) in ops {
+                    if !matches!(from_seq, Some(from_seq) if seq <= from_seq) {
+                        replay.ops.push((seq, port, op));
+                        forwarded_after_checkpoint = true;
+                    }
+                    seq.1 += 1;
+                }
+                if !forwarded_after_checkpoint {
+                    checkpointed.push(key.to_vec());
+                }
+                replay.last_seq = Some(first_seq);
+                replay.next_seq = Some(seq);
+                exists = cursor.next()?;
+            }
+        }
+        if let Some(last_seq) = replay.last_seq {
+            checkpointed.retain(|key| *key != log_key(last_seq));
+        }
+        for key in checkpointed {
+            txn.del(self.db, &key, None)?;
+        }
+        Ok(replay)
+    }
+}
+
+fn log_key((lsn, seq_in_tx): (u64, u64)) -> Vec<u8> {
+    let mut key = lsn.to_be_bytes().to_vec();
+    key.extend(seq_in_tx.to_be_bytes());
+    key
+}
+
+fn decode_log_key(key: &[u8]) -> Result<(u64, u64), StorageError> {
+    match (key.get(..8), key.get(8..)) {
+        (Some(lsn), Some(seq_in_tx)) => Ok((
+            u64::from_be_bytes(lsn.try_into().map_err(|_| StorageError::InvalidRecord)?),
+            u64::from_be_bytes(
+                seq_in_tx
+                    .try_into()
+                    .map_err(|_| StorageError::InvalidRecord)?,
+            ),
+        )),
+        _ => Err(StorageError::InvalidRecord),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pipeline::history::{ForwardedLog, SourceHistory};
+    use dozer_core::storage::lmdb_storage::{LmdbEnvironmentManager, SharedTransaction};
+    use dozer_types::chrono::{DateTime, Duration, FixedOffset};
+    use dozer_types::models::source::{
+        AppendOnlyConfig, HistoryType, MasterHistoryConfig, MasterHistoryPolicy, OverwriteConfig,
+        RetainPartialConfig, TransactionalHistoryConfig, TransactionalHistoryPolicy,
+    };
+    use dozer_types::types::{
+        Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+    };
+    use tempdir::TempDir;
+
+    fn get_schema() -> Schema {
+        Schema::empty()
+            .field(
+                FieldDefinition::new(
+                    "id".to_string(),
+                    FieldType::Int,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                true,
+            )
+            .field(
+                FieldDefinition::new(
+                    "name".to_string(),
+                    FieldType::String,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .clone()
+    }
+
+    fn append_only(open_date_field: &str) -> HistoryType {
+        HistoryType::Master(MasterHistoryConfig {
+            policy: Some(MasterHistoryPolicy::AppendOnly(AppendOnlyConfig {
+                unique_key_field: "id".to_string(),
+                open_date_field: open_date_field.to_string(),
+                closed_date_field: "valid_to".to_string(),
+            })),
+        })
+    }
+
+    fn retain_partial(timestamp_field: &str) -> HistoryType {
+        HistoryType::Transactional(TransactionalHistoryConfig {
+            policy: Some(TransactionalHistoryPolicy::RetainPartial(
+                RetainPartialConfig {
+                    timestamp_field: timestamp_field.to_string(),
+                    retention_period: 24 * 60 * 60,
+                },
+            )),
+        })
+    }
+
+    fn init_history(history: &mut SourceHistory, tmp_dir: &TempDir) -> SharedTransaction {
+        let mut env = LmdbEnvironmentManager::create(tmp_dir.path(), "history").unwrap();
+        history.init(&mut env, "users").unwrap();
+        env.create_txn().unwrap()
+    }
+
+    fn record(id: i64, name: &str, dates: &[Field]) -> Record {
+        let mut values = vec![Field::Int(id), Field::String(name.to_string())];
+        values.extend_from_slice(dates);
+        Record::new(None, values, None)
+    }
+
+    #[test]
+    fn test_append_only_history() {
+        let schema = get_schema();
+        let mut history = SourceHistory::new(&append_only("valid_from"), &schema)
+            .unwrap()
+            .unwrap();
+        let tmp_dir = TempDir::new("history").unwrap();
+        let txn = init_history(&mut history, &tmp_dir);
+
+        let output_schema = history.get_output_schema(schema);
+        assert_eq!(output_schema.fields[2].name, "valid_from");
+        assert_eq!(output_schema.fields[3].name, "valid_to");
+        assert!(output_schema.fields[3].nullable);
+        assert_eq!(output_schema.primary_index, vec![0, 2]);
+
+        let t1 = DateTime::<FixedOffset>::parse_from_rfc3339("2023-01-01T00:00:00Z").unwrap();
+        let t2 = t1 + Duration::days(1);
+        let open = |t| [Field::Timestamp(t), Field::Null];
+        let closed = |t, c| [Field::Timestamp(t), Field::Timestamp(c)];
+
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Insert {
+                    new: record(1, "a", &[]),
+                },
+                t1,
+            )
+            .unwrap();
+        assert_eq!(
+            ops,
+            vec![Operation::Insert {
+                new: record(1, "a", &open(t1)),
+            }]
+        );
+
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Update {
+                    old: record(1, "a", &[]),
+                    new: record(1, "b", &[]),
+                },
+                t2,
+            )
+            .unwrap();
+        assert_eq!(
+            ops,
+            vec![
+                Operation::Update {
+                    old: record(1, "a", &open(t1)),
+                    new: record(1, "a", &closed(t1, t2)),
+                },
+                Operation::Insert {
+                    new: record(1, "b", &open(t2)),
+                },
+            ]
+        );
+
+        // Versions opened in the same millisecond still get distinct keys
+        let t3 = t2 + Duration::milliseconds(1);
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Update {
+                    old: record(1, "b", &[]),
+                    new: record(1, "c", &[]),
+                },
+                t2,
+            )
+            .unwrap();
+        assert_eq!(
+            ops,
+            vec![
+                Operation::Update {
+                    old: record(1, "b", &open(t2)),
+                    new: record(1, "b", &closed(t2, t3)),
+                },
+                Operation::Insert {
+                    new: record(1, "c", &open(t3)),
+                },
+            ]
+        );
+
+        let t4 = t3 + Duration::days(1);
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Delete {
+                    old: record(1, "c", &[]),
+                },
+                t4,
+            )
+            .unwrap();
+        assert_eq!(
+            ops,
+            vec![Operation::Update {
+                old: record(1, "c", &open(t3)),
+                new: record(1, "c", &closed(t3, t4)),
+            }]
+        );
+
+        // Nothing is left to close
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Delete {
+                    old: record(1, "c", &[]),
+                },
+                t4,
+            )
+            .unwrap();
+        assert!(ops.is_empty());
+    }
+
+    #[test]
+    fn test_append_only_history_retractions() {
+        let schema = get_schema();
+        let mut history = SourceHistory::new(&append_only("valid_from"), &schema)
+            .unwrap()
+            .unwrap();
+        let tmp_dir = TempDir::new("history").unwrap();
+        let txn = init_history(&mut history, &tmp_dir);
+
+        let t1 = DateTime::<FixedOffset>::parse_from_rfc3339("2023-01-01T00:00:00Z").unwrap();
+        let t2 = t1 + Duration::days(1);
+        let open = |t| [Field::Timestamp(t), Field::Null];
+        let closed = |t, c| [Field::Timestamp(t), Field::Timestamp(c)];
+
+        for new in [record(1, "a", &[]), record(2, "b", &[])] {
+            history
+                .process(&mut txn.write(), Operation::Insert { new }, t1)
+                .unwrap();
+        }
+
+        // An insert on an open key retracts the version that was forwarded
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Insert {
+                    new: record(1, "c", &[]),
+                },
+                t2,
+            )
+            .unwrap();
+        assert_eq!(
+            ops,
+            vec![
+                Operation::Update {
+                    old: record(1, "a", &open(t1)),
+                    new: record(1, "a", &closed(t1, t2)),
+                },
+                Operation::Insert {
+                    new: record(1, "c", &open(t2)),
+                },
+            ]
+        );
+
+        // Updating the unique key closes the versions of both keys as they were forwarded
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Update {
+                    old: record(1, "c", &[]),
+                    new: record(2, "d", &[]),
+                },
+                t2,
+            )
+            .unwrap();
+        let t3 = t2 + Duration::milliseconds(1);
+        assert_eq!(
+            ops,
+            vec![
+                Operation::Update {
+                    old: record(1, "c", &open(t2)),
+                    new: record(1, "c", &closed(t2, t3)),
+                },
+                Operation::Update {
+                    old: record(2, "b", &open(t1)),
+                    new: record(2, "b", &closed(t1, t3)),
+                },
+                Operation::Insert {
+                    new: record(2, "d", &open(t3)),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_append_only_history_restart() {
+        let schema = get_schema();
+        let tmp_dir = TempDir::new("history").unwrap();
+        let t1 = DateTime::<FixedOffset>::parse_from_rfc3339("2023-01-01T00:00:00Z").unwrap();
+        let t2 = t1 + Duration::days(1);
+
+        let mut history = SourceHistory::new(&append_only("valid_from"), &schema)
+            .unwrap()
+            .unwrap();
+        let txn = init_history(&mut history, &tmp_dir);
+        history
+            .process(
+                &mut txn.write(),
+                Operation::Insert {
+                    new: record(1, "a", &[]),
+                },
+                t1,
+            )
+            .unwrap();
+        txn.write().commit_and_renew().unwrap();
+        drop(txn);
+
+        // The version opened before the restart is closed
+        let mut history = SourceHistory::new(&append_only("valid_from"), &schema)
+            .unwrap()
+            .unwrap();
+        let txn = init_history(&mut history, &tmp_dir);
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Delete {
+                    old: record(1, "a", &[]),
+                },
+                t2,
+            )
+            .unwrap();
+        assert_eq!(
+            ops,
+            vec![Operation::Update {
+                old: record(1, "a", &[Field::Timestamp(t1), Field::Null]),
+                new: record(1, "a", &[Field::Timestamp(t1), Field::Timestamp(t2)]),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_history_config_errors() {
+        let schema = get_schema();
+        assert!(SourceHistory::new(&append_only("name"), &schema).is_err());
+
+        let mut config = append_only("valid_from");
+        if let HistoryType::Master(MasterHistoryConfig {
+            policy: Some(MasterHistoryPolicy::AppendOnly(config)),
+        }) = &mut config
+        {
+            config.unique_key_field = "missing".to_string();
+        }
+        assert!(SourceHistory::new(&config, &schema).is_err());
+
+        let overwrite = HistoryType::Master(MasterHistoryConfig {
+            policy: Some(MasterHistoryPolicy::Overwrite(OverwriteConfig {})),
+        });
+        assert!(SourceHistory::new(&overwrite, &schema).unwrap().is_none());
+
+        assert!(SourceHistory::new(&retain_partial("name"), &schema).is_err());
+    }
+
+    #[test]
+    fn test_retain_partial_history() {
+        let mut schema = get_schema();
+        schema.field(
+            FieldDefinition::new(
+                "created_at".to_string(),
+                FieldType::Timestamp,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        );
+        let mut history = SourceHistory::new(&retain_partial("created_at"), &schema)
+            .unwrap()
+            .unwrap();
+        let tmp_dir = TempDir::new("history").unwrap();
+        let txn = init_history(&mut history, &tmp_dir);
+        assert_eq!(history.get_output_schema(schema.clone()), schema);
+
+        let now = DateTime::<FixedOffset>::parse_from_rfc3339("2023-01-10T00:00:00Z").unwrap();
+        let created = |age: Duration| [Field::Timestamp(now - age)];
+
+        // Already expired
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Insert {
+                    new: record(1, "a", &created(Duration::days(2))),
+                },
+                now,
+            )
+            .unwrap();
+        assert!(ops.is_empty());
+
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Insert {
+                    new: record(2, "b", &created(Duration::hours(12))),
+                },
+                now,
+            )
+            .unwrap();
+        assert_eq!(
+            ops,
+            vec![Operation::Insert {
+                new: record(2, "b", &created(Duration::hours(12))),
+            }]
+        );
+
+        // Never expires
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Insert {
+                    new: record(3, "c", &[Field::Null]),
+                },
+                now,
+            )
+            .unwrap();
+        assert_eq!(ops.len(), 1);
+
+        // Updates moving records out of and into the retention period
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Update {
+                    old: record(2, "b", &created(Duration::hours(12))),
+                    new: record(2, "b", &created(Duration::days(2))),
+                },
+                now,
+            )
+            .unwrap();
+        assert_eq!(
+            ops,
+            vec![Operation::Delete {
+                old: record(2, "b", &created(Duration::hours(12))),
+            }]
+        );
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Update {
+                    old: record(1, "a", &created(Duration::days(2))),
+                    new: record(1, "a", &created(Duration::zero())),
+                },
+                now,
+            )
+            .unwrap();
+        assert_eq!(
+            ops,
+            vec![Operation::Insert {
+                new: record(1, "a", &created(Duration::zero())),
+            }]
+        );
+
+        assert!(history
+            .expire(&mut txn.write(), now + Duration::days(1))
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            history
+                .expire(
+                    &mut txn.write(),
+                    now + Duration::days(1) + Duration::seconds(1)
+                )
+                .unwrap(),
+            vec![Operation::Delete {
+                old: record(1, "a", &created(Duration::zero())),
+            }]
+        );
+
+        // Deletes retract the version that was forwarded
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Delete {
+                    old: record(3, "", &[Field::Null]),
+                },
+                now,
+            )
+            .unwrap();
+        assert_eq!(
+            ops,
+            vec![Operation::Delete {
+                old: record(3, "c", &[Field::Null]),
+            }]
+        );
+        let ops = history
+            .process(
+                &mut txn.write(),
+                Operation::Delete {
+                    old: record(1, "a", &created(Duration::zero())),
+                },
+                now,
+            )
+            .unwrap();
+        assert!(ops.is_empty());
+    }
+    #[test]
+    fn test_retain_partial_history_restart() {
+        let mut schema = get_schema();
+        schema.field(
+            FieldDefinition::new(
+                "created_at".to_string(),
+                FieldType::Timestamp,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        );
+        let tmp_dir = TempDir::new("history").unwrap();
+        let now = DateTime::<FixedOffset>::parse_from_rfc3339("2023-01-10T00:00:00Z").unwrap();
+        let created = [Field::Timestamp(now)];
+
+        let mut history = SourceHistory::new(&retain_partial("created_at"), &schema)
+            .unwrap()
+            .unwrap();
+        let txn = init_history(&mut history, &tmp_dir);
+        history
+            .process(
+                &mut txn.write(),
+                Operation::Insert {
+                    new: record(1, "a", &created),
+                },
+                now,
+            )
+            .unwrap();
+        txn.write().commit_and_renew().unwrap();
+        drop(txn);
+
+        // The records forwarded before the restart still expire
+        let mut history = SourceHistory::new(&retain_partial("created_at"), &schema)
+            .unwrap()
+            .unwrap();
+        let txn = init_history(&mut history, &tmp_dir);
+        assert_eq!(
+            history
+                .expire(&mut txn.write(), now + Duration::days(2))
+                .unwrap(),
+            vec![Operation::Delete {
+                old: record(1, "a", &created),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_forwarded_log_replay() {
+        let tmp_dir = TempDir::new("history").unwrap();
+        let mut env = LmdbEnvironmentManager::create(tmp_dir.path(), "history").unwrap();
+        let log = ForwardedLog::new(&mut env).unwrap();
+        let txn = env.create_txn().unwrap();
+        let insert = |id| Operation::Insert {
+            new: record(id, "a", &[]),
+        };
+
+        let replay = log.replay(&mut txn.write(), None).unwrap();
+        assert!(replay.ops.is_empty());
+        assert_eq!(replay.last_seq, None);
+
+        log.append(&mut txn.write(), (1, 0), &[(0, insert(1)), (0, insert(2))])
+            .unwrap();
+        log.append(&mut txn.write(), (1, 1 << 32), &[]).unwrap();
+        log.append(&mut txn.write(), (2, 0), &[(1, insert(3))])
+            .unwrap();
+
+        // The second operation forwarded for the first ingested operation is not checkpointed
+        let replay = log.replay(&mut txn.write(), Some((1, 0))).unwrap();
+        assert_eq!(
+            replay.ops,
+            vec![((1, 1), 0, insert(2)), ((2, 0), 1, insert(3))]
+        );
+        assert_eq!(replay.last_seq, Some((2, 0)));
+        assert_eq!(replay.next_seq, Some((2, 1)));
+
+        // The checkpointed operations are removed, except the last ones
+        let replay = log.replay(&mut txn.write(), Some((2, 0))).unwrap();
+        assert!(replay.ops.is_empty());
+        let replay = log.replay(&mut txn.write(), None).unwrap();
+        assert_eq!(replay.ops, vec![((2, 0), 1, insert(3))]);
+        assert_eq!(replay.last_seq, Some((2, 0)));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod connector_source;
+mod history;
 mod sinks;
 pub mod source_builder;
 mod streaming_sink;
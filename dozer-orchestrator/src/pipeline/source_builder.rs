@@ -7,6 +7,7 @@ use dozer_sql::pipeline::builder::SchemaSQLContext;
 use dozer_types::models::source::Source;
 use dozer_types::parking_lot::RwLock;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
@@ -21,6 +22,7 @@ impl SourceBuilder {
         ingestor: Arc<RwLock<Ingestor>>,
         iterator: Arc<RwLock<IngestionIterator>>,
         running: Arc<AtomicBool>,
+        pipeline_dir: &Path,
     ) -> Result<AppSourceManager<SchemaSQLContext>, OrchestrationError> {
         let mut asm = AppSourceManager::new();
 
@@ -32,6 +34,7 @@ impl SourceBuilder {
             if let Some(connection) = &first_source.connection {
                 let mut ports = HashMap::new();
                 let mut tables = vec![];
+                let mut history_types = HashMap::new();
                 for source in &sources_group {
                     if used_sources.contains(&source.name) {
                         ports.insert(source.name.clone(), port);
@@ -43,6 +46,10 @@ impl SourceBuilder {
                             columns: Some(source.columns.clone()),
                         });
 
+                        if let Some(history_type) = &source.history_type {
+                            history_types.insert(port, history_type.clone());
+                        }
+
                         port += 1;
                     }
                 }
@@ -54,6 +61,8 @@ impl SourceBuilder {
                     tables,
                     connection.clone(),
                     running.clone(),
+                    history_types,
+                    pipeline_dir.to_path_buf(),
                 );
 
                 asm.add(AppSource::new(
@@ -85,6 +94,7 @@ mod tests {
     use crate::pipeline::source_builder::SourceBuilder;
     use dozer_ingestion::ingestion::{IngestionConfig, Ingestor};
     use dozer_types::models::app_config::Config;
+    use std::path::Path;
     use std::sync::atomic::AtomicBool;
     use std::sync::Arc;
 
@@ -126,6 +136,7 @@ mod tests {
                     columns: vec!["id".to_string()],
                     connection: Some(events1_conn.clone()),
                     refresh_config: None,
+                    history_type: None,
                     app_id: None,
                 },
                 Source {
@@ -135,6 +146,7 @@ mod tests {
                     columns: vec!["id".to_string()],
                     connection: Some(events1_conn),
                     refresh_config: None,
+                    history_type: None,
                     app_id: None,
                 },
                 Source {
@@ -144,6 +156,7 @@ mod tests {
                     columns: vec!["id".to_string()],
                     connection: Some(events2_conn.clone()),
                     refresh_config: None,
+                    history_type: None,
                     app_id: None,
                 },
                 Source {
@@ -153,11 +166,15 @@ mod tests {
                     columns: vec!["id".to_string()],
                     connection: Some(events2_conn),
                     refresh_config: None,
+                    history_type: None,
                     app_id: None,
                 },
             ],
             endpoints: vec![],
             home_dir: "test".to_string(),
+            udfs: vec![],
+            sql: None,
+            sql_dialect: None,
         }
     }
 
@@ -180,6 +197,7 @@ mod tests {
             ingestor,
             iterator_ref,
             Arc::new(AtomicBool::new(true)),
+            Path::new("test"),
         )
         .unwrap();
 
@@ -217,6 +235,7 @@ mod tests {
             ingestor,
             iterator_ref,
             Arc::new(AtomicBool::new(true)),
+            Path::new("test"),
         )
         .unwrap();
 
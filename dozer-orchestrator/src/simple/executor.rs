@@ -1,6 +1,9 @@
 use dozer_api::grpc::internal_grpc::PipelineResponse;
 use dozer_core::dag::app::{App, AppPipeline};
-use dozer_sql::pipeline::builder::{statement_to_pipeline, SchemaSQLContext};
+use dozer_sql::pipeline::builder::{
+    locate_schema_error, shared_statement_to_pipeline, views_to_query_context, QueryContext,
+    SchemaSQLContext,
+};
 use dozer_types::indicatif::MultiProgress;
 use dozer_types::types::{Operation, SchemaWithChangesType};
 use std::collections::HashMap;
@@ -22,6 +25,7 @@ use dozer_types::crossbeam;
 use dozer_types::log::{error, info};
 
 use dozer_types::models::connection::Connection;
+use dozer_types::models::sql_dialect::SqlDialect;
 use dozer_types::parking_lot::RwLock;
 use OrchestrationError::ExecutionError;
 
@@ -33,6 +37,10 @@ use crate::{validate, validate_schema};
 
 pub struct Executor {
     sources: Vec<Source>,
+    /// Views shared by the endpoints
+    sql: Option<String>,
+    /// Dialect of the views, and of the endpoints not setting their own
+    sql_dialect: SqlDialect,
     cache_endpoints: Vec<CacheEndpoint>,
     pipeline_dir: PathBuf,
     ingestor: Arc<RwLock<Ingestor>>,
@@ -43,6 +51,8 @@ pub struct Executor {
 impl Executor {
     pub fn new(
         sources: Vec<Source>,
+        sql: Option<String>,
+        sql_dialect: SqlDialect,
         cache_endpoints: Vec<CacheEndpoint>,
         ingestor: Arc<RwLock<Ingestor>>,
         iterator: Arc<RwLock<IngestionIterator>>,
@@ -51,6 +61,8 @@ impl Executor {
     ) -> Self {
         Self {
             sources,
+            sql,
+            sql_dialect,
             cache_endpoints,
             pipeline_dir,
             ingestor,
@@ -60,6 +72,17 @@ impl Executor {
         }
     }
 
+    /// Returns the context holding the views shared by the endpoints
+    fn get_views(&self) -> Result<QueryContext, OrchestrationError> {
+        self.sql.as_ref().map_or_else(
+            || Ok(QueryContext::default()),
+            |sql| {
+                views_to_query_context(sql, self.sql_dialect)
+                    .map_err(OrchestrationError::PipelineError)
+            },
+        )
+    }
+
     pub fn get_connection_groups(&self) -> HashMap<String, Vec<Source>> {
         SourceBuilder::group_connections(self.sources.clone())
     }
@@ -161,23 +184,73 @@ impl Executor {
         &self,
         sql: String,
         sender: crossbeam::channel::Sender<Operation>,
+    ) -> Result<dozer_core::dag::dag::Dag<SchemaSQLContext>, OrchestrationError> {
+        let dag = self.build_query_dag(&[("streaming_sink", &sql, self.sql_dialect)], sender)?;
+        let path = &self.pipeline_dir;
+        let mut exec = DagExecutor::new(
+            &dag,
+            path.as_path(),
+            ExecutorOptions::default(),
+            self.running.clone(),
+        )
+        .map_err(|e| self.locate_schema_error(&[(&sql, self.sql_dialect)], e))?;
+
+        exec.start()?;
+        Ok(dag)
+    }
+
+    /// Points a schema error at the first of `queries`, or of the shared views, mentioning the
+    /// name it is about
+    pub fn locate_schema_error(
+        &self,
+        queries: &[(&str, SqlDialect)],
+        error: dozer_core::dag::errors::ExecutionError,
+    ) -> OrchestrationError {
+        let views = self.sql.as_deref().map(|sql| (sql, self.sql_dialect));
+        let mut error = error;
+        for (sql, dialect) in queries.iter().copied().chain(views) {
+            match locate_schema_error(sql, dialect, error) {
+                Ok(located) => return OrchestrationError::PipelineError(located),
+                Err(e) => error = e,
+            }
+        }
+        ExecutionError(error)
+    }
+
+    /// Builds the DAG of the named queries without running it, so that it can be explained
+    pub fn explain(
+        &self,
+        queries: &[(&str, &str, SqlDialect)],
+    ) -> Result<dozer_core::dag::dag::Dag<SchemaSQLContext>, OrchestrationError> {
+        let (sender, _receiver) = crossbeam::channel::unbounded();
+        self.build_query_dag(queries, sender)
+    }
+
+    /// Builds a DAG streaming the result of each query to `sender`, through a sink named
+    /// after the query. The queries share a pipeline, so that they share the views they use.
+    fn build_query_dag(
+        &self,
+        queries: &[(&str, &str, SqlDialect)],
+        sender: crossbeam::channel::Sender<Operation>,
     ) -> Result<dozer_core::dag::dag::Dag<SchemaSQLContext>, OrchestrationError> {
         let grouped_connections = self.get_connection_groups();
 
-        let (mut pipeline, (query_name, query_port)) =
-            statement_to_pipeline(&sql).map_err(OrchestrationError::PipelineError)?;
-        pipeline.add_sink(
-            Arc::new(StreamingSinkFactory::new(sender)),
-            "streaming_sink",
-        );
-        pipeline
-            .connect_nodes(
-                &query_name,
-                Some(query_port),
-                "streaming_sink",
-                Some(DEFAULT_PORT_HANDLE),
-            )
-            .map_err(OrchestrationError::ExecutionError)?;
+        let mut views = self.get_views()?;
+        let mut pipeline = AppPipeline::new();
+        for (name, sql, dialect) in queries {
+            let (query_name, query_port) =
+                shared_statement_to_pipeline(sql, *dialect, &mut pipeline, &mut views)
+                    .map_err(OrchestrationError::PipelineError)?;
+            pipeline.add_sink(Arc::new(StreamingSinkFactory::new(sender.clone())), name);
+            pipeline
+                .connect_nodes(
+                    &query_name,
+                    Some(query_port),
+                    name,
+                    Some(DEFAULT_PORT_HANDLE),
+                )
+                .map_err(OrchestrationError::ExecutionError)?;
+        }
 
         let used_sources: Vec<String> = pipeline.get_entry_points_sources_names();
 
@@ -187,21 +260,12 @@ impl Executor {
             self.ingestor.clone(),
             self.iterator.clone(),
             self.running.clone(),
+            &self.pipeline_dir,
         )?;
         let mut app = App::new(asm);
         app.add_pipeline(pipeline);
 
-        let dag = app.get_dag().map_err(OrchestrationError::ExecutionError)?;
-        let path = &self.pipeline_dir;
-        let mut exec = DagExecutor::new(
-            &dag,
-            path.as_path(),
-            ExecutorOptions::default(),
-            self.running.clone(),
-        )?;
-
-        exec.start()?;
-        Ok(dag)
+        app.get_dag().map_err(OrchestrationError::ExecutionError)
     }
 
     // This function is used by both migrate and actual execution
@@ -215,21 +279,29 @@ impl Executor {
 
         Self::validate_grouped_connections(&grouped_connections)?;
 
+        // The endpoints defined by SQL share a pipeline, so that they share the nodes of the views
+        let mut views = self.get_views()?;
+        let mut sql_pipeline = AppPipeline::new();
         let mut pipelines: Vec<AppPipeline<SchemaSQLContext>> = vec![];
         let mut used_sources = vec![];
         for cache_endpoint in self.cache_endpoints.iter().cloned() {
             let api_endpoint = cache_endpoint.endpoint.clone();
-            let _api_endpoint_name = api_endpoint.name.clone();
             let cache = cache_endpoint.cache;
 
-            // let mut pipeline = PipelineBuilder {}
-            //     .build_pipeline(&api_endpoint.sql)
-            //     .map_err(OrchestrationError::PipelineError)?;
-
-            let (mut pipeline, (query_name, query_port)) = api_endpoint.sql.as_ref().map_or_else(
-                || Ok(source_to_pipeline(&api_endpoint)),
-                |sql| statement_to_pipeline(sql).map_err(OrchestrationError::PipelineError),
-            )?;
+            let (mut direct_pipeline, (query_name, query_port)) = match &api_endpoint.sql {
+                Some(sql) => {
+                    let dialect = api_endpoint.get_sql_dialect(self.sql_dialect);
+                    let node =
+                        shared_statement_to_pipeline(sql, dialect, &mut sql_pipeline, &mut views)
+                            .map_err(OrchestrationError::PipelineError)?;
+                    (None, node)
+                }
+                None => {
+                    let (pipeline, node) = source_to_pipeline(&api_endpoint);
+                    (Some(pipeline), node)
+                }
+            };
+            let pipeline = direct_pipeline.as_mut().unwrap_or(&mut sql_pipeline);
 
             pipeline.add_sink(
                 Arc::new(CacheSinkFactory::new(
@@ -253,12 +325,13 @@ impl Executor {
                 )
                 .map_err(ExecutionError)?;
 
-            for name in pipeline.get_entry_points_sources_names() {
-                used_sources.push(name);
+            if let Some(pipeline) = direct_pipeline {
+                used_sources.extend(pipeline.get_entry_points_sources_names());
+                pipelines.push(pipeline);
             }
-
-            pipelines.push(pipeline);
         }
+        used_sources.extend(sql_pipeline.get_entry_points_sources_names());
+        pipelines.push(sql_pipeline);
 
         let asm = SourceBuilder::build_source_manager(
             used_sources,
@@ -266,6 +339,7 @@ impl Executor {
             self.ingestor.clone(),
             self.iterator.clone(),
             self.running.clone(),
+            &self.pipeline_dir,
         )?;
         let mut app = App::new(asm);
 
@@ -280,6 +354,16 @@ impl Executor {
                 info!("[pipeline] Validation completed");
             })
             .map_err(|e| {
+                let queries: Vec<(&str, SqlDialect)> = self
+                    .cache_endpoints
+                    .iter()
+                    .filter_map(|cache_endpoint| {
+                        let endpoint = &cache_endpoint.endpoint;
+                        let sql = endpoint.sql.as_deref()?;
+                        Some((sql, endpoint.get_sql_dialect(self.sql_dialect)))
+                    })
+                    .collect();
+                let e = self.locate_schema_error(&queries, e);
                 error!("[pipeline] Validation error: {}", e);
                 OrchestrationError::PipelineValidationError
             })?;
@@ -19,16 +19,23 @@ use dozer_api::{
 };
 use dozer_cache::cache::{CacheCommonOptions, CacheOptions, CacheReadOptions, CacheWriteOptions};
 use dozer_cache::cache::{CacheOptionsKind, LmdbCache};
+use dozer_core::dag::app::AppPipeline;
 use dozer_core::dag::dag_schemas::DagSchemaManager;
 use dozer_core::dag::errors::ExecutionError::InternalError;
+use dozer_core::dag::explain::{explain_dag, ExplainFormat};
 use dozer_ingestion::ingestion::IngestionConfig;
 use dozer_ingestion::ingestion::Ingestor;
-use dozer_sql::pipeline::builder::statement_to_pipeline;
+use dozer_sql::pipeline::builder::{
+    shared_statement_to_pipeline, views_to_query_context, QueryContext,
+};
+use dozer_sql::pipeline::{register_udf, register_wasm_udf, ScalarUdf};
 use dozer_types::crossbeam::channel::{self, unbounded, Sender};
 use dozer_types::log::{info, warn};
 use dozer_types::models::api_config::ApiConfig;
 use dozer_types::models::api_endpoint::ApiEndpoint;
 use dozer_types::models::app_config::Config;
+use dozer_types::models::sql_dialect::SqlDialect;
+use dozer_types::parking_lot::{const_mutex, Mutex};
 use dozer_types::prettytable::{row, Table};
 use dozer_types::serde_yaml;
 use dozer_types::tracing::error;
@@ -50,6 +57,9 @@ pub struct SimpleOrchestrator {
     pub cache_write_options: CacheWriteOptions,
 }
 
+/// Whether the WebAssembly functions of the config were registered in the process
+static CONFIG_UDFS_REGISTERED: Mutex<bool> = const_mutex(false);
+
 impl SimpleOrchestrator {
     pub fn new(config: &Config) -> Self {
         Self {
@@ -57,6 +67,28 @@ impl SimpleOrchestrator {
             ..Default::default()
         }
     }
+
+    /// Makes `udf` callable from the endpoint SQL of the pipelines built afterwards.
+    /// Functions are registered for the whole process, so a name can only be registered once.
+    pub fn register_udf(&self, udf: Arc<dyn ScalarUdf>) -> Result<(), OrchestrationError> {
+        Ok(register_udf(udf)?)
+    }
+
+    /// Loads the WebAssembly functions declared in the config. Functions are registered for
+    /// the whole process, and entry points such as `migrate` and `run_apps` can run one
+    /// after the other, so the functions are only loaded the first time.
+    pub(crate) fn register_config_udfs(&self) -> Result<(), OrchestrationError> {
+        let mut registered = CONFIG_UDFS_REGISTERED.lock();
+        if *registered {
+            return Ok(());
+        }
+        for udf in &self.config.udfs {
+            register_wasm_udf(&udf.name, &udf.module, udf.function.as_deref())?;
+        }
+        *registered = true;
+        Ok(())
+    }
+
     fn write_internal_config(&self) -> Result<(), OrchestrationError> {
         let path = Path::new(&self.config.home_dir).join("internal_config");
         if path.exists() {
@@ -186,6 +218,7 @@ impl Orchestrator for SimpleOrchestrator {
         running: Arc<AtomicBool>,
         api_notifier: Option<Sender<bool>>,
     ) -> Result<(), OrchestrationError> {
+        self.register_config_udfs()?;
         let pipeline_home_dir = get_pipeline_dir(self.config.to_owned());
         // gRPC notifier channel
         let (sender, receiver) = channel::unbounded::<PipelineResponse>();
@@ -212,6 +245,8 @@ impl Orchestrator for SimpleOrchestrator {
 
         let executor = Executor::new(
             sources,
+            self.config.sql.clone(),
+            self.config.sql_dialect(),
             cache_endpoints,
             ingestor,
             iterator,
@@ -255,6 +290,7 @@ impl Orchestrator for SimpleOrchestrator {
         sender: Sender<Operation>,
         running: Arc<AtomicBool>,
     ) -> Result<Schema, OrchestrationError> {
+        self.register_config_udfs()?;
         // Ingestion channel
         let (ingestor, iterator) = Ingestor::initialize_channel(IngestionConfig::default());
 
@@ -264,6 +300,8 @@ impl Orchestrator for SimpleOrchestrator {
             .map_err(|e| OrchestrationError::InternalError(Box::new(e)))?;
         let executor = Executor::new(
             sources,
+            self.config.sql.clone(),
+            self.config.sql_dialect(),
             vec![],
             ingestor,
             iterator,
@@ -282,6 +320,60 @@ impl Orchestrator for SimpleOrchestrator {
             .clone();
         Ok(schema)
     }
+
+    fn explain(
+        &self,
+        sql: Option<String>,
+        endpoint: Option<String>,
+        format: ExplainFormat,
+    ) -> Result<String, OrchestrationError> {
+        self.register_config_udfs()?;
+        // Explaining a query explains it alone, otherwise the endpoints share a pipeline
+        let dialect = self.config.sql_dialect();
+        let queries: Vec<(&str, &str, SqlDialect)> = match &sql {
+            Some(sql) => vec![("query", sql.as_str(), dialect)],
+            None => {
+                let queries: Vec<(&str, &str, SqlDialect)> = self
+                    .config
+                    .endpoints
+                    .iter()
+                    .filter(|e| endpoint.as_ref().map_or(true, |name| &e.name == name))
+                    .filter_map(|e| {
+                        let sql = e.sql.as_deref()?;
+                        Some((e.name.as_str(), sql, e.get_sql_dialect(dialect)))
+                    })
+                    .collect();
+                if let (Some(name), true) = (&endpoint, queries.is_empty()) {
+                    return Err(OrchestrationError::SqlEndpointNotFound(name.clone()));
+                }
+                queries
+            }
+        };
+
+        let (ingestor, iterator) = Ingestor::initialize_channel(IngestionConfig::default());
+        let pipeline_dir = tempdir::TempDir::new("explain")
+            .map_err(|e| OrchestrationError::InternalError(Box::new(e)))?;
+        let executor = Executor::new(
+            self.config.sources.clone(),
+            self.config.sql.clone(),
+            self.config.sql_dialect(),
+            vec![],
+            ingestor,
+            iterator,
+            Arc::new(AtomicBool::new(true)),
+            pipeline_dir.path().to_path_buf(),
+        );
+
+        let dag = executor.explain(&queries)?;
+        explain_dag(&dag, format).map_err(|e| {
+            let queries: Vec<(&str, SqlDialect)> = queries
+                .iter()
+                .map(|(_, sql, dialect)| (*sql, *dialect))
+                .collect();
+            executor.locate_schema_error(&queries, e)
+        })
+    }
+
     fn migrate(&mut self, force: bool) -> Result<(), OrchestrationError> {
         self.write_internal_config()
             .map_err(|e| InternalError(Box::new(e)))?;
@@ -312,7 +404,12 @@ impl Orchestrator for SimpleOrchestrator {
         }
 
         print_api_endpoints(&self.config.endpoints);
-        validate_endpoints(&self.config.endpoints)?;
+        self.register_config_udfs()?;
+        validate_endpoints(
+            self.config.sql.as_deref(),
+            self.config.sql_dialect(),
+            &self.config.endpoints,
+        )?;
 
         // Ingestion channel
         let (ingestor, iterator) = Ingestor::initialize_channel(IngestionConfig::default());
@@ -323,6 +420,8 @@ impl Orchestrator for SimpleOrchestrator {
 
         let executor = Executor::new(
             sources,
+            self.config.sql.clone(),
+            self.config.sql_dialect(),
             cache_endpoints,
             ingestor,
             iterator,
@@ -403,7 +502,23 @@ impl SimpleOrchestrator {
     }
 }
 
-pub fn validate_endpoints(endpoints: &Vec<ApiEndpoint>) -> Result<(), OrchestrationError> {
+pub fn validate_endpoints(
+    sql: Option<&str>,
+    sql_dialect: SqlDialect,
+    endpoints: &Vec<ApiEndpoint>,
+) -> Result<(), OrchestrationError> {
+    let mut views = match sql {
+        Some(sql) => views_to_query_context(sql, sql_dialect).map_err(|e| {
+            error!(
+                "[Views] {} Views validation error: {}",
+                get_colored_text("X", "31"),
+                e
+            );
+            OrchestrationError::PipelineValidationError
+        })?,
+        None => QueryContext::default(),
+    };
+    let mut pipeline = AppPipeline::new();
     let mut is_all_valid = true;
     for endpoint in endpoints {
         endpoint.sql.as_ref().map_or_else(
@@ -415,7 +530,8 @@ pub fn validate_endpoints(endpoints: &Vec<ApiEndpoint>) -> Result<(), Orchestrat
                 );
             },
             |sql| {
-                statement_to_pipeline(sql).map_or_else(
+                let dialect = endpoint.get_sql_dialect(sql_dialect);
+                shared_statement_to_pipeline(sql, dialect, &mut pipeline, &mut views).map_or_else(
                     |e| {
                         is_all_valid = false;
                         error!(
@@ -19,6 +19,8 @@ use dozer_types::{
         api_endpoint::{ApiEndpoint, ApiIndex},
         connection::EventsAuthentication,
         flags::Flags,
+        sql_dialect::SqlDialect,
+        udf_config::UdfConfig,
     },
     types::{Field, OperationEvent, Record, Schema},
 };
@@ -28,6 +30,7 @@ use tempdir::TempDir;
 use crate::pipeline::CacheSinkSettings;
 
 use super::executor::Executor;
+use super::SimpleOrchestrator;
 
 fn single_source_sink_impl(schema: Schema) {
     let source = models::source::Source {
@@ -45,6 +48,7 @@ fn single_source_sink_impl(schema: Schema) {
             ..Default::default()
         }),
         refresh_config: Some(models::source::RefreshConfig::default()),
+        history_type: None,
         ..Default::default()
     };
 
@@ -91,6 +95,8 @@ fn single_source_sink_impl(schema: Schema) {
     let _thread = thread::spawn(move || {
         let executor = Executor::new(
             vec![source],
+            None,
+            SqlDialect::Ansi,
             vec![cache_endpoint],
             ingestor,
             iterator,
@@ -146,3 +152,36 @@ fn test_query(schema_name: String, query: Value, count: usize, cache: &LmdbCache
 
     assert_eq!(records.len(), count, "Count must be equal : {query:?}");
 }
+
+/// `(module (func (export "add") (param i64 i64) (result i64) local.get 0 local.get 1 i64.add))`
+const ADD_MODULE: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7e, 0x7e, 0x01,
+    0x7e, 0x03, 0x02, 0x01, 0x00, 0x07, 0x07, 0x01, 0x03, 0x61, 0x64, 0x64, 0x00, 0x00, 0x0a, 0x09,
+    0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x7c, 0x0b,
+];
+
+#[test]
+fn register_config_udfs_once() {
+    let tmp_dir = TempDir::new("udfs").unwrap();
+    let module = tmp_dir.path().join("add.wasm");
+    fs::write(&module, ADD_MODULE).unwrap();
+    let config = models::app_config::Config {
+        udfs: vec![UdfConfig {
+            name: "config_add".to_string(),
+            module: module.to_str().unwrap().to_string(),
+            function: Some("add".to_string()),
+        }],
+        ..Default::default()
+    };
+
+    // `dozer` runs `migrate` then `run_apps` in the same process, and both register the
+    // functions of the config
+    let orchestrator = SimpleOrchestrator::new(&config);
+    orchestrator.register_config_udfs().unwrap();
+    orchestrator.register_config_udfs().unwrap();
+    // The function was registered by the first call
+    assert!(
+        dozer_sql::pipeline::register_wasm_udf("config_add", module.to_str().unwrap(), None)
+            .is_err()
+    );
+}
@@ -56,6 +56,7 @@ pub fn init_endpoint() -> ApiEndpoint {
         index: Some(ApiIndex {
             primary_key: vec!["film_id".to_string()],
         }),
+        sql_dialect: None,
         app_id: None,
     }
 }
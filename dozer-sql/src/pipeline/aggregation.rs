@@ -1,9 +1,15 @@
 pub mod aggregator;
+mod approx_count_distinct;
+mod approx_percentile;
 mod avg;
 mod count;
+mod count_distinct;
 pub mod factory;
 mod max;
+mod median;
 mod min;
 pub mod processor;
+mod stddev;
 mod sum;
 mod tests;
+mod variance;
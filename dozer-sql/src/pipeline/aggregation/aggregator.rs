@@ -1,22 +1,36 @@
+use crate::pipeline::aggregation::approx_count_distinct::ApproxCountDistinctAggregator;
+use crate::pipeline::aggregation::approx_percentile::ApproxPercentileAggregator;
 use crate::pipeline::aggregation::avg::AvgAggregator;
 use crate::pipeline::aggregation::count::CountAggregator;
+use crate::pipeline::aggregation::count_distinct::CountDistinctAggregator;
 use crate::pipeline::aggregation::max::MaxAggregator;
+use crate::pipeline::aggregation::median::MedianAggregator;
 use crate::pipeline::aggregation::min::MinAggregator;
+use crate::pipeline::aggregation::stddev::StddevAggregator;
 use crate::pipeline::aggregation::sum::SumAggregator;
+use crate::pipeline::aggregation::variance::VarianceAggregator;
 use crate::pipeline::errors::PipelineError;
 
 use dozer_core::storage::common::Database;
 use dozer_core::storage::prefix_transaction::PrefixTransaction;
+use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::types::{Field, FieldType};
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum Aggregator {
+    ApproxCountDistinct,
+    /// Percentile between 0 and 1
+    ApproxPercentile(OrderedFloat<f64>),
     Avg,
     Count,
+    CountDistinct,
     Max,
+    Median,
     Min,
+    Stddev,
     Sum,
+    Variance,
 }
 
 pub(crate) struct AggregationResult {
@@ -39,21 +53,37 @@ impl Display for Aggregator {
 impl Aggregator {
     pub(crate) fn get_return_type(&self, from: FieldType) -> FieldType {
         match (&self, from) {
+            (Aggregator::ApproxCountDistinct, _) => {
+                ApproxCountDistinctAggregator::get_return_type()
+            }
+            (Aggregator::ApproxPercentile(_), from) => {
+                ApproxPercentileAggregator::get_return_type(from)
+            }
             (Aggregator::Avg, _) => AvgAggregator::get_return_type(from),
             (Aggregator::Count, _) => CountAggregator::get_return_type(),
+            (Aggregator::CountDistinct, _) => CountDistinctAggregator::get_return_type(),
             (Aggregator::Max, from) => MaxAggregator::get_return_type(from),
+            (Aggregator::Median, from) => MedianAggregator::get_return_type(from),
             (Aggregator::Min, from) => MinAggregator::get_return_type(from),
+            (Aggregator::Stddev, from) => StddevAggregator::get_return_type(from),
             (Aggregator::Sum, from) => SumAggregator::get_return_type(from),
+            (Aggregator::Variance, from) => VarianceAggregator::get_return_type(from),
         }
     }
 
     pub(crate) fn _get_type(&self) -> u32 {
         match &self {
+            Aggregator::ApproxCountDistinct => ApproxCountDistinctAggregator::_get_type(),
+            Aggregator::ApproxPercentile(_) => ApproxPercentileAggregator::_get_type(),
             Aggregator::Avg => AvgAggregator::_get_type(),
             Aggregator::Count => CountAggregator::_get_type(),
+            Aggregator::CountDistinct => CountDistinctAggregator::_get_type(),
             Aggregator::Max => MaxAggregator::_get_type(),
+            Aggregator::Median => MedianAggregator::_get_type(),
             Aggregator::Min => MinAggregator::_get_type(),
+            Aggregator::Stddev => StddevAggregator::_get_type(),
             Aggregator::Sum => SumAggregator::_get_type(),
+            Aggregator::Variance => VarianceAggregator::_get_type(),
         }
     }
 
@@ -66,11 +96,25 @@ impl Aggregator {
         agg_db: Database,
     ) -> Result<AggregationResult, PipelineError> {
         match &self {
+            Aggregator::ApproxCountDistinct => {
+                ApproxCountDistinctAggregator::insert(cur_state, new, return_type, txn)
+            }
+            Aggregator::ApproxPercentile(percentile) => {
+                ApproxPercentileAggregator::insert(cur_state, new, percentile.0, return_type, txn)
+            }
             Aggregator::Avg => AvgAggregator::insert(cur_state, new, return_type, txn, agg_db),
             Aggregator::Count => CountAggregator::insert(cur_state, new, return_type, txn),
+            Aggregator::CountDistinct => {
+                CountDistinctAggregator::insert(cur_state, new, return_type, txn, agg_db)
+            }
             Aggregator::Max => MaxAggregator::insert(cur_state, new, return_type, txn, agg_db),
+            Aggregator::Median => {
+                MedianAggregator::insert(cur_state, new, return_type, txn, agg_db)
+            }
             Aggregator::Min => MinAggregator::insert(cur_state, new, return_type, txn, agg_db),
+            Aggregator::Stddev => StddevAggregator::insert(cur_state, new, return_type, txn),
             Aggregator::Sum => SumAggregator::insert(cur_state, new, return_type, txn),
+            Aggregator::Variance => VarianceAggregator::insert(cur_state, new, return_type, txn),
         }
     }
 
@@ -84,11 +128,32 @@ impl Aggregator {
         agg_db: Database,
     ) -> Result<AggregationResult, PipelineError> {
         match &self {
+            Aggregator::ApproxCountDistinct => {
+                ApproxCountDistinctAggregator::update(cur_state, old, new, return_type, txn)
+            }
+            Aggregator::ApproxPercentile(percentile) => ApproxPercentileAggregator::update(
+                cur_state,
+                old,
+                new,
+                percentile.0,
+                return_type,
+                txn,
+            ),
             Aggregator::Avg => AvgAggregator::update(cur_state, old, new, return_type, txn, agg_db),
             Aggregator::Count => CountAggregator::update(cur_state, old, new, return_type, txn),
+            Aggregator::CountDistinct => {
+                CountDistinctAggregator::update(cur_state, old, new, return_type, txn, agg_db)
+            }
             Aggregator::Max => MaxAggregator::update(cur_state, old, new, return_type, txn, agg_db),
+            Aggregator::Median => {
+                MedianAggregator::update(cur_state, old, new, return_type, txn, agg_db)
+            }
             Aggregator::Min => MinAggregator::update(cur_state, old, new, return_type, txn, agg_db),
+            Aggregator::Stddev => StddevAggregator::update(cur_state, old, new, return_type, txn),
             Aggregator::Sum => SumAggregator::update(cur_state, old, new, return_type, txn),
+            Aggregator::Variance => {
+                VarianceAggregator::update(cur_state, old, new, return_type, txn)
+            }
         }
     }
 
@@ -101,11 +166,25 @@ impl Aggregator {
         agg_db: Database,
     ) -> Result<AggregationResult, PipelineError> {
         match &self {
+            Aggregator::ApproxCountDistinct => {
+                ApproxCountDistinctAggregator::delete(cur_state, old, return_type, txn)
+            }
+            Aggregator::ApproxPercentile(percentile) => {
+                ApproxPercentileAggregator::delete(cur_state, old, percentile.0, return_type, txn)
+            }
             Aggregator::Avg => AvgAggregator::delete(cur_state, old, return_type, txn, agg_db),
             Aggregator::Count => CountAggregator::delete(cur_state, old, return_type, txn),
+            Aggregator::CountDistinct => {
+                CountDistinctAggregator::delete(cur_state, old, return_type, txn, agg_db)
+            }
             Aggregator::Max => MaxAggregator::delete(cur_state, old, return_type, txn, agg_db),
+            Aggregator::Median => {
+                MedianAggregator::delete(cur_state, old, return_type, txn, agg_db)
+            }
             Aggregator::Min => MinAggregator::delete(cur_state, old, return_type, txn, agg_db),
+            Aggregator::Stddev => StddevAggregator::delete(cur_state, old, return_type, txn),
             Aggregator::Sum => SumAggregator::delete(cur_state, old, return_type, txn),
+            Aggregator::Variance => VarianceAggregator::delete(cur_state, old, return_type, txn),
         }
     }
 }
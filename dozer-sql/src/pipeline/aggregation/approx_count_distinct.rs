@@ -0,0 +1,168 @@
+use crate::deserialize;
+use crate::pipeline::aggregation::aggregator::AggregationResult;
+use crate::pipeline::errors::PipelineError;
+use dozer_core::storage::prefix_transaction::PrefixTransaction;
+use dozer_types::types::Field::Int;
+use dozer_types::types::{Field, FieldType};
+use std::collections::BTreeMap;
+
+pub struct ApproxCountDistinctAggregator {}
+
+impl ApproxCountDistinctAggregator {
+    const _AGGREGATOR_ID: u32 = 0x0A;
+
+    pub(crate) fn get_return_type() -> FieldType {
+        FieldType::Int
+    }
+
+    pub(crate) fn _get_type() -> u32 {
+        ApproxCountDistinctAggregator::_AGGREGATOR_ID
+    }
+
+    pub(crate) fn insert(
+        cur_state: Option<&[u8]>,
+        new: &Field,
+        _return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        let mut sketch = HyperLogLog::decode(cur_state);
+        sketch.insert(new);
+        Ok(Self::get_result(&sketch))
+    }
+
+    pub(crate) fn update(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        new: &Field,
+        _return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        let mut sketch = HyperLogLog::decode(cur_state);
+        sketch.delete(old);
+        sketch.insert(new);
+        Ok(Self::get_result(&sketch))
+    }
+
+    pub(crate) fn delete(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        _return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        let mut sketch = HyperLogLog::decode(cur_state);
+        sketch.delete(old);
+        Ok(Self::get_result(&sketch))
+    }
+
+    fn get_result(sketch: &HyperLogLog) -> AggregationResult {
+        AggregationResult::new(Int(sketch.estimate()), Some(sketch.encode()))
+    }
+}
+
+/// Number of bits of the hash selecting the register, giving a standard error of 3.25%
+const PRECISION: u32 = 10;
+const REGISTERS: usize = 1 << PRECISION;
+/// Size of an encoded entry: register, rank and count
+const ENTRY_SIZE: usize = 7;
+
+/// HyperLogLog sketch supporting deletes: instead of the highest rank seen, each register
+/// keeps how many values were inserted with each rank, so that the estimate after deletes is
+/// the one of a sketch built from the remaining values. Keeping only the highest ranks would
+/// leave registers empty once their highest values are deleted, biasing the estimate low.
+/// The number of ranks of a register grows with the logarithm of its values, up to 55.
+#[derive(Debug, Default)]
+struct HyperLogLog {
+    /// Number of values by register and rank
+    counts: BTreeMap<(u16, u8), u32>,
+}
+
+impl HyperLogLog {
+    fn decode(state: Option<&[u8]>) -> Self {
+        let counts = state
+            .unwrap_or_default()
+            .chunks_exact(ENTRY_SIZE)
+            .map(|entry| {
+                let register = u16::from_be_bytes(deserialize!(&entry[0..2]));
+                let count = u32::from_be_bytes(deserialize!(&entry[3..7]));
+                ((register, entry[2]), count)
+            })
+            .collect();
+        Self { counts }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.counts.len() * ENTRY_SIZE);
+        for ((register, rank), count) in &self.counts {
+            buf.extend(register.to_be_bytes());
+            buf.push(*rank);
+            buf.extend(count.to_be_bytes());
+        }
+        buf
+    }
+
+    fn insert(&mut self, value: &Field) {
+        let Some((register, rank)) = Self::locate(value) else {
+            return;
+        };
+        *self.counts.entry((register, rank)).or_default() += 1;
+    }
+
+    fn delete(&mut self, value: &Field) {
+        let Some(key) = Self::locate(value) else {
+            return;
+        };
+        if let Some(count) = self.counts.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&key);
+            }
+        }
+    }
+
+    fn estimate(&self) -> i64 {
+        let mut ranks = [0_u8; REGISTERS];
+        for (register, rank) in self.counts.keys() {
+            let highest = &mut ranks[*register as usize];
+            *highest = (*highest).max(*rank);
+        }
+
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = ranks.iter().map(|rank| 2_f64.powi(-(*rank as i32))).sum();
+        let estimate = alpha * m * m / sum;
+
+        // Linear counting is more accurate for small cardinalities
+        let empty = ranks.iter().filter(|rank| **rank == 0).count();
+        let estimate = if estimate <= 2.5 * m && empty > 0 {
+            m * (m / empty as f64).ln()
+        } else {
+            estimate
+        };
+        estimate.round() as i64
+    }
+
+    /// Register and rank of a value, the rank being the position of the first set bit after
+    /// the register bits of its hash. Null values are not counted.
+    fn locate(value: &Field) -> Option<(u16, u8)> {
+        if value == &Field::Null {
+            return None;
+        }
+        let hash = hash64(&value.encode());
+        let register = (hash >> (64 - PRECISION)) as u16;
+        let rank = ((hash << PRECISION).leading_zeros() + 1).min(64 - PRECISION + 1) as u8;
+        Some((register, rank))
+    }
+}
+
+/// FNV-1a followed by the finalizer of MurmurHash3, to spread the bits. The hash is stable
+/// across releases, as the sketches are persisted.
+fn hash64(bytes: &[u8]) -> u64 {
+    let mut hash = bytes.iter().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    });
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^ (hash >> 33)
+}
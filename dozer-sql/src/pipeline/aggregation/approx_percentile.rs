@@ -0,0 +1,222 @@
+use crate::deserialize;
+use crate::pipeline::aggregation::aggregator::AggregationResult;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::errors::PipelineError::InvalidOperandType;
+use dozer_core::storage::prefix_transaction::PrefixTransaction;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+use std::f64::consts::PI;
+
+pub struct ApproxPercentileAggregator {}
+const AGGREGATOR_NAME: &str = "APPROX_PERCENTILE";
+
+impl ApproxPercentileAggregator {
+    const _AGGREGATOR_ID: u32 = 0x0B;
+
+    pub(crate) fn get_return_type(from: FieldType) -> FieldType {
+        match from {
+            FieldType::Decimal | FieldType::Float | FieldType::Int | FieldType::UInt => {
+                FieldType::Float
+            }
+            _ => from,
+        }
+    }
+
+    pub(crate) fn _get_type() -> u32 {
+        ApproxPercentileAggregator::_AGGREGATOR_ID
+    }
+
+    pub(crate) fn insert(
+        cur_state: Option<&[u8]>,
+        new: &Field,
+        percentile: f64,
+        return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        Self::validate_type(return_type)?;
+        let mut digest = TDigest::decode(cur_state);
+        digest.insert(new);
+        Ok(Self::get_result(&digest, percentile))
+    }
+
+    pub(crate) fn update(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        new: &Field,
+        percentile: f64,
+        return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        Self::validate_type(return_type)?;
+        let mut digest = TDigest::decode(cur_state);
+        digest.delete(old);
+        digest.insert(new);
+        Ok(Self::get_result(&digest, percentile))
+    }
+
+    pub(crate) fn delete(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        percentile: f64,
+        return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        Self::validate_type(return_type)?;
+        let mut digest = TDigest::decode(cur_state);
+        digest.delete(old);
+        Ok(Self::get_result(&digest, percentile))
+    }
+
+    fn validate_type(return_type: FieldType) -> Result<(), PipelineError> {
+        match return_type {
+            FieldType::Decimal | FieldType::Float | FieldType::Int | FieldType::UInt => Ok(()),
+            _ => Err(InvalidOperandType(AGGREGATOR_NAME.to_string())),
+        }
+    }
+
+    fn get_result(digest: &TDigest, percentile: f64) -> AggregationResult {
+        let value = digest
+            .quantile(percentile)
+            .map_or(Field::Null, |value| Field::Float(OrderedFloat(value)));
+        AggregationResult::new(value, Some(digest.encode()))
+    }
+}
+
+/// Bounds the number of centroids, trading size for accuracy
+const COMPRESSION: f64 = 100.0;
+/// Centroids are merged once there are this many of them
+const MAX_CENTROIDS: usize = 2 * COMPRESSION as usize;
+/// Size of an encoded centroid: mean and weight
+const CENTROID_SIZE: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: u64,
+}
+
+/// Merging t-digest. The centroids near the extremes are kept small, so the tail percentiles
+/// stay accurate. Deletes remove a value from the centroid closest to it, which is exact for
+/// the values that were not merged yet and an approximation otherwise.
+#[derive(Debug, Default)]
+struct TDigest {
+    /// Centroids sorted by mean
+    centroids: Vec<Centroid>,
+}
+
+impl TDigest {
+    fn decode(state: Option<&[u8]>) -> Self {
+        let centroids = state
+            .unwrap_or_default()
+            .chunks_exact(CENTROID_SIZE)
+            .map(|centroid| Centroid {
+                mean: f64::from_be_bytes(deserialize!(&centroid[0..8])),
+                weight: u64::from_be_bytes(deserialize!(&centroid[8..16])),
+            })
+            .collect();
+        Self { centroids }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.centroids.len() * CENTROID_SIZE);
+        for centroid in &self.centroids {
+            buf.extend(centroid.mean.to_be_bytes());
+            buf.extend(centroid.weight.to_be_bytes());
+        }
+        buf
+    }
+
+    fn insert(&mut self, value: &Field) {
+        let Some(mean) = Self::get_value(value) else {
+            return;
+        };
+        let idx = self.centroids.partition_point(|c| c.mean < mean);
+        self.centroids.insert(idx, Centroid { mean, weight: 1 });
+        if self.centroids.len() > MAX_CENTROIDS {
+            self.compress();
+        }
+    }
+
+    fn delete(&mut self, value: &Field) {
+        let Some(value) = Self::get_value(value) else {
+            return;
+        };
+        let closest = self
+            .centroids
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| (a.mean - value).abs().total_cmp(&(b.mean - value).abs()))
+            .map(|(idx, _)| idx);
+        if let Some(idx) = closest {
+            let centroid = &mut self.centroids[idx];
+            centroid.weight -= 1;
+            if centroid.weight == 0 {
+                self.centroids.remove(idx);
+            }
+        }
+    }
+
+    /// Merges the adjacent centroids whose merged size stays within the bound of the `k1`
+    /// scale function
+    fn compress(&mut self) {
+        let total = self.total_weight() as f64;
+        let scale = |q: f64| COMPRESSION / (2.0 * PI) * (2.0 * q - 1.0).clamp(-1.0, 1.0).asin();
+
+        let mut merged: Vec<Centroid> = Vec::with_capacity(self.centroids.len());
+        let mut weight_before = 0_u64;
+        for centroid in self.centroids.drain(..) {
+            match merged.last_mut() {
+                Some(last)
+                    if scale((weight_before + last.weight + centroid.weight) as f64 / total)
+                        - scale(weight_before as f64 / total)
+                        <= 1.0 =>
+                {
+                    let weight = last.weight + centroid.weight;
+                    last.mean +=
+                        (centroid.mean - last.mean) * centroid.weight as f64 / weight as f64;
+                    last.weight = weight;
+                }
+                Some(last) => {
+                    weight_before += last.weight;
+                    merged.push(centroid);
+                }
+                None => merged.push(centroid),
+            }
+        }
+        self.centroids = merged;
+    }
+
+    /// Interpolates between the centers of the centroids around the `q` quantile. Returns
+    /// `None` if the digest is empty.
+    fn quantile(&self, q: f64) -> Option<f64> {
+        let first = self.centroids.first()?;
+        let target = q * self.total_weight() as f64;
+
+        let mut weight_before = 0_f64;
+        let mut prev = (first.mean, first.weight as f64 / 2.0);
+        if target <= prev.1 {
+            return Some(first.mean);
+        }
+        for centroid in &self.centroids {
+            let center = weight_before + centroid.weight as f64 / 2.0;
+            if target <= center {
+                let ratio = (target - prev.1) / (center - prev.1);
+                return Some(prev.0 + (centroid.mean - prev.0) * ratio);
+            }
+            prev = (centroid.mean, center);
+            weight_before += centroid.weight as f64;
+        }
+        Some(prev.0)
+    }
+
+    fn total_weight(&self) -> u64 {
+        self.centroids.iter().map(|c| c.weight).sum()
+    }
+
+    fn get_value(value: &Field) -> Option<f64> {
+        match value {
+            Field::Null => None,
+            value => value.to_float(),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::pipeline::aggregation::aggregator::AggregationResult;
+use crate::pipeline::errors::PipelineError;
+use crate::{deserialize, deserialize_i64, deserialize_u64, to_bytes, try_unwrap};
+use dozer_core::storage::common::Database;
+use dozer_core::storage::prefix_transaction::PrefixTransaction;
+use dozer_types::types::Field::Int;
+use dozer_types::types::{Field, FieldType};
+
+pub struct CountDistinctAggregator {}
+
+impl CountDistinctAggregator {
+    const _AGGREGATOR_ID: u32 = 0x09;
+
+    pub(crate) fn get_return_type() -> FieldType {
+        FieldType::Int
+    }
+
+    pub(crate) fn _get_type() -> u32 {
+        CountDistinctAggregator::_AGGREGATOR_ID
+    }
+
+    pub(crate) fn insert(
+        cur_state: Option<&[u8]>,
+        new: &Field,
+        _return_type: FieldType,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<AggregationResult, PipelineError> {
+        let prev = deserialize_i64!(cur_state);
+        let delta = Self::update_aggregator_db(new, false, ptx, aggregators_db);
+        Ok(Self::get_result(prev + delta))
+    }
+
+    pub(crate) fn update(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        new: &Field,
+        _return_type: FieldType,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<AggregationResult, PipelineError> {
+        let prev = deserialize_i64!(cur_state);
+        let delta = Self::update_aggregator_db(new, false, ptx, aggregators_db)
+            + Self::update_aggregator_db(old, true, ptx, aggregators_db);
+        Ok(Self::get_result(prev + delta))
+    }
+
+    pub(crate) fn delete(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        _return_type: FieldType,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<AggregationResult, PipelineError> {
+        let prev = deserialize_i64!(cur_state);
+        let delta = Self::update_aggregator_db(old, true, ptx, aggregators_db);
+        Ok(Self::get_result(prev + delta))
+    }
+
+    fn get_result(count: i64) -> AggregationResult {
+        let buf = count.to_be_bytes();
+        AggregationResult::new(Int(count), Some(Vec::from(buf)))
+    }
+
+    /// Updates the occurrences of `val` in aggregators_db, returning the change in the
+    /// number of distinct values. Null values are not counted.
+    fn update_aggregator_db(
+        val: &Field,
+        decr: bool,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> i64 {
+        if val == &Field::Null {
+            return 0;
+        }
+        let key = val.encode();
+        let get_prev_count = try_unwrap!(ptx.get(aggregators_db, key.as_slice()));
+        let prev_count = deserialize_u64!(get_prev_count);
+        match (decr, prev_count) {
+            (false, _) => {
+                try_unwrap!(ptx.put(aggregators_db, key.as_slice(), to_bytes!(prev_count + 1)));
+                if prev_count == 0 {
+                    1
+                } else {
+                    0
+                }
+            }
+            (true, 0) => 0,
+            (true, 1) => {
+                try_unwrap!(ptx.del(
+                    aggregators_db,
+                    key.as_slice(),
+                    Option::from(to_bytes!(prev_count))
+                ));
+                -1
+            }
+            (true, _) => {
+                try_unwrap!(ptx.put(aggregators_db, key.as_slice(), to_bytes!(prev_count - 1)));
+                0
+            }
+        }
+    }
+}
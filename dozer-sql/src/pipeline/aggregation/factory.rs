@@ -5,8 +5,9 @@ use dozer_core::dag::{
     errors::ExecutionError,
     node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
 };
+use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::types::{FieldDefinition, Schema, SourceDefinition};
-use sqlparser::ast::{Expr as SqlExpr, Expr, Ident, SelectItem};
+use sqlparser::ast::{Expr as SqlExpr, Expr, FunctionArg, FunctionArgExpr, Ident, SelectItem};
 
 use crate::pipeline::{
     builder::SchemaSQLContext,
@@ -15,7 +16,7 @@ use crate::pipeline::{
 use crate::pipeline::{
     errors::PipelineError,
     expression::{
-        aggregate::AggregateFunctionType,
+        aggregate::{get_percentile, AggregateFunctionType},
         builder::{BuilderExpressionType, ExpressionBuilder},
         execution::{Expression, ExpressionExecutor},
     },
@@ -24,7 +25,7 @@ use crate::pipeline::{
 
 use super::{
     aggregator::Aggregator,
-    processor::{AggregationProcessor, FieldRule},
+    processor::{AggregationProcessor, FieldRule, HavingFilter},
 };
 
 #[derive(Debug)]
@@ -32,6 +33,7 @@ pub struct AggregationProcessorFactory {
     name: NameOrAlias,
     select: Vec<SelectItem>,
     groupby: Vec<SqlExpr>,
+    having: Option<SqlExpr>,
     stateful: bool,
 }
 
@@ -41,15 +43,45 @@ impl AggregationProcessorFactory {
         name: NameOrAlias,
         select: Vec<SelectItem>,
         groupby: Vec<SqlExpr>,
+        having: Option<SqlExpr>,
         stateful: bool,
     ) -> Self {
         Self {
             name,
             select,
             groupby,
+            having,
             stateful,
         }
     }
+
+    /// Returns the aggregation rules, including the hidden ones required by the HAVING
+    /// clause, the HAVING clause rewritten over the output fields and the number of
+    /// output fields sent to the next node
+    pub(crate) fn get_rules(
+        &self,
+        input_schema: &Schema,
+    ) -> Result<(Vec<FieldRule>, Option<SqlExpr>, usize), PipelineError> {
+        let mut output_field_rules =
+            get_aggregation_rules(&self.select, &self.groupby, input_schema)?;
+        let output_size = output_field_rules.len() - self.groupby.len();
+
+        match &self.having {
+            Some(having) => {
+                let (having, hidden_rules) = get_having_rules(
+                    having,
+                    &self.select,
+                    &output_field_rules[..output_size],
+                    input_schema,
+                )?;
+                // Hidden rules are placed right after the selected fields, so that
+                // they can be stripped from the output records
+                output_field_rules.splice(output_size..output_size, hidden_rules);
+                Ok((output_field_rules, Some(having), output_size))
+            }
+            None => Ok((output_field_rules, None, output_size)),
+        }
+    }
 }
 
 impl ProcessorFactory<SchemaSQLContext> for AggregationProcessorFactory {
@@ -82,11 +114,21 @@ impl ProcessorFactory<SchemaSQLContext> for AggregationProcessorFactory {
         let (input_schema, ctx) = input_schemas
             .get(&DEFAULT_PORT_HANDLE)
             .ok_or(ExecutionError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
-        let output_field_rules =
-            get_aggregation_rules(&self.select, &self.groupby, input_schema).unwrap();
-
-        if is_aggregation(&self.groupby, &output_field_rules) {
-            let output_schema = build_output_schema(input_schema, output_field_rules)?;
+        let (output_field_rules, having, output_size) = self
+            .get_rules(input_schema)
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+
+        if is_aggregation(&self.groupby, &output_field_rules, &having) {
+            let mut output_schema = build_output_schema(input_schema, &output_field_rules)?;
+            output_schema.fields.truncate(output_size);
+            // The GROUP BY key is lost if part of it is only used by the HAVING clause
+            if output_schema
+                .primary_index
+                .iter()
+                .any(|idx| *idx >= output_size)
+            {
+                output_schema.primary_index.clear();
+            }
             return Ok((output_schema, ctx.clone()));
         }
         build_projection_schema(input_schema, ctx, &self.select)
@@ -101,13 +143,17 @@ impl ProcessorFactory<SchemaSQLContext> for AggregationProcessorFactory {
             .get(&DEFAULT_PORT_HANDLE)
             .ok_or(ExecutionError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
         let input_schema = extend_schema_source_def(input_schema, &self.name);
-        let output_field_rules =
-            get_aggregation_rules(&self.select, &self.groupby, &input_schema).unwrap();
+        let (output_field_rules, having, output_size) = self
+            .get_rules(&input_schema)
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
 
-        if is_aggregation(&self.groupby, &output_field_rules) {
+        if is_aggregation(&self.groupby, &output_field_rules, &having) {
+            let having =
+                build_having_filter(having, &input_schema, &output_field_rules, output_size)?;
             return Ok(Box::new(AggregationProcessor::new(
                 output_field_rules,
                 input_schema,
+                having,
             )));
         }
 
@@ -155,8 +201,12 @@ impl ProcessorFactory<SchemaSQLContext> for AggregationProcessorFactory {
     }
 }
 
-fn is_aggregation(groupby: &[SqlExpr], output_field_rules: &[FieldRule]) -> bool {
-    if !groupby.is_empty() {
+fn is_aggregation(
+    groupby: &[SqlExpr],
+    output_field_rules: &[FieldRule],
+    having: &Option<SqlExpr>,
+) -> bool {
+    if !groupby.is_empty() || having.is_some() {
         return true;
     }
 
@@ -207,6 +257,197 @@ pub(crate) fn get_aggregation_rules(
     Ok(select_rules)
 }
 
+pub(crate) fn build_having_filter(
+    having: Option<SqlExpr>,
+    input_schema: &Schema,
+    output_field_rules: &[FieldRule],
+    output_size: usize,
+) -> Result<Option<HavingFilter>, ExecutionError> {
+    match having {
+        Some(having) => {
+            let schema = build_output_schema(input_schema, output_field_rules)?;
+            let expression = ExpressionBuilder {}
+                .build(&BuilderExpressionType::FullExpression, &having, &schema)
+                .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+            Ok(Some(HavingFilter::new(expression, schema, output_size)))
+        }
+        None => Ok(None),
+    }
+}
+
+fn get_having_rules(
+    having: &SqlExpr,
+    select: &[SelectItem],
+    select_rules: &[FieldRule],
+    schema: &Schema,
+) -> Result<(SqlExpr, Vec<FieldRule>), PipelineError> {
+    // Maps the expressions of the projection to the name of their output field
+    let mut output_names: HashMap<String, String> = select_rules
+        .iter()
+        .map(|rule| match rule {
+            FieldRule::Dimension(_, _, name) | FieldRule::Measure(_, _, name) => {
+                (name.clone(), name.clone())
+            }
+        })
+        .collect();
+    for item in select {
+        if let SelectItem::ExprWithAlias { expr, alias } = item {
+            output_names.insert(expr.to_string(), alias.value.clone());
+        }
+    }
+
+    let mut hidden_rules = vec![];
+    let having = rewrite_having_expr(having, &mut output_names, &mut hidden_rules, schema)?;
+    Ok((having, hidden_rules))
+}
+
+fn rewrite_having_expr(
+    expr: &SqlExpr,
+    output_names: &mut HashMap<String, String>,
+    hidden_rules: &mut Vec<FieldRule>,
+    schema: &Schema,
+) -> Result<SqlExpr, PipelineError> {
+    match expr {
+        SqlExpr::Function(function)
+            if AggregateFunctionType::new(&function.name.to_string().to_lowercase()).is_ok() =>
+        {
+            get_having_field(expr, output_names, hidden_rules, schema)
+        }
+        SqlExpr::Identifier(_) | SqlExpr::CompoundIdentifier(_) => {
+            get_having_field(expr, output_names, hidden_rules, schema)
+        }
+        SqlExpr::Function(function) => {
+            let mut function = function.clone();
+            for arg in function.args.iter_mut() {
+                if let FunctionArg::Unnamed(FunctionArgExpr::Expr(arg_expr))
+                | FunctionArg::Named {
+                    arg: FunctionArgExpr::Expr(arg_expr),
+                    ..
+                } = arg
+                {
+                    *arg_expr = rewrite_having_expr(arg_expr, output_names, hidden_rules, schema)?;
+                }
+            }
+            Ok(SqlExpr::Function(function))
+        }
+        SqlExpr::BinaryOp { left, op, right } => Ok(SqlExpr::BinaryOp {
+            left: Box::new(rewrite_having_expr(
+                left,
+                output_names,
+                hidden_rules,
+                schema,
+            )?),
+            op: op.clone(),
+            right: Box::new(rewrite_having_expr(
+                right,
+                output_names,
+                hidden_rules,
+                schema,
+            )?),
+        }),
+        SqlExpr::UnaryOp { op, expr } => Ok(SqlExpr::UnaryOp {
+            op: *op,
+            expr: Box::new(rewrite_having_expr(
+                expr,
+                output_names,
+                hidden_rules,
+                schema,
+            )?),
+        }),
+        SqlExpr::Nested(expr) => Ok(SqlExpr::Nested(Box::new(rewrite_having_expr(
+            expr,
+            output_names,
+            hidden_rules,
+            schema,
+        )?))),
+        SqlExpr::IsNull(expr) => Ok(SqlExpr::IsNull(Box::new(rewrite_having_expr(
+            expr,
+            output_names,
+            hidden_rules,
+            schema,
+        )?))),
+        SqlExpr::IsNotNull(expr) => Ok(SqlExpr::IsNotNull(Box::new(rewrite_having_expr(
+            expr,
+            output_names,
+            hidden_rules,
+            schema,
+        )?))),
+        SqlExpr::InList {
+            expr,
+            list,
+            negated,
+        } => Ok(SqlExpr::InList {
+            expr: Box::new(rewrite_having_expr(
+                expr,
+                output_names,
+                hidden_rules,
+                schema,
+            )?),
+            list: list
+                .iter()
+                .map(|item| rewrite_having_expr(item, output_names, hidden_rules, schema))
+                .collect::<Result<Vec<SqlExpr>, PipelineError>>()?,
+            negated: *negated,
+        }),
+        SqlExpr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => Ok(SqlExpr::Between {
+            expr: Box::new(rewrite_having_expr(
+                expr,
+                output_names,
+                hidden_rules,
+                schema,
+            )?),
+            negated: *negated,
+            low: Box::new(rewrite_having_expr(
+                low,
+                output_names,
+                hidden_rules,
+                schema,
+            )?),
+            high: Box::new(rewrite_having_expr(
+                high,
+                output_names,
+                hidden_rules,
+                schema,
+            )?),
+        }),
+        SqlExpr::Cast { expr, data_type } => Ok(SqlExpr::Cast {
+            expr: Box::new(rewrite_having_expr(
+                expr,
+                output_names,
+                hidden_rules,
+                schema,
+            )?),
+            data_type: data_type.clone(),
+        }),
+        _ => Ok(expr.clone()),
+    }
+}
+
+/// Returns a reference to the output field of `expr`, adding a hidden rule if it is not
+/// part of the projection
+fn get_having_field(
+    expr: &SqlExpr,
+    output_names: &mut HashMap<String, String>,
+    hidden_rules: &mut Vec<FieldRule>,
+    schema: &Schema,
+) -> Result<SqlExpr, PipelineError> {
+    let key = expr.to_string();
+    let name = match output_names.get(&key) {
+        Some(name) => name.clone(),
+        None => {
+            hidden_rules.push(build_field_rule(expr, schema, key.clone())?);
+            output_names.insert(key.clone(), key.clone());
+            key
+        }
+    };
+    Ok(SqlExpr::Identifier(Ident::new(name)))
+}
+
 fn build_field_rule(
     sql_expr: &Expr,
     schema: &Schema,
@@ -271,14 +512,21 @@ fn get_aggregator(
         Expression::AggregateFunction { fun, args } => {
             let arg_type = args[0].get_type(schema);
             match (&fun, arg_type) {
+                (AggregateFunctionType::ApproxCountDistinct, _) => {
+                    Ok(Aggregator::ApproxCountDistinct)
+                }
+                (AggregateFunctionType::ApproxPercentile, _) => Ok(Aggregator::ApproxPercentile(
+                    OrderedFloat(get_percentile(&args)?),
+                )),
                 (AggregateFunctionType::Avg, _) => Ok(Aggregator::Avg),
                 (AggregateFunctionType::Count, _) => Ok(Aggregator::Count),
+                (AggregateFunctionType::CountDistinct, _) => Ok(Aggregator::CountDistinct),
                 (AggregateFunctionType::Max, _) => Ok(Aggregator::Max),
+                (AggregateFunctionType::Median, _) => Ok(Aggregator::Median),
                 (AggregateFunctionType::Min, _) => Ok(Aggregator::Min),
+                (AggregateFunctionType::Stddev, _) => Ok(Aggregator::Stddev),
                 (AggregateFunctionType::Sum, _) => Ok(Aggregator::Sum),
-                _ => Err(PipelineError::InvalidExpression(format!(
-                    "Not implemented Aggregation function: {fun:?}"
-                ))),
+                (AggregateFunctionType::Variance, _) => Ok(Aggregator::Variance),
             }
         }
         _ => Err(PipelineError::InvalidExpression(format!(
@@ -289,7 +537,7 @@ fn get_aggregator(
 
 fn build_output_schema(
     input_schema: &Schema,
-    output_field_rules: Vec<FieldRule>,
+    output_field_rules: &[FieldRule],
 ) -> Result<Schema, ExecutionError> {
     let mut output_schema = Schema::empty();
     for e in output_field_rules.iter().enumerate() {
@@ -299,10 +547,13 @@ fn build_output_schema(
                     .get_type(input_schema)
                     .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
 
+                // Sample variance is undefined for a single value
+                let nullable =
+                    res.nullable || matches!(aggr, Aggregator::Stddev | Aggregator::Variance);
                 output_schema.fields.push(FieldDefinition::new(
                     name.clone(),
                     aggr.get_return_type(res.return_type),
-                    res.nullable,
+                    nullable,
                     res.source,
                 ));
             }
@@ -319,14 +570,37 @@ fn build_output_schema(
                         res.nullable,
                         res.source,
                     ));
-                    output_schema.primary_index.push(e.0);
                 }
             }
         }
     }
+    output_schema.primary_index = get_group_by_key(output_field_rules).unwrap_or_default();
     Ok(output_schema)
 }
 
+/// Returns the indexes of the output fields holding the GROUP BY expressions, which identify
+/// the output records, or `None` if some of the expressions are not part of the output
+fn get_group_by_key(output_field_rules: &[FieldRule]) -> Option<Vec<usize>> {
+    let mut key = vec![];
+    for rule in output_field_rules {
+        if let FieldRule::Dimension(expression, false, _) = rule {
+            let idx = output_field_rules
+                .iter()
+                .position(|output_rule| match output_rule {
+                    FieldRule::Dimension(output_expression, true, _) => {
+                        output_expression == expression
+                    }
+                    _ => false,
+                })?;
+            if !key.contains(&idx) {
+                key.push(idx);
+            }
+        }
+    }
+    key.sort();
+    Some(key)
+}
+
 fn build_projection_schema(
     input_schema: &Schema,
     context: &SchemaSQLContext,
@@ -350,12 +624,10 @@ fn build_projection_schema(
                     }
                 }
             }
-            _ => {
-                let res = parse_sql_select_item(s, input_schema);
-                if let Ok(..) = res {
-                    select_expr.push(res.unwrap())
-                }
-            }
+            _ => select_expr.push(
+                parse_sql_select_item(s, input_schema)
+                    .map_err(|e| ExecutionError::InternalError(Box::new(e)))?,
+            ),
         }
     }
 
@@ -375,6 +647,25 @@ fn build_projection_schema(
         ));
     }
     output_schema.fields = fields;
+    output_schema.primary_index = project_primary_index(input_schema, &select_expr);
 
     Ok((output_schema, context.clone()))
 }
+
+/// Maps the primary key of the input to the output fields selecting its columns as they are.
+/// The key is lost if one of its columns is not selected.
+fn project_primary_index(
+    input_schema: &Schema,
+    select_expr: &[(String, Expression)],
+) -> Vec<usize> {
+    input_schema
+        .primary_index
+        .iter()
+        .map(|idx| {
+            select_expr
+                .iter()
+                .position(|(_, expression)| *expression == Expression::Column { index: *idx })
+        })
+        .collect::<Option<Vec<usize>>>()
+        .unwrap_or_default()
+}
@@ -0,0 +1,340 @@
+use crate::deserialize;
+use crate::pipeline::aggregation::aggregator::AggregationResult;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::errors::PipelineError::InvalidOperandType;
+use crate::pipeline::sort_key::encode_sort_key;
+use dozer_core::storage::common::Database;
+use dozer_core::storage::errors::StorageError::InvalidRecord;
+use dozer_core::storage::prefix_transaction::PrefixTransaction;
+use dozer_types::errors::types::TypeError;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+use std::cmp::Ordering;
+use std::ops::Div;
+use std::string::ToString;
+
+pub struct MedianAggregator {}
+const AGGREGATOR_NAME: &str = "MEDIAN";
+
+/// Position of the lower median among the values of a group, sorted in aggregators_db by
+/// their sort key: its sort key and its index among the occurrences of its value
+#[derive(Debug, Clone)]
+struct Position {
+    key: Vec<u8>,
+    index: u64,
+}
+
+/// State of the median of a group: the number of values and the position of the lower
+/// median, which moves by at most one value on each change
+#[derive(Debug, Default)]
+struct MedianState {
+    count: u64,
+    lower: Option<Position>,
+}
+
+impl MedianAggregator {
+    const _AGGREGATOR_ID: u32 = 0x06;
+
+    pub(crate) fn get_return_type(from: FieldType) -> FieldType {
+        match from {
+            FieldType::Decimal => FieldType::Decimal,
+            FieldType::Float => FieldType::Float,
+            FieldType::Int => FieldType::Decimal,
+            FieldType::UInt => FieldType::Decimal,
+            _ => from,
+        }
+    }
+
+    pub(crate) fn _get_type() -> u32 {
+        MedianAggregator::_AGGREGATOR_ID
+    }
+
+    pub(crate) fn insert(
+        cur_state: Option<&[u8]>,
+        new: &Field,
+        return_type: FieldType,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<AggregationResult, PipelineError> {
+        Self::validate_type(return_type)?;
+        let mut state = MedianState::decode(cur_state);
+        Self::insert_value(&mut state, new, ptx, aggregators_db)?;
+        Self::get_result(&state, return_type, ptx, aggregators_db)
+    }
+
+    pub(crate) fn update(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        new: &Field,
+        return_type: FieldType,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<AggregationResult, PipelineError> {
+        Self::validate_type(return_type)?;
+        let mut state = MedianState::decode(cur_state);
+        Self::delete_value(&mut state, old, ptx, aggregators_db)?;
+        Self::insert_value(&mut state, new, ptx, aggregators_db)?;
+        Self::get_result(&state, return_type, ptx, aggregators_db)
+    }
+
+    pub(crate) fn delete(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        return_type: FieldType,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<AggregationResult, PipelineError> {
+        Self::validate_type(return_type)?;
+        let mut state = MedianState::decode(cur_state);
+        Self::delete_value(&mut state, old, ptx, aggregators_db)?;
+        Self::get_result(&state, return_type, ptx, aggregators_db)
+    }
+
+    fn validate_type(return_type: FieldType) -> Result<(), PipelineError> {
+        match return_type {
+            FieldType::Decimal | FieldType::Float | FieldType::Int | FieldType::UInt => Ok(()),
+            _ => Err(InvalidOperandType(AGGREGATOR_NAME.to_string())),
+        }
+    }
+
+    /// Adds `value` to aggregators_db and moves the lower median to index `(count - 1) / 2`.
+    /// A value equal to the lower median is added after it.
+    fn insert_value(
+        state: &mut MedianState,
+        value: &Field,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<(), PipelineError> {
+        if value == &Field::Null {
+            return Ok(());
+        }
+        let key = encode_sort_key(value, false, false);
+        Self::update_count(&key, value, false, ptx, aggregators_db)?;
+
+        let count = state.count;
+        state.count += 1;
+        let Some(lower) = state.lower.take() else {
+            state.lower = Some(Position { key, index: 0 });
+            return Ok(());
+        };
+        // The new lower median index is one more than the old one if the count was even
+        let inserted_before = key < lower.key;
+        let odd = count % 2 == 1;
+        state.lower = Some(match (inserted_before, odd) {
+            (true, false) | (false, true) => lower,
+            (true, true) => Self::step_back(&lower, ptx, aggregators_db)?,
+            (false, false) => Self::step_forward(&lower, ptx, aggregators_db)?,
+        });
+        Ok(())
+    }
+
+    /// Removes `value` from aggregators_db and moves the lower median to index
+    /// `(count - 1) / 2`. The last occurrence of a value is removed.
+    fn delete_value(
+        state: &mut MedianState,
+        value: &Field,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<(), PipelineError> {
+        if value == &Field::Null {
+            return Ok(());
+        }
+        let key = encode_sort_key(value, false, false);
+        let Some(lower) = state.lower.take() else {
+            return Ok(());
+        };
+        let count = state.count;
+        if count <= 1 {
+            Self::update_count(&key, value, true, ptx, aggregators_db)?;
+            *state = MedianState::default();
+            return Ok(());
+        }
+
+        let removed = match key.cmp(&lower.key) {
+            Ordering::Less => Ordering::Less,
+            Ordering::Greater => Ordering::Greater,
+            Ordering::Equal => {
+                if lower.index + 1 < Self::get_count(&lower.key, ptx, aggregators_db)? {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            }
+        };
+        // The lower median itself is removed: its successor takes its index
+        let lower = match removed {
+            Ordering::Equal => Self::step_forward(&lower, ptx, aggregators_db)?,
+            _ => lower,
+        };
+        Self::update_count(&key, value, true, ptx, aggregators_db)?;
+        state.count -= 1;
+
+        // The new lower median index is one less than the old one if the count was odd
+        let odd = count % 2 == 1;
+        state.lower = Some(match (removed, odd) {
+            (Ordering::Less, true) | (Ordering::Greater, false) | (Ordering::Equal, false) => lower,
+            (Ordering::Less, false) => Self::step_forward(&lower, ptx, aggregators_db)?,
+            (Ordering::Greater, true) | (Ordering::Equal, true) => {
+                Self::step_back(&lower, ptx, aggregators_db)?
+            }
+        });
+        Ok(())
+    }
+
+    /// Position of the value following `position`
+    fn step_forward(
+        position: &Position,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<Position, PipelineError> {
+        if position.index + 1 < Self::get_count(&position.key, ptx, aggregators_db)? {
+            return Ok(Position {
+                key: position.key.clone(),
+                index: position.index + 1,
+            });
+        }
+        let cursor = ptx.open_cursor(aggregators_db)?;
+        if !cursor.seek(&position.key)? || !cursor.next()? {
+            return Err(PipelineError::InternalStorageError(InvalidRecord));
+        }
+        let (key, _) = cursor
+            .read()?
+            .ok_or(PipelineError::InternalStorageError(InvalidRecord))?;
+        Ok(Position {
+            key: key.to_vec(),
+            index: 0,
+        })
+    }
+
+    /// Position of the value preceding `position`
+    fn step_back(
+        position: &Position,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<Position, PipelineError> {
+        if position.index > 0 {
+            return Ok(Position {
+                key: position.key.clone(),
+                index: position.index - 1,
+            });
+        }
+        let cursor = ptx.open_cursor(aggregators_db)?;
+        if !cursor.seek(&position.key)? || !cursor.prev()? {
+            return Err(PipelineError::InternalStorageError(InvalidRecord));
+        }
+        let (key, value) = cursor
+            .read()?
+            .ok_or(PipelineError::InternalStorageError(InvalidRecord))?;
+        Ok(Position {
+            key: key.to_vec(),
+            index: u64::from_be_bytes(deserialize!(&value[0..8])) - 1,
+        })
+    }
+
+    /// Number of occurrences of the value with the sort key `key`
+    fn get_count(
+        key: &[u8],
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<u64, PipelineError> {
+        Ok(ptx
+            .get(aggregators_db, key)?
+            .map_or(0, |value| u64::from_be_bytes(deserialize!(&value[0..8]))))
+    }
+
+    /// Updates the occurrences of `value` in aggregators_db, stored with the value itself
+    fn update_count(
+        key: &[u8],
+        value: &Field,
+        decr: bool,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<(), PipelineError> {
+        let prev_count = Self::get_count(key, ptx, aggregators_db)?;
+        let new_count = if decr {
+            prev_count.saturating_sub(1)
+        } else {
+            prev_count + 1
+        };
+        if new_count == 0 {
+            ptx.del(aggregators_db, key, None)?;
+        } else {
+            let mut stored = new_count.to_be_bytes().to_vec();
+            stored.extend(value.encode());
+            ptx.put(aggregators_db, key, &stored)?;
+        }
+        Ok(())
+    }
+
+    fn get_value(
+        position: &Position,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<Field, PipelineError> {
+        let stored = ptx
+            .get(aggregators_db, &position.key)?
+            .ok_or(PipelineError::InternalStorageError(InvalidRecord))?;
+        let value = Field::decode(&stored[8..]).map_err(TypeError::DeserializationError)?;
+        Ok(value)
+    }
+
+    fn get_result(
+        state: &MedianState,
+        return_type: FieldType,
+        ptx: &mut PrefixTransaction,
+        aggregators_db: Database,
+    ) -> Result<AggregationResult, PipelineError> {
+        let Some(lower_position) = &state.lower else {
+            return Ok(AggregationResult::new(Field::Null, Some(state.encode())));
+        };
+        let lower = Self::get_value(lower_position, ptx, aggregators_db)?;
+        let upper = if state.count % 2 == 1 {
+            lower.clone()
+        } else {
+            let upper_position = Self::step_forward(lower_position, ptx, aggregators_db)?;
+            Self::get_value(&upper_position, ptx, aggregators_db)?
+        };
+
+        let median = match return_type {
+            FieldType::Float => {
+                let lower = lower.to_float().unwrap();
+                let upper = upper.to_float().unwrap();
+                Field::Float(OrderedFloat((lower + upper) / 2_f64))
+            }
+            _ => {
+                let lower = lower.to_decimal().unwrap();
+                let upper = upper.to_decimal().unwrap();
+                Field::Decimal((lower + upper).div(dozer_types::rust_decimal::Decimal::from(2)))
+            }
+        };
+        Ok(AggregationResult::new(median, Some(state.encode())))
+    }
+}
+
+impl MedianState {
+    fn decode(state: Option<&[u8]>) -> Self {
+        match state {
+            Some(state) if state.len() > 16 => Self {
+                count: u64::from_be_bytes(deserialize!(&state[0..8])),
+                lower: Some(Position {
+                    index: u64::from_be_bytes(deserialize!(&state[8..16])),
+                    key: state[16..].to_vec(),
+                }),
+            },
+            _ => Self::default(),
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        match &self.lower {
+            Some(lower) => {
+                let mut buf = Vec::with_capacity(16 + lower.key.len());
+                buf.extend(self.count.to_be_bytes());
+                buf.extend(lower.index.to_be_bytes());
+                buf.extend(&lower.key);
+                buf
+            }
+            None => vec![],
+        }
+    }
+}
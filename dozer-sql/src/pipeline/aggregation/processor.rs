@@ -45,6 +45,76 @@ pub enum FieldRule {
     ),
 }
 
+/// Filter of the HAVING clause, evaluated over the aggregated records
+#[derive(Debug)]
+pub struct HavingFilter {
+    /// HAVING predicate over the aggregated fields, including the hidden ones
+    expression: Box<Expression>,
+    /// Schema of the aggregated records, including the hidden fields
+    schema: Schema,
+    /// Number of aggregated fields sent to the next node. Hidden fields follow them
+    output_size: usize,
+}
+
+impl HavingFilter {
+    pub fn new(expression: Box<Expression>, schema: Schema, output_size: usize) -> Self {
+        Self {
+            expression,
+            schema,
+            output_size,
+        }
+    }
+
+    fn matches(&self, record: &Record) -> Result<bool, PipelineError> {
+        Ok(self.expression.evaluate(record, &self.schema)? == Field::Boolean(true))
+    }
+
+    fn strip(&self, mut record: Record) -> Record {
+        record.values.truncate(self.output_size);
+        record
+    }
+
+    /// Converts the aggregation results according to the groups entering or leaving
+    /// the HAVING condition
+    fn apply(&self, ops: Vec<Operation>) -> Result<Vec<Operation>, PipelineError> {
+        let mut res = Vec::with_capacity(ops.len());
+        for op in ops {
+            match op {
+                Operation::Insert { new } => {
+                    if self.matches(&new)? {
+                        res.push(Operation::Insert {
+                            new: self.strip(new),
+                        });
+                    }
+                }
+                Operation::Delete { old } => {
+                    if self.matches(&old)? {
+                        res.push(Operation::Delete {
+                            old: self.strip(old),
+                        });
+                    }
+                }
+                Operation::Update { old, new } => {
+                    match (self.matches(&old)?, self.matches(&new)?) {
+                        (true, true) => res.push(Operation::Update {
+                            old: self.strip(old),
+                            new: self.strip(new),
+                        }),
+                        (true, false) => res.push(Operation::Delete {
+                            old: self.strip(old),
+                        }),
+                        (false, true) => res.push(Operation::Insert {
+                            new: self.strip(new),
+                        }),
+                        (false, false) => {}
+                    }
+                }
+            }
+        }
+        Ok(res)
+    }
+}
+
 const COUNTER_KEY: u8 = 1_u8;
 
 pub(crate) struct AggregationData<'a> {
@@ -71,6 +141,7 @@ pub struct AggregationProcessor {
     meta_db: Option<Database>,
     aggregators_db: Option<Database>,
     input_schema: Schema,
+    having: Option<HavingFilter>,
 }
 
 enum AggregatorOperation {
@@ -85,7 +156,11 @@ const AGG_COUNT_DATASET_ID: u16 = 0x0001_u16;
 const AGG_DEFAULT_DIMENSION_ID: u8 = 0xFF_u8;
 
 impl AggregationProcessor {
-    pub fn new(output_field_rules: Vec<FieldRule>, input_schema: Schema) -> Self {
+    pub fn new(
+        output_field_rules: Vec<FieldRule>,
+        input_schema: Schema,
+        having: Option<HavingFilter>,
+    ) -> Self {
         let (out_measures, out_dimensions) = populate_rules(&output_field_rules).unwrap();
         Self {
             out_dimensions,
@@ -94,6 +169,7 @@ impl AggregationProcessor {
             meta_db: None,
             aggregators_db: None,
             input_schema,
+            having,
         }
     }
 
@@ -485,6 +561,19 @@ impl AggregationProcessor {
         txn: &mut LmdbExclusiveTransaction,
         db: Database,
         op: Operation,
+    ) -> Result<Vec<Operation>, PipelineError> {
+        let ops = self.aggregate_groups(txn, db, op)?;
+        match &self.having {
+            Some(having) => having.apply(ops),
+            None => Ok(ops),
+        }
+    }
+
+    fn aggregate_groups(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        db: Database,
+        op: Operation,
     ) -> Result<Vec<Operation>, PipelineError> {
         match op {
             Operation::Insert { ref new } => Ok(vec![self.agg_insert(txn, db, new)?]),
@@ -0,0 +1,62 @@
+use crate::pipeline::aggregation::aggregator::AggregationResult;
+use crate::pipeline::aggregation::variance::{Moments, VarianceAggregator};
+use crate::pipeline::errors::PipelineError;
+use dozer_core::storage::prefix_transaction::PrefixTransaction;
+use dozer_types::types::{Field, FieldType};
+
+pub struct StddevAggregator {}
+const AGGREGATOR_NAME: &str = "STDDEV";
+
+impl StddevAggregator {
+    const _AGGREGATOR_ID: u32 = 0x08;
+
+    pub(crate) fn get_return_type(_from: FieldType) -> FieldType {
+        FieldType::Float
+    }
+
+    pub(crate) fn _get_type() -> u32 {
+        StddevAggregator::_AGGREGATOR_ID
+    }
+
+    pub(crate) fn insert(
+        cur_state: Option<&[u8]>,
+        new: &Field,
+        return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        VarianceAggregator::validate_type(return_type, AGGREGATOR_NAME)?;
+        let mut moments = Moments::decode(cur_state);
+        moments.insert(new);
+        Ok(Self::get_result(&moments))
+    }
+
+    pub(crate) fn update(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        new: &Field,
+        return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        VarianceAggregator::validate_type(return_type, AGGREGATOR_NAME)?;
+        let mut moments = Moments::decode(cur_state);
+        moments.delete(old);
+        moments.insert(new);
+        Ok(Self::get_result(&moments))
+    }
+
+    pub(crate) fn delete(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        VarianceAggregator::validate_type(return_type, AGGREGATOR_NAME)?;
+        let mut moments = Moments::decode(cur_state);
+        moments.delete(old);
+        Ok(Self::get_result(&moments))
+    }
+
+    fn get_result(moments: &Moments) -> AggregationResult {
+        VarianceAggregator::get_result(moments, moments.variance().map(f64::sqrt))
+    }
+}
@@ -1,16 +1,28 @@
 #[cfg(test)]
+mod aggregation_approx_tests;
+#[cfg(test)]
 mod aggregation_avg_tests;
 #[cfg(test)]
+mod aggregation_count_distinct_tests;
+#[cfg(test)]
 mod aggregation_count_tests;
 #[cfg(test)]
+mod aggregation_having_tests;
+#[cfg(test)]
 mod aggregation_max_tests;
 #[cfg(test)]
+mod aggregation_median_tests;
+#[cfg(test)]
 mod aggregation_min_tests;
 #[cfg(test)]
 mod aggregation_null;
 #[cfg(test)]
+mod aggregation_stddev_tests;
+#[cfg(test)]
 mod aggregation_sum_tests;
 #[cfg(test)]
 mod aggregation_tests_utils;
 #[cfg(test)]
+mod aggregation_variance_tests;
+#[cfg(test)]
 mod encode_decode;
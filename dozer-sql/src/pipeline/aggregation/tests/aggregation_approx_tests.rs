@@ -0,0 +1,261 @@
+use crate::output;
+use crate::pipeline::aggregation::factory::AggregationProcessorFactory;
+use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
+    delete_exp, delete_field, init_input_schema, init_processor, insert_exp, insert_field,
+    update_exp, update_field, FIELD_100_FLOAT, FIELD_100_INT, FIELD_1_INT, FIELD_200_INT,
+    FIELD_2_INT, FIELD_50_FLOAT, FIELD_50_INT, FIELD_75_FLOAT, FIELD_NULL, ITALY, SINGAPORE,
+};
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::expression::builder::NameOrAlias;
+use crate::pipeline::tests::utils::get_select;
+use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
+use dozer_core::dag::node::ProcessorFactory;
+use dozer_types::types::FieldType::{Float, Int};
+use dozer_types::types::{Field, Operation};
+use std::collections::HashMap;
+
+fn get_value(ops: &[Operation]) -> f64 {
+    match ops.last() {
+        Some(Operation::Insert { new } | Operation::Update { new, .. }) => {
+            new.values[1].to_float().unwrap()
+        }
+        _ => panic!("expected an insert or an update, got {ops:?}"),
+    }
+}
+
+#[test]
+fn test_approx_count_distinct_aggregation() {
+    let schema = init_input_schema(Int, "APPROX_COUNT_DISTINCT");
+    let (processor, tx) = init_processor(
+        "SELECT Country, APPROX_COUNT_DISTINCT(Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Small cardinalities are exact
+    let mut inp = insert_field(ITALY, FIELD_100_INT);
+    let mut out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    inp = insert_field(ITALY, FIELD_100_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    inp = insert_field(ITALY, FIELD_50_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_2_INT)];
+    assert_eq!(out, exp);
+
+    inp = insert_field(ITALY, FIELD_NULL);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_2_INT, FIELD_2_INT)];
+    assert_eq!(out, exp);
+
+    // Update Italy value 50 -> 100
+    inp = update_field(ITALY, ITALY, FIELD_50_INT, FIELD_100_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_2_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Update Italy value 100 -> 200 moving it to Singapore
+    inp = update_field(ITALY, SINGAPORE, FIELD_100_INT, FIELD_200_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![
+        update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_1_INT),
+        insert_exp(SINGAPORE, FIELD_1_INT),
+    ];
+    assert_eq!(out, exp);
+
+    inp = delete_field(SINGAPORE, FIELD_200_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![delete_exp(SINGAPORE, FIELD_1_INT)];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_approx_count_distinct_cardinality() {
+    let schema = init_input_schema(Int, "APPROX_COUNT_DISTINCT");
+    let (processor, tx) = init_processor(
+        "SELECT Country, APPROX_COUNT_DISTINCT(Salary) FROM Users GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let mut out = vec![];
+    for salary in 0..5000 {
+        let inp = insert_field(ITALY, &Field::Int(salary % 4000));
+        out = output!(processor, inp, tx);
+    }
+    let estimate = get_value(&out);
+    assert!((estimate - 4000.0).abs() < 4000.0 * 0.05, "{estimate}");
+
+    for salary in 0..2000 {
+        let inp = delete_field(ITALY, &Field::Int(salary));
+        out = output!(processor, inp, tx);
+    }
+    // 0 to 999 and 2000 to 3999 are left
+    let estimate = get_value(&out);
+    assert!((estimate - 3000.0).abs() < 3000.0 * 0.05, "{estimate}");
+}
+
+#[test]
+fn test_approx_count_distinct_heavy_deletes() {
+    let schema = init_input_schema(Int, "APPROX_COUNT_DISTINCT");
+    let (processor, tx) = init_processor(
+        "SELECT Country, APPROX_COUNT_DISTINCT(Salary) FROM Users GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let mut out = vec![];
+    for salary in 0..10000 {
+        let inp = insert_field(ITALY, &Field::Int(salary));
+        out = output!(processor, inp, tx);
+    }
+    let estimate = get_value(&out);
+    assert!((estimate - 10000.0).abs() < 10000.0 * 0.05, "{estimate}");
+
+    // Deleting most values leaves the registers holding only low ranks
+    for salary in 0..9000 {
+        let inp = delete_field(ITALY, &Field::Int(salary));
+        out = output!(processor, inp, tx);
+    }
+    let estimate = get_value(&out);
+    assert!((estimate - 1000.0).abs() < 1000.0 * 0.05, "{estimate}");
+
+    for salary in 9000..9900 {
+        let inp = delete_field(ITALY, &Field::Int(salary));
+        out = output!(processor, inp, tx);
+    }
+    let estimate = get_value(&out);
+    assert!((estimate - 100.0).abs() < 100.0 * 0.05, "{estimate}");
+}
+
+#[test]
+fn test_approx_percentile_aggregation() {
+    let schema = init_input_schema(Float, "APPROX_PERCENTILE");
+    let (processor, tx) = init_processor(
+        "SELECT Country, APPROX_PERCENTILE(Salary, 0.5) FROM Users GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let mut inp = insert_field(ITALY, FIELD_100_FLOAT);
+    let mut out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, FIELD_100_FLOAT)];
+    assert_eq!(out, exp);
+
+    inp = insert_field(ITALY, FIELD_50_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_100_FLOAT, FIELD_75_FLOAT)];
+    assert_eq!(out, exp);
+
+    inp = insert_field(ITALY, FIELD_NULL);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_75_FLOAT, FIELD_75_FLOAT)];
+    assert_eq!(out, exp);
+
+    inp = delete_field(ITALY, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_75_FLOAT, FIELD_50_FLOAT)];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_approx_percentile_accuracy() {
+    let schema = init_input_schema(Int, "APPROX_PERCENTILE");
+    let (processor, tx) = init_processor(
+        "SELECT Country, APPROX_PERCENTILE(Salary, 0.95) FROM Users GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Inserted out of order, so the digest gets compressed several times
+    let mut out = vec![];
+    for salary in 0..5000 {
+        let inp = insert_field(ITALY, &Field::Int((salary * 7919) % 5000 + 1));
+        out = output!(processor, inp, tx);
+    }
+    let estimate = get_value(&out);
+    assert!((estimate - 4750.0).abs() < 5000.0 * 0.01, "{estimate}");
+
+    for salary in 2501..=5000 {
+        let inp = delete_field(ITALY, &Field::Int(salary));
+        out = output!(processor, inp, tx);
+    }
+    let estimate = get_value(&out);
+    assert!((estimate - 2375.0).abs() < 2500.0 * 0.05, "{estimate}");
+}
+
+#[test]
+fn test_approx_percentile_deletes() {
+    let schema = init_input_schema(Int, "APPROX_PERCENTILE");
+    let (processor, tx) = init_processor(
+        "SELECT Country, APPROX_PERCENTILE(Salary, 0.5) FROM Users GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let mut out = vec![];
+    for salary in 0..2000 {
+        let inp = insert_field(ITALY, &Field::Int((salary * 7919) % 2000 + 1));
+        out = output!(processor, inp, tx);
+    }
+    let estimate = get_value(&out);
+    assert!((estimate - 1000.0).abs() < 2000.0 * 0.01, "{estimate}");
+
+    // Deleting the lower half moves the median to the middle of the upper half
+    for salary in 1..=1000 {
+        let inp = delete_field(ITALY, &Field::Int(salary));
+        out = output!(processor, inp, tx);
+    }
+    let estimate = get_value(&out);
+    assert!((estimate - 1500.0).abs() < 1000.0 * 0.05, "{estimate}");
+
+    // Inserting the lower half again restores the median
+    for salary in 1..=1000 {
+        let inp = insert_field(ITALY, &Field::Int(salary));
+        out = output!(processor, inp, tx);
+    }
+    let estimate = get_value(&out);
+    assert!((estimate - 1000.0).abs() < 2000.0 * 0.02, "{estimate}");
+
+    // Deleting every value empties the digest
+    for salary in 1..=2000 {
+        let inp = delete_field(ITALY, &Field::Int(salary));
+        out = output!(processor, inp, tx);
+    }
+    assert!(
+        matches!(out.last(), Some(Operation::Delete { .. })),
+        "{out:?}"
+    );
+}
+
+#[test]
+fn test_approx_percentile_arguments() {
+    for sql in [
+        "SELECT Country, APPROX_PERCENTILE(Salary) FROM Users GROUP BY Country",
+        "SELECT Country, APPROX_PERCENTILE(Salary, 1.5) FROM Users GROUP BY Country",
+        "SELECT Country, APPROX_PERCENTILE(Salary, Salary) FROM Users GROUP BY Country",
+        "SELECT Country, APPROX_PERCENTILE(Salary, 0.5, 0.9) FROM Users GROUP BY Country",
+    ] {
+        let select = get_select(sql).unwrap();
+        let factory = AggregationProcessorFactory::new(
+            NameOrAlias(select.from[0].relation.to_string(), None),
+            select.projection,
+            select.group_by,
+            select.having,
+            false,
+        );
+        let schema = init_input_schema(Float, "APPROX_PERCENTILE");
+        let result = factory.get_output_schema(
+            &DEFAULT_PORT_HANDLE,
+            &HashMap::from([(DEFAULT_PORT_HANDLE, (schema, SchemaSQLContext::default()))]),
+        );
+        assert!(result.is_err(), "{sql}");
+    }
+}
@@ -0,0 +1,115 @@
+use crate::output;
+use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
+    delete_exp, delete_field, init_input_schema, init_processor, insert_exp, insert_field,
+    update_exp, update_field, FIELD_100_INT, FIELD_1_INT, FIELD_200_INT, FIELD_2_INT, FIELD_50_INT,
+    FIELD_NULL, ITALY, SINGAPORE,
+};
+use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
+use dozer_types::types::FieldType::Int;
+use std::collections::HashMap;
+
+#[test]
+fn test_count_distinct_aggregation_int() {
+    let schema = init_input_schema(Int, "COUNT");
+    let (processor, tx) = init_processor(
+        "SELECT Country, COUNT(DISTINCT Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 100
+        -------------
+        COUNT DISTINCT = 1
+    */
+    let mut inp = insert_field(ITALY, FIELD_100_INT);
+    let mut out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Insert another 100 for segment Italy
+    /*
+        Italy, 100
+        Italy, 100
+        -------------
+        COUNT DISTINCT = 1
+    */
+    inp = insert_field(ITALY, FIELD_100_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Insert 50 for segment Italy
+    /*
+        Italy, 100
+        Italy, 100
+        Italy, 50
+        -------------
+        COUNT DISTINCT = 2
+    */
+    inp = insert_field(ITALY, FIELD_50_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_2_INT)];
+    assert_eq!(out, exp);
+
+    // Insert null for segment Italy
+    /*
+        Italy, 100
+        Italy, 100
+        Italy, 50
+        Italy, NULL
+        -------------
+        COUNT DISTINCT = 2
+    */
+    inp = insert_field(ITALY, FIELD_NULL);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_2_INT, FIELD_2_INT)];
+    assert_eq!(out, exp);
+
+    // Update Italy value 50 -> 100
+    /*
+        Italy, 100
+        Italy, 100
+        Italy, 100
+        Italy, NULL
+        -------------
+        COUNT DISTINCT = 1
+    */
+    inp = update_field(ITALY, ITALY, FIELD_50_INT, FIELD_100_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_2_INT, FIELD_1_INT)];
+    assert_eq!(out, exp);
+
+    // Update Italy value 100 -> 200 moving it to Singapore
+    /*
+        Italy, 100
+        Italy, 100
+        Italy, NULL
+        -------------
+        COUNT DISTINCT = 1
+
+        Singapore, 200
+        -------------
+        COUNT DISTINCT = 1
+    */
+    inp = update_field(ITALY, SINGAPORE, FIELD_100_INT, FIELD_200_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![
+        update_exp(ITALY, ITALY, FIELD_1_INT, FIELD_1_INT),
+        insert_exp(SINGAPORE, FIELD_1_INT),
+    ];
+    assert_eq!(out, exp);
+
+    // Delete Singapore record
+    /*
+        -------------
+        COUNT DISTINCT = 0
+    */
+    inp = delete_field(SINGAPORE, FIELD_200_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![delete_exp(SINGAPORE, FIELD_1_INT)];
+    assert_eq!(out, exp);
+}
@@ -0,0 +1,173 @@
+use crate::output;
+use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
+    delete_exp, delete_field, init_input_schema, init_processor, insert_exp, insert_field,
+    update_exp, update_field, FIELD_100_FLOAT, FIELD_150_FLOAT, FIELD_200_FLOAT, FIELD_250_FLOAT,
+    FIELD_50_FLOAT, ITALY, SINGAPORE,
+};
+use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
+use dozer_types::types::FieldType::Float;
+use std::collections::HashMap;
+
+#[test]
+fn test_having_aggregation() {
+    let schema = init_input_schema(Float, "SUM");
+    let (processor, tx) = init_processor(
+        "SELECT Country, SUM(Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country HAVING SUM(Salary) > 150",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 100.0
+        -------------
+        SUM = 100.0 (filtered out)
+    */
+    let mut inp = insert_field(ITALY, FIELD_100_FLOAT);
+    let mut out = output!(processor, inp, tx);
+    assert_eq!(out, vec![]);
+
+    // Insert another 100 for segment Italy
+    /*
+        Italy, 100.0
+        Italy, 100.0
+        -------------
+        SUM = 200.0
+    */
+    inp = insert_field(ITALY, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, FIELD_200_FLOAT)];
+    assert_eq!(out, exp);
+
+    // Insert 50 for segment Singapore
+    /*
+        Singapore, 50.0
+        ---------------
+        SUM = 50.0 (filtered out)
+    */
+    inp = insert_field(SINGAPORE, FIELD_50_FLOAT);
+    out = output!(processor, inp, tx);
+    assert_eq!(out, vec![]);
+
+    // Update Italy value 100 -> 150
+    /*
+        Italy, 100.0
+        Italy, 150.0
+        -------------
+        SUM = 250.0
+    */
+    inp = update_field(ITALY, ITALY, FIELD_100_FLOAT, FIELD_150_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_200_FLOAT, FIELD_250_FLOAT)];
+    assert_eq!(out, exp);
+
+    // Move Italy value 100 to Singapore
+    /*
+        Italy, 150.0
+        -------------
+        SUM = 150.0 (filtered out)
+
+        Singapore, 50.0
+        Singapore, 100.0
+        ---------------
+        SUM = 150.0 (filtered out)
+    */
+    inp = update_field(ITALY, SINGAPORE, FIELD_100_FLOAT, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![delete_exp(ITALY, FIELD_250_FLOAT)];
+    assert_eq!(out, exp);
+
+    // Insert 100 for segment Singapore
+    /*
+        Singapore, 50.0
+        Singapore, 100.0
+        Singapore, 100.0
+        ---------------
+        SUM = 250.0
+    */
+    inp = insert_field(SINGAPORE, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![insert_exp(SINGAPORE, FIELD_250_FLOAT)];
+    assert_eq!(out, exp);
+
+    // Delete 100 for segment Singapore
+    /*
+        Singapore, 50.0
+        Singapore, 100.0
+        ---------------
+        SUM = 150.0 (filtered out)
+    */
+    inp = delete_field(SINGAPORE, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![delete_exp(SINGAPORE, FIELD_250_FLOAT)];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_having_hidden_aggregation() {
+    let schema = init_input_schema(Float, "SUM");
+    let (processor, tx) = init_processor(
+        "SELECT Country, SUM(Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country HAVING COUNT(Salary) > 1",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 100.0
+        -------------
+        COUNT = 1 (filtered out)
+    */
+    let mut inp = insert_field(ITALY, FIELD_100_FLOAT);
+    let mut out = output!(processor, inp, tx);
+    assert_eq!(out, vec![]);
+
+    // Insert 50 for segment Italy, COUNT is not part of the output
+    /*
+        Italy, 100.0
+        Italy, 50.0
+        -------------
+        SUM = 150.0
+        COUNT = 2
+    */
+    inp = insert_field(ITALY, FIELD_50_FLOAT);
+    out = output!(processor, inp, tx);
+    let exp = vec![insert_exp(ITALY, FIELD_150_FLOAT)];
+    assert_eq!(out, exp);
+
+    // Delete 100 for segment Italy
+    /*
+        Italy, 50.0
+        -------------
+        COUNT = 1 (filtered out)
+    */
+    inp = delete_field(ITALY, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    let exp = vec![delete_exp(ITALY, FIELD_150_FLOAT)];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_having_alias_and_dimension() {
+    let schema = init_input_schema(Float, "SUM");
+    let (processor, tx) = init_processor(
+        "SELECT Country, SUM(Salary) AS total \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country HAVING Country = 'Italy' AND SUM(Salary) >= 100",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    let mut inp = insert_field(SINGAPORE, FIELD_100_FLOAT);
+    let mut out = output!(processor, inp, tx);
+    assert_eq!(out, vec![]);
+
+    inp = insert_field(ITALY, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    let exp = vec![insert_exp(ITALY, FIELD_100_FLOAT)];
+    assert_eq!(out, exp);
+}
@@ -0,0 +1,285 @@
+use crate::output;
+use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
+    delete_exp, delete_field, get_decimal_div_field, get_decimal_field, init_input_schema,
+    init_processor, insert_exp, insert_field, update_exp, update_field, FIELD_100_FLOAT,
+    FIELD_100_INT, FIELD_150_FLOAT, FIELD_200_FLOAT, FIELD_200_INT, FIELD_350_FLOAT,
+    FIELD_50_FLOAT, FIELD_50_INT, FIELD_75_FLOAT, FIELD_NULL, ITALY, SINGAPORE,
+};
+use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::FieldType::{Float, Int};
+use dozer_types::types::{Field, Operation};
+use std::collections::HashMap;
+
+#[test]
+fn test_median_aggregation_float() {
+    let schema = init_input_schema(Float, "MEDIAN");
+    let (processor, tx) = init_processor(
+        "SELECT Country, MEDIAN(Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 100.0
+        -------------
+        MEDIAN = 100.0
+    */
+    let mut inp = insert_field(ITALY, FIELD_100_FLOAT);
+    let mut out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, FIELD_100_FLOAT)];
+    assert_eq!(out, exp);
+
+    // Insert 200 for segment Italy
+    /*
+        Italy, 100.0
+        Italy, 200.0
+        -------------
+        MEDIAN = 150.0
+    */
+    inp = insert_field(ITALY, FIELD_200_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_100_FLOAT, FIELD_150_FLOAT)];
+    assert_eq!(out, exp);
+
+    // Insert 50 for segment Singapore
+    /*
+        Singapore, 50.0
+        -------------
+        MEDIAN = 50.0
+    */
+    inp = insert_field(SINGAPORE, FIELD_50_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![insert_exp(SINGAPORE, FIELD_50_FLOAT)];
+    assert_eq!(out, exp);
+
+    // Update Singapore segment to Italy
+    /*
+        Italy, 100.0
+        Italy, 200.0
+        Italy, 50.0
+        -------------
+        MEDIAN = 100.0
+    */
+    inp = update_field(SINGAPORE, ITALY, FIELD_50_FLOAT, FIELD_50_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![
+        delete_exp(SINGAPORE, FIELD_50_FLOAT),
+        update_exp(ITALY, ITALY, FIELD_150_FLOAT, FIELD_100_FLOAT),
+    ];
+    assert_eq!(out, exp);
+
+    // Update Italy value 200 -> 350
+    /*
+        Italy, 100.0
+        Italy, 350.0
+        Italy, 50.0
+        -------------
+        MEDIAN = 100.0
+    */
+    inp = update_field(ITALY, ITALY, FIELD_200_FLOAT, FIELD_350_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_100_FLOAT, FIELD_100_FLOAT)];
+    assert_eq!(out, exp);
+
+    // Delete 1 record (350)
+    /*
+        Italy, 100.0
+        Italy, 50.0
+        -------------
+        MEDIAN = 75.0
+    */
+    inp = delete_field(ITALY, FIELD_350_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_100_FLOAT, FIELD_75_FLOAT)];
+    assert_eq!(out, exp);
+
+    // Delete another record (100)
+    /*
+        Italy, 50.0
+        -------------
+        MEDIAN = 50.0
+    */
+    inp = delete_field(ITALY, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_75_FLOAT, FIELD_50_FLOAT)];
+    assert_eq!(out, exp);
+
+    // Delete last record
+    /*
+        -------------
+        MEDIAN = NULL
+    */
+    inp = delete_field(ITALY, FIELD_50_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![delete_exp(ITALY, FIELD_50_FLOAT)];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_median_aggregation_int() {
+    let schema = init_input_schema(Int, "MEDIAN");
+    let (processor, tx) = init_processor(
+        "SELECT Country, MEDIAN(Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 100
+        -------------
+        MEDIAN = 100
+    */
+    let mut inp = insert_field(ITALY, FIELD_100_INT);
+    let mut out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, &get_decimal_field(100))];
+    assert_eq!(out, exp);
+
+    // Insert 50 for segment Italy
+    /*
+        Italy, 100
+        Italy, 50
+        -------------
+        MEDIAN = 75
+    */
+    inp = insert_field(ITALY, FIELD_50_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &get_decimal_field(100),
+        &get_decimal_field(75),
+    )];
+    assert_eq!(out, exp);
+
+    // Update Italy value 100 -> 200
+    /*
+        Italy, 200
+        Italy, 50
+        -------------
+        MEDIAN = 125
+    */
+    inp = update_field(ITALY, ITALY, FIELD_100_INT, FIELD_200_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &get_decimal_field(75),
+        &get_decimal_div_field(250, 2),
+    )];
+    assert_eq!(out, exp);
+
+    // Delete 1 record (50)
+    /*
+        Italy, 200
+        -------------
+        MEDIAN = 200
+    */
+    inp = delete_field(ITALY, FIELD_50_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &get_decimal_div_field(250, 2),
+        &get_decimal_field(200),
+    )];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_median_aggregation_float_null() {
+    let schema = init_input_schema(Float, "MEDIAN");
+    let (processor, tx) = init_processor(
+        "SELECT Country, MEDIAN(Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert NULL for segment Italy
+    /*
+        Italy, NULL
+        -------------
+        MEDIAN = NULL
+    */
+    let mut inp = insert_field(ITALY, FIELD_NULL);
+    let mut out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, FIELD_NULL)];
+    assert_eq!(out, exp);
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, NULL
+        Italy, 100.0
+        -------------
+        MEDIAN = 100.0
+    */
+    inp = insert_field(ITALY, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(ITALY, ITALY, FIELD_NULL, FIELD_100_FLOAT)];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_median_aggregation_incremental() {
+    let schema = init_input_schema(Int, "MEDIAN");
+    let (processor, tx) = init_processor(
+        "SELECT Country, MEDIAN(Salary) FROM Users GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Inserts, updates and deletes values with many duplicates, checking the median
+    // against the sorted values after each change
+    let mut values: Vec<i64> = vec![];
+    let mut seed = 42_u64;
+    let mut next = |bound: u64| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) % bound
+    };
+    for _ in 0..500 {
+        let value = next(20) as i64;
+        let inp = match next(3) {
+            0 if !values.is_empty() => {
+                let old = values.remove(next(values.len() as u64) as usize);
+                delete_field(ITALY, &Field::Int(old))
+            }
+            1 if !values.is_empty() => {
+                let old = values.remove(next(values.len() as u64) as usize);
+                values.push(value);
+                update_field(ITALY, ITALY, &Field::Int(old), &Field::Int(value))
+            }
+            _ => {
+                values.push(value);
+                insert_field(ITALY, &Field::Int(value))
+            }
+        };
+        let out = output!(processor, inp, tx);
+
+        let mut sorted = values.clone();
+        sorted.sort();
+        let median = match out.last() {
+            Some(Operation::Insert { new } | Operation::Update { new, .. }) => {
+                new.values[1].clone()
+            }
+            _ => Field::Null,
+        };
+        let expected = match sorted.len() {
+            0 => Field::Null,
+            len => Field::Decimal(
+                (Decimal::from(sorted[(len - 1) / 2]) + Decimal::from(sorted[len / 2]))
+                    / Decimal::from(2),
+            ),
+        };
+        assert_eq!(median, expected, "{sorted:?}");
+    }
+}
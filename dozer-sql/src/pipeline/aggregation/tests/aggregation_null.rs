@@ -124,6 +124,7 @@ fn test_aggregation_alias() {
         NameOrAlias("Users".to_string(), None),
         select.projection,
         select.group_by,
+        select.having,
         false,
     );
     let out_schema = factory
@@ -0,0 +1,119 @@
+use crate::output;
+use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
+    delete_exp, delete_field, init_input_schema, init_processor, insert_exp, insert_field,
+    update_exp, update_field, FIELD_100_FLOAT, FIELD_200_FLOAT, FIELD_300_FLOAT, FIELD_NULL, ITALY,
+};
+use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::Field;
+use dozer_types::types::FieldType::Float;
+use std::collections::HashMap;
+
+#[test]
+fn test_stddev_aggregation_float() {
+    let schema = init_input_schema(Float, "STDDEV");
+    let (processor, tx) = init_processor(
+        "SELECT Country, STDDEV(Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 100.0
+        -------------
+        STDDEV = NULL
+    */
+    let mut inp = insert_field(ITALY, FIELD_100_FLOAT);
+    let mut out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, FIELD_NULL)];
+    assert_eq!(out, exp);
+
+    // Insert 300 for segment Italy
+    /*
+        Italy, 100.0
+        Italy, 300.0
+        -------------
+        STDDEV = sqrt(20000.0)
+    */
+    inp = insert_field(ITALY, FIELD_300_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        FIELD_NULL,
+        &Field::Float(OrderedFloat(20000_f64.sqrt())),
+    )];
+    assert_eq!(out, exp);
+
+    // Insert 200 for segment Italy
+    /*
+        Italy, 100.0
+        Italy, 300.0
+        Italy, 200.0
+        -------------
+        STDDEV = 100.0
+    */
+    inp = insert_field(ITALY, FIELD_200_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::Float(OrderedFloat(20000_f64.sqrt())),
+        &Field::Float(OrderedFloat(100.0)),
+    )];
+    assert_eq!(out, exp);
+
+    // Update Italy value 300 -> 100
+    /*
+        Italy, 100.0
+        Italy, 100.0
+        Italy, 200.0
+        -------------
+        STDDEV = sqrt(10000.0 / 3.0)
+    */
+    inp = update_field(ITALY, ITALY, FIELD_300_FLOAT, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::Float(OrderedFloat(100.0)),
+        &Field::Float(OrderedFloat((10000_f64 / 3_f64).sqrt())),
+    )];
+    assert_eq!(out, exp);
+
+    // Delete 1 record (200)
+    /*
+        Italy, 100.0
+        Italy, 100.0
+        -------------
+        STDDEV = 0.0
+    */
+    inp = delete_field(ITALY, FIELD_200_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::Float(OrderedFloat((10000_f64 / 3_f64).sqrt())),
+        &Field::Float(OrderedFloat(0.0)),
+    )];
+    assert_eq!(out, exp);
+
+    // Delete remaining records
+    inp = delete_field(ITALY, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::Float(OrderedFloat(0.0)),
+        FIELD_NULL,
+    )];
+    assert_eq!(out, exp);
+
+    inp = delete_field(ITALY, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![delete_exp(ITALY, FIELD_NULL)];
+    assert_eq!(out, exp);
+}
@@ -11,8 +11,12 @@ use dozer_types::types::{
 use std::collections::HashMap;
 
 use crate::pipeline::{
-    aggregation::{factory::get_aggregation_rules, processor::AggregationProcessor},
+    aggregation::{
+        factory::{build_having_filter, AggregationProcessorFactory},
+        processor::AggregationProcessor,
+    },
     errors::PipelineError,
+    expression::builder::NameOrAlias,
     tests::utils::get_select,
 };
 
@@ -32,13 +36,18 @@ pub(crate) fn init_processor(
         .get(&DEFAULT_PORT_HANDLE)
         .unwrap_or_else(|| panic!("Error getting Input Schema"));
 
-    let output_field_rules = get_aggregation_rules(
-        &select.projection.clone(),
-        &select.group_by.clone(),
-        input_schema,
-    )?;
+    let factory = AggregationProcessorFactory::new(
+        NameOrAlias(select.from[0].relation.to_string(), None),
+        select.projection,
+        select.group_by,
+        select.having,
+        false,
+    );
+    let (output_field_rules, having, output_size) = factory.get_rules(input_schema)?;
+    let having = build_having_filter(having, input_schema, &output_field_rules, output_size)
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
 
-    let mut processor = AggregationProcessor::new(output_field_rules, input_schema.clone());
+    let mut processor = AggregationProcessor::new(output_field_rules, input_schema.clone(), having);
 
     let mut storage = LmdbEnvironmentManager::create(Path::new("/tmp"), "aggregation_test")
         .unwrap_or_else(|e| panic!("{}", e.to_string()));
@@ -232,6 +241,7 @@ pub const FIELD_100_FLOAT: &Field = &Field::Float(OrderedFloat(100.0));
 pub const FIELD_150_FLOAT: &Field = &Field::Float(OrderedFloat(150.0));
 pub const FIELD_200_FLOAT: &Field = &Field::Float(OrderedFloat(200.0));
 pub const FIELD_250_FLOAT: &Field = &Field::Float(OrderedFloat(250.0));
+pub const FIELD_300_FLOAT: &Field = &Field::Float(OrderedFloat(300.0));
 pub const FIELD_350_FLOAT: &Field = &Field::Float(OrderedFloat(350.0));
 pub const FIELD_75_FLOAT: &Field = &Field::Float(OrderedFloat(75.0));
 pub const FIELD_50_FLOAT: &Field = &Field::Float(OrderedFloat(50.0));
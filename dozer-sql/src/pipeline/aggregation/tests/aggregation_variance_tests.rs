@@ -0,0 +1,173 @@
+use crate::output;
+use crate::pipeline::aggregation::tests::aggregation_tests_utils::{
+    delete_exp, delete_field, init_input_schema, init_processor, insert_exp, insert_field,
+    update_exp, update_field, FIELD_100_FLOAT, FIELD_100_INT, FIELD_200_FLOAT, FIELD_200_INT,
+    FIELD_50_FLOAT, FIELD_NULL, ITALY, SINGAPORE,
+};
+use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::FieldType::{Float, Int};
+use dozer_types::types::{Field, Operation};
+use std::collections::HashMap;
+
+#[test]
+fn test_variance_aggregation_float() {
+    let schema = init_input_schema(Float, "VARIANCE");
+    let (processor, tx) = init_processor(
+        "SELECT Country, VARIANCE(Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100 for segment Italy
+    /*
+        Italy, 100.0
+        -------------
+        VARIANCE = NULL
+    */
+    let mut inp = insert_field(ITALY, FIELD_100_FLOAT);
+    let mut out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, FIELD_NULL)];
+    assert_eq!(out, exp);
+
+    // Insert 200 for segment Italy
+    /*
+        Italy, 100.0
+        Italy, 200.0
+        -------------
+        VARIANCE = 5000.0
+    */
+    inp = insert_field(ITALY, FIELD_200_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        FIELD_NULL,
+        &Field::Float(OrderedFloat(5000.0)),
+    )];
+    assert_eq!(out, exp);
+
+    // Insert 50 for segment Singapore
+    /*
+        Singapore, 50.0
+        -------------
+        VARIANCE = NULL
+    */
+    inp = insert_field(SINGAPORE, FIELD_50_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![insert_exp(SINGAPORE, FIELD_NULL)];
+    assert_eq!(out, exp);
+
+    // Update Italy value 200 -> 100
+    /*
+        Italy, 100.0
+        Italy, 100.0
+        -------------
+        VARIANCE = 0.0
+    */
+    inp = update_field(ITALY, ITALY, FIELD_200_FLOAT, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::Float(OrderedFloat(5000.0)),
+        &Field::Float(OrderedFloat(0.0)),
+    )];
+    assert_eq!(out, exp);
+
+    // Delete 1 record (100)
+    /*
+        Italy, 100.0
+        -------------
+        VARIANCE = NULL
+    */
+    inp = delete_field(ITALY, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        &Field::Float(OrderedFloat(0.0)),
+        FIELD_NULL,
+    )];
+    assert_eq!(out, exp);
+
+    // Delete last record
+    /*
+        -------------
+        VARIANCE = NULL
+    */
+    inp = delete_field(ITALY, FIELD_100_FLOAT);
+    out = output!(processor, inp, tx);
+    exp = vec![delete_exp(ITALY, FIELD_NULL)];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_variance_aggregation_int() {
+    let schema = init_input_schema(Int, "VARIANCE");
+    let (processor, tx) = init_processor(
+        "SELECT Country, VARIANCE(Salary) \
+        FROM Users \
+        WHERE Salary >= 1 GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Insert 100 and 200 for segment Italy
+    /*
+        Italy, 100
+        Italy, 200
+        -------------
+        VARIANCE = 5000.0
+    */
+    let mut inp = insert_field(ITALY, FIELD_100_INT);
+    let mut out = output!(processor, inp, tx);
+    let mut exp = vec![insert_exp(ITALY, FIELD_NULL)];
+    assert_eq!(out, exp);
+
+    inp = insert_field(ITALY, FIELD_200_INT);
+    out = output!(processor, inp, tx);
+    exp = vec![update_exp(
+        ITALY,
+        ITALY,
+        FIELD_NULL,
+        &Field::Float(OrderedFloat(5000.0)),
+    )];
+    assert_eq!(out, exp);
+}
+
+#[test]
+fn test_variance_aggregation_incremental() {
+    let schema = init_input_schema(Float, "VARIANCE");
+    let (processor, tx) = init_processor(
+        "SELECT Country, VARIANCE(Salary) FROM Users GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+    )
+    .unwrap();
+
+    // Values inserted then mostly deleted, the variance being kept without rescanning them
+    let value = |i: i64| (i * 37 % 101) as f64 / 4.0;
+    let mut out = vec![];
+    for i in 0..1000 {
+        let inp = insert_field(ITALY, &Field::Float(OrderedFloat(value(i))));
+        out = output!(processor, inp, tx);
+    }
+    for i in 0..990 {
+        let inp = delete_field(ITALY, &Field::Float(OrderedFloat(value(i))));
+        out = output!(processor, inp, tx);
+    }
+
+    let left: Vec<f64> = (990..1000).map(value).collect();
+    let mean = left.iter().sum::<f64>() / left.len() as f64;
+    let expected = left.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / 9.0;
+    let variance = match out.last() {
+        Some(Operation::Update { new, .. }) => new.values[1].to_float().unwrap(),
+        _ => panic!("expected an update, got {out:?}"),
+    };
+    assert!(
+        (variance - expected).abs() < 1e-6,
+        "{variance} != {expected}"
+    );
+}
@@ -0,0 +1,147 @@
+use crate::deserialize;
+use crate::pipeline::aggregation::aggregator::AggregationResult;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::errors::PipelineError::InvalidOperandType;
+use dozer_core::storage::prefix_transaction::PrefixTransaction;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+use std::string::ToString;
+
+pub struct VarianceAggregator {}
+const AGGREGATOR_NAME: &str = "VARIANCE";
+
+impl VarianceAggregator {
+    const _AGGREGATOR_ID: u32 = 0x07;
+
+    pub(crate) fn get_return_type(_from: FieldType) -> FieldType {
+        FieldType::Float
+    }
+
+    pub(crate) fn _get_type() -> u32 {
+        VarianceAggregator::_AGGREGATOR_ID
+    }
+
+    pub(crate) fn insert(
+        cur_state: Option<&[u8]>,
+        new: &Field,
+        return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        Self::validate_type(return_type, AGGREGATOR_NAME)?;
+        let mut moments = Moments::decode(cur_state);
+        moments.insert(new);
+        Ok(Self::get_result(&moments, moments.variance()))
+    }
+
+    pub(crate) fn update(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        new: &Field,
+        return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        Self::validate_type(return_type, AGGREGATOR_NAME)?;
+        let mut moments = Moments::decode(cur_state);
+        moments.delete(old);
+        moments.insert(new);
+        Ok(Self::get_result(&moments, moments.variance()))
+    }
+
+    pub(crate) fn delete(
+        cur_state: Option<&[u8]>,
+        old: &Field,
+        return_type: FieldType,
+        _txn: &mut PrefixTransaction,
+    ) -> Result<AggregationResult, PipelineError> {
+        Self::validate_type(return_type, AGGREGATOR_NAME)?;
+        let mut moments = Moments::decode(cur_state);
+        moments.delete(old);
+        Ok(Self::get_result(&moments, moments.variance()))
+    }
+
+    pub(crate) fn validate_type(
+        return_type: FieldType,
+        aggregator_name: &str,
+    ) -> Result<(), PipelineError> {
+        match return_type {
+            FieldType::Decimal | FieldType::Float | FieldType::Int | FieldType::UInt => Ok(()),
+            _ => Err(InvalidOperandType(aggregator_name.to_string())),
+        }
+    }
+
+    pub(crate) fn get_result(moments: &Moments, value: Option<f64>) -> AggregationResult {
+        let value = value.map_or(Field::Null, |v| Field::Float(OrderedFloat(v)));
+        AggregationResult::new(value, Some(moments.encode()))
+    }
+}
+
+/// Size of the encoded moments: count, sum and sum of squares
+const MOMENTS_SIZE: usize = 24;
+
+/// Count, sum and sum of squares of the values of a group, from which the variance is
+/// computed in constant time. Null values are not part of the group.
+#[derive(Debug, Default)]
+pub(crate) struct Moments {
+    count: u64,
+    sum: f64,
+    sum_sq: f64,
+}
+
+impl Moments {
+    pub(crate) fn decode(state: Option<&[u8]>) -> Self {
+        match state {
+            Some(state) if state.len() == MOMENTS_SIZE => Self {
+                count: u64::from_be_bytes(deserialize!(&state[0..8])),
+                sum: f64::from_be_bytes(deserialize!(&state[8..16])),
+                sum_sq: f64::from_be_bytes(deserialize!(&state[16..24])),
+            },
+            _ => Self::default(),
+        }
+    }
+
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(MOMENTS_SIZE);
+        buf.extend(self.count.to_be_bytes());
+        buf.extend(self.sum.to_be_bytes());
+        buf.extend(self.sum_sq.to_be_bytes());
+        buf
+    }
+
+    pub(crate) fn insert(&mut self, value: &Field) {
+        if let Some(value) = Self::get_value(value) {
+            self.count += 1;
+            self.sum += value;
+            self.sum_sq += value * value;
+        }
+    }
+
+    pub(crate) fn delete(&mut self, value: &Field) {
+        if let Some(value) = Self::get_value(value) {
+            self.count = self.count.saturating_sub(1);
+            if self.count == 0 {
+                // Drops the rounding errors accumulated by the removals
+                *self = Self::default();
+            } else {
+                self.sum -= value;
+                self.sum_sq -= value * value;
+            }
+        }
+    }
+
+    /// Sample variance of the values, `None` when there are fewer than two
+    pub(crate) fn variance(&self) -> Option<f64> {
+        if self.count < 2 {
+            return None;
+        }
+        let n = self.count as f64;
+        // Rounding errors must not make the variance negative
+        Some(((n * self.sum_sq - self.sum * self.sum) / (n * (n - 1.0))).max(0.0))
+    }
+
+    fn get_value(value: &Field) -> Option<f64> {
+        match value {
+            Field::Null => None,
+            value => value.to_float(),
+        }
+    }
+}
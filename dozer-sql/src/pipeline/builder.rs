@@ -1,22 +1,33 @@
 use crate::pipeline::aggregation::factory::AggregationProcessorFactory;
 use crate::pipeline::builder::PipelineError::InvalidQuery;
+use crate::pipeline::distinct::factory::DistinctProcessorFactory;
 use crate::pipeline::selection::factory::SelectionProcessorFactory;
+use crate::pipeline::semi_join::factory::{SemiJoinProcessorFactory, LEFT_PORT, RIGHT_PORT};
+use crate::pipeline::semi_join::processor::SemiJoinType;
+use crate::pipeline::top_n::factory::TopNProcessorFactory;
+use crate::pipeline::union::factory::UnionProcessorFactory;
+use crate::pipeline::window::factory::{WindowProcessorFactory, WINDOW_END, WINDOW_START};
 use crate::pipeline::{errors::PipelineError, product::factory::ProductProcessorFactory};
 use dozer_core::dag::app::AppPipeline;
 use dozer_core::dag::app::PipelineEntryPoint;
 use dozer_core::dag::appsource::AppSourceId;
 use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
+use dozer_core::dag::errors::ExecutionError;
 use dozer_core::dag::node::PortHandle;
-use sqlparser::ast::{Join, TableFactor, TableWithJoins};
+use dozer_types::models::sql_dialect::SqlDialect;
+use sqlparser::ast::{
+    BinaryOperator, Expr as SqlExpr, Function, Ident, Join, SelectItem, TableFactor,
+    TableWithJoins, UnaryOperator, Value, WindowSpec,
+};
 use sqlparser::{
-    ast::{Query, Select, SetExpr, Statement},
-    dialect::AnsiDialect,
-    parser::Parser,
+    ast::{Query, Select, SetExpr, SetOperator, SetQuantifier, Statement},
+    dialect::{AnsiDialect, Dialect, PostgreSqlDialect},
 };
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use super::errors::UnsupportedSqlError;
+use super::error_location::SqlTokens;
+use super::errors::{UnsupportedSqlError, WindowError};
 use super::expression::builder::{fullname_from_ident, normalize_ident, NameOrAlias};
 
 #[derive(Debug, Clone, Default)]
@@ -26,6 +37,39 @@ pub struct SchemaSQLContext {}
 #[derive(Debug, Clone, Default)]
 pub struct QueryContext {
     pub pipeline_map: HashMap<String, (String, PortHandle)>,
+    /// Upper bounds on the row numbers computed by the query being built, taken from the
+    /// WHERE clause of the enclosing query
+    pub row_number_limits: HashMap<String, usize>,
+    /// Queries of the views defined by `CREATE VIEW`, built the first time they are selected from
+    pub views: HashMap<String, Query>,
+}
+
+impl QueryContext {
+    /// Returns the context to build the view `name`, where only the other views are visible
+    fn get_view_context(&self, name: &str) -> QueryContext {
+        let mut views = self.views.clone();
+        views.remove(name);
+        let pipeline_map = self
+            .pipeline_map
+            .iter()
+            .filter(|(name, _)| views.contains_key(*name))
+            .map(|(name, node)| (name.clone(), node.clone()))
+            .collect();
+        QueryContext {
+            pipeline_map,
+            row_number_limits: HashMap::new(),
+            views,
+        }
+    }
+
+    /// Registers the views built in `view_ctx`
+    fn add_built_views(&mut self, view_ctx: QueryContext) {
+        for (name, node) in view_ctx.pipeline_map {
+            if self.views.contains_key(&name) {
+                self.pipeline_map.insert(name, node);
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -37,23 +81,184 @@ pub struct IndexedTabelWithJoins {
 pub fn statement_to_pipeline(
     sql: &str,
 ) -> Result<(AppPipeline<SchemaSQLContext>, (String, PortHandle)), PipelineError> {
-    let dialect = AnsiDialect {};
-    let mut ctx = QueryContext::default();
+    let mut pipeline = AppPipeline::new();
+    let node = shared_statement_to_pipeline(
+        sql,
+        SqlDialect::Ansi,
+        &mut pipeline,
+        &mut QueryContext::default(),
+    )?;
+    Ok((pipeline, node))
+}
 
-    let ast = Parser::parse_sql(&dialect, sql).unwrap();
-    let query_name = NameOrAlias(format!("query_{}", uuid::Uuid::new_v4()), None);
-    let statement = ast.get(0).expect("First statement is missing").to_owned();
+/// Builds the query of `sql` into `pipeline`, after the views defined by its `CREATE VIEW`
+/// statements. The query can also select from the views of `views`, which are built once and
+/// shared by all the queries built with the same context.
+pub fn shared_statement_to_pipeline(
+    sql: &str,
+    dialect: SqlDialect,
+    pipeline: &mut AppPipeline<SchemaSQLContext>,
+    views: &mut QueryContext,
+) -> Result<(String, PortHandle), PipelineError> {
+    let mut query_ctx = views.clone();
+    let mut nodes = statements_to_pipeline(sql, dialect, pipeline, &mut query_ctx)?;
+    views.add_built_views(query_ctx);
+    match (nodes.pop(), nodes.is_empty()) {
+        (Some(node), true) => Ok(node),
+        _ => Err(InvalidQuery(
+            "SQL must contain exactly one query besides CREATE VIEW statements".to_string(),
+        )),
+    }
+}
 
-    let mut pipeline = AppPipeline::new();
-    if let Statement::Query(query) = statement {
-        query_to_pipeline(&query_name, &query, &mut pipeline, &mut ctx, false)?;
+/// Returns the context holding the views defined by the `CREATE VIEW` statements of `sql`
+pub fn views_to_query_context(
+    sql: &str,
+    dialect: SqlDialect,
+) -> Result<QueryContext, PipelineError> {
+    let mut query_ctx = QueryContext::default();
+    if !statements_to_pipeline(sql, dialect, &mut AppPipeline::new(), &mut query_ctx)?.is_empty() {
+        return Err(InvalidQuery(
+            "only CREATE VIEW statements are allowed in a block of views".to_string(),
+        ));
+    }
+    Ok(query_ctx)
+}
+
+/// Builds the statements of `sql` into `pipeline`, returning the output nodes of its queries.
+/// Views are registered in `query_ctx`, so that the following statements can select from them.
+fn statements_to_pipeline(
+    sql: &str,
+    dialect: SqlDialect,
+    pipeline: &mut AppPipeline<SchemaSQLContext>,
+    query_ctx: &mut QueryContext,
+) -> Result<Vec<(String, PortHandle)>, PipelineError> {
+    let (tokens, ast) = SqlTokens::parse(sql, get_dialect(dialect).as_ref())?;
+
+    let mut nodes = vec![];
+    for (idx, statement) in ast.into_iter().enumerate() {
+        statement_to_nodes(statement, pipeline, query_ctx, &mut nodes).map_err(
+            |error| match tokens.locate(Some(idx), &error) {
+                Some(location) => PipelineError::SqlError {
+                    error: Box::new(error),
+                    location,
+                },
+                None => error,
+            },
+        )?;
+    }
+    Ok(nodes)
+}
+
+/// Locates in `sql` an error raised while computing the schemas of the pipeline built from it.
+/// The error is given back if the name it is about can't be found in `sql`.
+pub fn locate_schema_error(
+    sql: &str,
+    dialect: SqlDialect,
+    error: ExecutionError,
+) -> Result<PipelineError, ExecutionError> {
+    let Ok((tokens, _)) = SqlTokens::parse(sql, get_dialect(dialect).as_ref()) else {
+        return Err(error);
     };
-    let node = ctx
-        .pipeline_map
-        .get(&query_name.0)
-        .expect("query should have been initialized")
-        .to_owned();
-    Ok((pipeline, node))
+    let error = PipelineError::InternalExecutionError(error);
+    match (tokens.locate(None, &error), error) {
+        (Some(location), error) => Ok(PipelineError::SqlError {
+            error: Box::new(error),
+            location,
+        }),
+        (None, PipelineError::InternalExecutionError(error)) => Err(error),
+        (None, error) => Ok(error),
+    }
+}
+
+fn get_dialect(dialect: SqlDialect) -> Box<dyn Dialect> {
+    match dialect {
+        SqlDialect::Ansi => Box::new(AnsiDialect {}),
+        SqlDialect::Postgres => Box::new(PostgreSqlDialect {}),
+    }
+}
+
+/// Builds `statement` into `pipeline`, adding the output node of a query to `nodes`
+fn statement_to_nodes(
+    statement: Statement,
+    pipeline: &mut AppPipeline<SchemaSQLContext>,
+    query_ctx: &mut QueryContext,
+    nodes: &mut Vec<(String, PortHandle)>,
+) -> Result<(), PipelineError> {
+    match statement {
+        Statement::Query(query) => {
+            let query_name = NameOrAlias(format!("query_{}", uuid::Uuid::new_v4()), None);
+            query_to_pipeline(&query_name, &query, pipeline, query_ctx, false)?;
+            nodes.push(get_pipeline_node(query_ctx, &query_name)?);
+        }
+        Statement::CreateView {
+            name,
+            columns,
+            query,
+            materialized: false,
+            ..
+        } => {
+            if !columns.is_empty() {
+                return Err(PipelineError::UnsupportedSqlError(
+                    UnsupportedSqlError::ViewColumnsError,
+                ));
+            }
+            let view_name = name
+                .0
+                .iter()
+                .map(normalize_ident)
+                .collect::<Vec<String>>()
+                .join(".");
+            if query_ctx.views.contains_key(&view_name)
+                || query_ctx.pipeline_map.contains_key(&view_name)
+            {
+                return Err(InvalidQuery(format!(
+                    "view {view_name:?} specified more than once"
+                )));
+            }
+            // The view is checked now, but only built if a query selects from it
+            query_to_pipeline(
+                &NameOrAlias(view_name.clone(), Some(view_name.clone())),
+                &query,
+                &mut AppPipeline::new(),
+                &mut query_ctx.get_view_context(&view_name),
+                false,
+            )?;
+            query_ctx.views.insert(view_name, *query);
+        }
+        _ => {
+            return Err(PipelineError::UnsupportedSqlError(
+                UnsupportedSqlError::StatementError,
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Builds the view `name` into `pipeline` the first time it is selected from
+fn view_to_pipeline(
+    name: &str,
+    pipeline: &mut AppPipeline<SchemaSQLContext>,
+    query_ctx: &mut QueryContext,
+) -> Result<(), PipelineError> {
+    if query_ctx.pipeline_map.contains_key(name) {
+        return Ok(());
+    }
+    let query = match query_ctx.views.get(name) {
+        Some(query) => query.clone(),
+        None => return Ok(()),
+    };
+
+    let mut view_ctx = query_ctx.get_view_context(name);
+    query_to_pipeline(
+        &NameOrAlias(name.to_string(), Some(name.to_string())),
+        &query,
+        pipeline,
+        &mut view_ctx,
+        false,
+    )?;
+    query_ctx.add_built_views(view_ctx);
+    Ok(())
 }
 
 fn query_to_pipeline(
@@ -64,13 +269,13 @@ fn query_to_pipeline(
     stateful: bool,
 ) -> Result<(), PipelineError> {
     // return error if there is unsupported syntax
-    if !query.order_by.is_empty() {
+    if !query.order_by.is_empty() && query.limit.is_none() {
         return Err(PipelineError::UnsupportedSqlError(
             UnsupportedSqlError::OrderByError,
         ));
     }
 
-    if query.limit.is_some() || query.offset.is_some() {
+    if query.order_by.is_empty() && (query.limit.is_some() || query.offset.is_some()) {
         return Err(PipelineError::UnsupportedSqlError(
             UnsupportedSqlError::LimitOffsetError,
         ));
@@ -91,7 +296,9 @@ fn query_to_pipeline(
                 ));
             }
             let table_name = table.alias.name.to_string();
-            if query_ctx.pipeline_map.contains_key(&table_name) {
+            if query_ctx.pipeline_map.contains_key(&table_name)
+                || query_ctx.views.contains_key(&table_name)
+            {
                 return Err(InvalidQuery(format!(
                     "WITH query name {table_name:?} specified more than once"
                 )));
@@ -106,33 +313,177 @@ fn query_to_pipeline(
         }
     };
 
-    match *query.body.clone() {
-        SetExpr::Select(select) => {
-            select_to_pipeline(processor_name, *select, pipeline, query_ctx, stateful)?;
-        }
-        SetExpr::Query(query) => {
-            let query_name = format!("subquery_{}", uuid::Uuid::new_v4());
-            let mut ctx = QueryContext::default();
-            query_to_pipeline(
-                &NameOrAlias(query_name, None),
-                &query,
+    let limit = match &query.limit {
+        Some(limit) => limit,
+        None => {
+            return set_expr_to_pipeline(
+                processor_name,
+                *query.body.clone(),
                 pipeline,
-                &mut ctx,
+                query_ctx,
                 stateful,
-            )?
+            )
         }
-        _ => {
-            return Err(PipelineError::UnsupportedSqlError(
-                UnsupportedSqlError::SelectOnlyError,
-            ))
+    };
+
+    // ORDER BY with LIMIT keeps the top records in a Top-N processor
+    let input_name = NameOrAlias(format!("top_n_input_{}", uuid::Uuid::new_v4()), None);
+    set_expr_to_pipeline(&input_name, *query.body.clone(), pipeline, query_ctx, false)?;
+    let (input_node, input_port) = get_pipeline_node(query_ctx, &input_name)?;
+
+    let offset = match &query.offset {
+        Some(offset) => parse_row_count(&offset.value, "OFFSET")?,
+        None => 0,
+    };
+    let top_n = TopNProcessorFactory::new(
+        vec![],
+        query.order_by.clone(),
+        offset,
+        Some(parse_row_count(limit, "LIMIT")?),
+        vec![],
+        stateful,
+    );
+
+    let gen_top_n_name = format!("top_n_{}", uuid::Uuid::new_v4());
+    pipeline.add_processor(Arc::new(top_n), &gen_top_n_name, vec![]);
+    pipeline.connect_nodes(
+        &input_node,
+        Some(input_port),
+        &gen_top_n_name,
+        Some(DEFAULT_PORT_HANDLE),
+    )?;
+
+    query_ctx.pipeline_map.insert(
+        processor_name.0.clone(),
+        (gen_top_n_name, DEFAULT_PORT_HANDLE),
+    );
+
+    Ok(())
+}
+
+fn parse_row_count(expr: &SqlExpr, clause: &str) -> Result<usize, PipelineError> {
+    match expr {
+        SqlExpr::Value(Value::Number(n, _)) => n
+            .parse::<usize>()
+            .map_err(|_| InvalidQuery(format!("{clause} must be a non negative integer"))),
+        _ => Err(InvalidQuery(format!(
+            "{clause} must be a non negative integer"
+        ))),
+    }
+}
+
+/// Returns the output node of the query registered as `name`
+fn get_pipeline_node(
+    query_ctx: &QueryContext,
+    name: &NameOrAlias,
+) -> Result<(String, PortHandle), PipelineError> {
+    query_ctx
+        .pipeline_map
+        .get(&name.0)
+        .cloned()
+        .ok_or_else(|| InvalidQuery(format!("Query {} not found", name.0)))
+}
+
+fn set_expr_to_pipeline(
+    processor_name: &NameOrAlias,
+    set_expr: SetExpr,
+    pipeline: &mut AppPipeline<SchemaSQLContext>,
+    query_ctx: &mut QueryContext,
+    stateful: bool,
+) -> Result<(), PipelineError> {
+    match set_expr {
+        SetExpr::Select(select) => {
+            select_to_pipeline(processor_name, *select, pipeline, query_ctx, stateful)
         }
+        SetExpr::Query(query) => {
+            query_to_pipeline(processor_name, &query, pipeline, query_ctx, stateful)
+        }
+        SetExpr::SetOperation {
+            op: SetOperator::Union,
+            set_quantifier,
+            left,
+            right,
+        } => union_to_pipeline(
+            processor_name,
+            set_quantifier,
+            vec![*left, *right],
+            pipeline,
+            query_ctx,
+            stateful,
+        ),
+        SetExpr::SetOperation { .. } => Err(PipelineError::UnsupportedSqlError(
+            UnsupportedSqlError::UnionOnlyError,
+        )),
+        _ => Err(PipelineError::UnsupportedSqlError(
+            UnsupportedSqlError::SelectOnlyError,
+        )),
+    }
+}
+
+fn union_to_pipeline(
+    processor_name: &NameOrAlias,
+    set_quantifier: SetQuantifier,
+    inputs: Vec<SetExpr>,
+    pipeline: &mut AppPipeline<SchemaSQLContext>,
+    query_ctx: &mut QueryContext,
+    stateful: bool,
+) -> Result<(), PipelineError> {
+    // UNION without ALL removes the duplicates
+    let distinct = set_quantifier != SetQuantifier::All;
+
+    let gen_union_name = format!("union_{}", uuid::Uuid::new_v4());
+    let union = UnionProcessorFactory::new(inputs.len());
+    pipeline.add_processor(Arc::new(union), &gen_union_name, vec![]);
+
+    for (port_index, input) in inputs.into_iter().enumerate() {
+        let input_name = NameOrAlias(format!("union_input_{}", uuid::Uuid::new_v4()), None);
+        set_expr_to_pipeline(&input_name, input, pipeline, query_ctx, false)?;
+
+        let (input_node, input_port) = get_pipeline_node(query_ctx, &input_name)?;
+        pipeline.connect_nodes(
+            &input_node,
+            Some(input_port),
+            &gen_union_name,
+            Some(port_index as PortHandle),
+        )?;
+    }
+
+    let output_name = if distinct {
+        distinct_to_pipeline(&gen_union_name, pipeline, stateful)?
+    } else {
+        gen_union_name
     };
+
+    query_ctx
+        .pipeline_map
+        .insert(processor_name.0.clone(), (output_name, DEFAULT_PORT_HANDLE));
+
     Ok(())
 }
 
+/// Appends a distinct processor to `input_name`, returning the name of the new node
+fn distinct_to_pipeline(
+    input_name: &str,
+    pipeline: &mut AppPipeline<SchemaSQLContext>,
+    stateful: bool,
+) -> Result<String, PipelineError> {
+    let gen_distinct_name = format!("distinct_{}", uuid::Uuid::new_v4());
+    let distinct = DistinctProcessorFactory::new(stateful);
+
+    pipeline.add_processor(Arc::new(distinct), &gen_distinct_name, vec![]);
+
+    pipeline.connect_nodes(
+        input_name,
+        Some(DEFAULT_PORT_HANDLE),
+        &gen_distinct_name,
+        Some(DEFAULT_PORT_HANDLE),
+    )?;
+    Ok(gen_distinct_name)
+}
+
 fn select_to_pipeline(
     processor_name: &NameOrAlias,
-    select: Select,
+    mut select: Select,
     pipeline: &mut AppPipeline<SchemaSQLContext>,
     query_ctx: &mut QueryContext,
     stateful: bool,
@@ -144,7 +495,22 @@ fn select_to_pipeline(
         ));
     }
 
-    let input_tables = get_input_tables(&select.from[0], pipeline, query_ctx)?;
+    // A filter on the row number of a derived table bounds the records kept by its Top-N
+    let outer_limits = std::mem::replace(
+        &mut query_ctx.row_number_limits,
+        get_row_number_limits(&select.selection),
+    );
+    let input_tables = get_input_tables(&select.from[0], pipeline, query_ctx);
+    query_ctx.row_number_limits = outer_limits;
+    let input_tables = input_tables?;
+
+    let windows = extract_window_functions(&mut select.projection)?;
+    if !windows.is_empty() && !select.group_by.is_empty() {
+        return Err(InvalidQuery(
+            "Window functions are not supported together with GROUP BY".to_string(),
+        ));
+    }
+    let window = extract_window(&mut select)?;
 
     let product = ProductProcessorFactory::new(input_tables.clone());
 
@@ -171,47 +537,527 @@ fn select_to_pipeline(
         input_tables.relation.0,
         select.projection.clone(),
         select.group_by,
-        stateful,
+        select.having,
+        stateful && !select.distinct,
     );
 
     pipeline.add_processor(Arc::new(aggregation), &gen_agg_name, vec![]);
 
+    let mut input_name = gen_product_name;
+
+    // IN and EXISTS conditions, each matched against its subquery by a semi-join
+    let outer_names = input_names
+        .into_iter()
+        .flat_map(|NameOrAlias(name, alias)| [Some(name), alias])
+        .flatten()
+        .collect::<Vec<_>>();
+    let (selection, conditions) = extract_subquery_conditions(select.selection, &outer_names)?;
+    for condition in conditions {
+        let subquery_name = NameOrAlias(format!("subquery_{}", uuid::Uuid::new_v4()), None);
+        query_to_pipeline(
+            &subquery_name,
+            &condition.subquery,
+            pipeline,
+            query_ctx,
+            false,
+        )?;
+        let (subquery_node, subquery_port) = get_pipeline_node(query_ctx, &subquery_name)?;
+
+        let gen_semi_join_name = format!("semi_join_{}", uuid::Uuid::new_v4());
+        let semi_join = SemiJoinProcessorFactory::new(condition.left_keys, condition.join_type);
+        pipeline.add_processor(Arc::new(semi_join), &gen_semi_join_name, vec![]);
+
+        pipeline.connect_nodes(
+            &input_name,
+            Some(DEFAULT_PORT_HANDLE),
+            &gen_semi_join_name,
+            Some(LEFT_PORT),
+        )?;
+        pipeline.connect_nodes(
+            &subquery_node,
+            Some(subquery_port),
+            &gen_semi_join_name,
+            Some(RIGHT_PORT),
+        )?;
+        input_name = gen_semi_join_name;
+    }
+
     // Where clause
-    if let Some(selection) = select.selection {
+    if let Some(selection) = selection {
         let selection = SelectionProcessorFactory::new(selection);
 
         pipeline.add_processor(Arc::new(selection), &gen_selection_name, vec![]);
 
         pipeline.connect_nodes(
-            &gen_product_name,
+            &input_name,
             Some(DEFAULT_PORT_HANDLE),
             &gen_selection_name,
             Some(DEFAULT_PORT_HANDLE),
         )?;
+        input_name = gen_selection_name;
+    }
+
+    // Window functions, appended to the records before the projection.
+    // A filter on a row number can only bound the records when there is a single window.
+    let single_window = windows.len() == 1;
+    for (window, functions) in windows {
+        let gen_top_n_name = format!("top_n_{}", uuid::Uuid::new_v4());
+        let limit = if single_window {
+            functions
+                .iter()
+                .filter(|(_, function)| function.name.to_string().to_lowercase() == "row_number")
+                .filter_map(|(name, _)| query_ctx.row_number_limits.get(name).copied())
+                .min()
+        } else {
+            None
+        };
+        let top_n = TopNProcessorFactory::new(
+            window.partition_by,
+            window.order_by,
+            0,
+            limit,
+            functions,
+            false,
+        );
+
+        pipeline.add_processor(Arc::new(top_n), &gen_top_n_name, vec![]);
 
         pipeline.connect_nodes(
-            &gen_selection_name,
+            &input_name,
             Some(DEFAULT_PORT_HANDLE),
-            &gen_agg_name,
+            &gen_top_n_name,
             Some(DEFAULT_PORT_HANDLE),
         )?;
-    } else {
+        input_name = gen_top_n_name;
+    }
+
+    // Time window, appended to the records before the aggregation
+    if let Some(window) = window {
+        let gen_window_name = format!("window_{}", uuid::Uuid::new_v4());
+
+        pipeline.add_processor(Arc::new(window), &gen_window_name, vec![]);
+
         pipeline.connect_nodes(
-            &gen_product_name,
+            &input_name,
             Some(DEFAULT_PORT_HANDLE),
-            &gen_agg_name,
+            &gen_window_name,
             Some(DEFAULT_PORT_HANDLE),
         )?;
+        input_name = gen_window_name;
     }
 
-    query_ctx.pipeline_map.insert(
-        processor_name.0.clone(),
-        (gen_agg_name, DEFAULT_PORT_HANDLE),
-    );
+    pipeline.connect_nodes(
+        &input_name,
+        Some(DEFAULT_PORT_HANDLE),
+        &gen_agg_name,
+        Some(DEFAULT_PORT_HANDLE),
+    )?;
+
+    // Distinct clause
+    let output_name = if select.distinct {
+        distinct_to_pipeline(&gen_agg_name, pipeline, stateful)?
+    } else {
+        gen_agg_name
+    };
+
+    query_ctx
+        .pipeline_map
+        .insert(processor_name.0.clone(), (output_name, DEFAULT_PORT_HANDLE));
 
     Ok(())
 }
 
+/// A condition of the WHERE clause on a subquery, evaluated by a semi-join
+struct SubqueryCondition {
+    /// Expressions matched against the first columns of the subquery
+    left_keys: Vec<SqlExpr>,
+    subquery: Query,
+    join_type: SemiJoinType,
+}
+
+/// Splits the IN and EXISTS conditions out of the WHERE clause, returning the remaining
+/// selection. `outer_names` are the names and aliases of the tables of the query, which
+/// correlated EXISTS subqueries may refer to in equality conditions.
+fn extract_subquery_conditions(
+    selection: Option<SqlExpr>,
+    outer_names: &[String],
+) -> Result<(Option<SqlExpr>, Vec<SubqueryCondition>), PipelineError> {
+    let mut conjuncts = vec![];
+    if let Some(selection) = selection {
+        split_conjunction(selection, &mut conjuncts);
+    }
+
+    let mut remaining: Option<SqlExpr> = None;
+    let mut conditions = vec![];
+    for conjunct in conjuncts {
+        let condition = match unwrap_nested(conjunct) {
+            SqlExpr::InSubquery {
+                expr,
+                subquery,
+                negated: false,
+            } => in_subquery_condition(*expr, *subquery)?,
+            SqlExpr::Exists { subquery, negated } => {
+                exists_subquery_condition(*subquery, negated, outer_names)?
+            }
+            SqlExpr::UnaryOp {
+                op: UnaryOperator::Not,
+                expr,
+            } => match unwrap_nested(*expr) {
+                SqlExpr::Exists { subquery, negated } => {
+                    exists_subquery_condition(*subquery, !negated, outer_names)?
+                }
+                SqlExpr::InSubquery { .. } => {
+                    return Err(PipelineError::UnsupportedSqlError(
+                        UnsupportedSqlError::NotInSubqueryError,
+                    ))
+                }
+                expr => {
+                    let expr = SqlExpr::UnaryOp {
+                        op: UnaryOperator::Not,
+                        expr: Box::new(expr),
+                    };
+                    remaining = Some(and_conditions(remaining, check_no_subquery(expr)?));
+                    continue;
+                }
+            },
+            SqlExpr::InSubquery { negated: true, .. } => {
+                return Err(PipelineError::UnsupportedSqlError(
+                    UnsupportedSqlError::NotInSubqueryError,
+                ))
+            }
+            expr => {
+                remaining = Some(and_conditions(remaining, check_no_subquery(expr)?));
+                continue;
+            }
+        };
+        conditions.push(condition);
+    }
+    Ok((remaining, conditions))
+}
+
+fn split_conjunction(expr: SqlExpr, conjuncts: &mut Vec<SqlExpr>) {
+    match expr {
+        SqlExpr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            split_conjunction(*left, conjuncts);
+            split_conjunction(*right, conjuncts);
+        }
+        SqlExpr::Nested(expr)
+            if matches!(
+                *expr,
+                SqlExpr::BinaryOp {
+                    op: BinaryOperator::And,
+                    ..
+                }
+            ) =>
+        {
+            split_conjunction(*expr, conjuncts)
+        }
+        expr => conjuncts.push(expr),
+    }
+}
+
+fn unwrap_nested(expr: SqlExpr) -> SqlExpr {
+    match expr {
+        SqlExpr::Nested(expr) => unwrap_nested(*expr),
+        expr => expr,
+    }
+}
+
+fn and_conditions(left: Option<SqlExpr>, right: SqlExpr) -> SqlExpr {
+    match left {
+        Some(left) => SqlExpr::BinaryOp {
+            left: Box::new(left),
+            op: BinaryOperator::And,
+            right: Box::new(right),
+        },
+        None => right,
+    }
+}
+
+/// Rejects the subqueries which cannot be evaluated by a semi-join
+fn check_no_subquery(expr: SqlExpr) -> Result<SqlExpr, PipelineError> {
+    fn contains_subquery(expr: &SqlExpr) -> bool {
+        match expr {
+            SqlExpr::InSubquery { .. } | SqlExpr::Exists { .. } | SqlExpr::Subquery(_) => true,
+            SqlExpr::BinaryOp { left, right, .. } => {
+                contains_subquery(left) || contains_subquery(right)
+            }
+            SqlExpr::UnaryOp { expr, .. }
+            | SqlExpr::Nested(expr)
+            | SqlExpr::IsNull(expr)
+            | SqlExpr::IsNotNull(expr) => contains_subquery(expr),
+            _ => false,
+        }
+    }
+
+    if contains_subquery(&expr) {
+        return Err(PipelineError::UnsupportedSqlError(
+            UnsupportedSqlError::SubqueryError,
+        ));
+    }
+    Ok(expr)
+}
+
+fn in_subquery_condition(
+    expr: SqlExpr,
+    subquery: Query,
+) -> Result<SubqueryCondition, PipelineError> {
+    if let SetExpr::Select(select) = subquery.body.as_ref() {
+        if select.projection.len() != 1 {
+            return Err(InvalidQuery(
+                "subquery of IN must return exactly one column".to_string(),
+            ));
+        }
+    }
+    Ok(SubqueryCondition {
+        left_keys: vec![expr],
+        subquery,
+        join_type: SemiJoinType::Semi,
+    })
+}
+
+/// Builds the condition of an EXISTS subquery. The equalities between a column of the
+/// outer query and an expression of the subquery are turned into the keys of the semi-join.
+fn exists_subquery_condition(
+    mut subquery: Query,
+    negated: bool,
+    outer_names: &[String],
+) -> Result<SubqueryCondition, PipelineError> {
+    let join_type = if negated {
+        SemiJoinType::Anti
+    } else {
+        SemiJoinType::Semi
+    };
+    let select = match subquery.body.as_mut() {
+        SetExpr::Select(select) => select,
+        _ => {
+            return Ok(SubqueryCondition {
+                left_keys: vec![],
+                subquery,
+                join_type,
+            })
+        }
+    };
+
+    // Names of the subquery tables shadow the ones of the outer query
+    let inner_names = select
+        .from
+        .iter()
+        .flat_map(|from| {
+            std::iter::once(&from.relation).chain(from.joins.iter().map(|join| &join.relation))
+        })
+        .flat_map(get_table_factor_names)
+        .collect::<Vec<_>>();
+    let is_outer = |expr: &SqlExpr| match expr {
+        SqlExpr::CompoundIdentifier(ident) if ident.len() > 1 => {
+            let table_name = fullname_from_ident(&ident[..ident.len() - 1]);
+            outer_names.contains(&table_name) && !inner_names.contains(&table_name)
+        }
+        _ => false,
+    };
+
+    let mut conjuncts = vec![];
+    if let Some(selection) = select.selection.take() {
+        split_conjunction(selection, &mut conjuncts);
+    }
+    let mut left_keys = vec![];
+    let mut right_keys = vec![];
+    for conjunct in conjuncts {
+        match conjunct {
+            SqlExpr::BinaryOp {
+                left,
+                op: BinaryOperator::Eq,
+                right,
+            } if is_outer(&left) != is_outer(&right) => {
+                if is_outer(&left) {
+                    left_keys.push(*left);
+                    right_keys.push(*right);
+                } else {
+                    left_keys.push(*right);
+                    right_keys.push(*left);
+                }
+            }
+            conjunct => select.selection = Some(and_conditions(select.selection.take(), conjunct)),
+        }
+    }
+
+    if !left_keys.is_empty() {
+        if !select.group_by.is_empty() {
+            return Err(InvalidQuery(
+                "correlated EXISTS subqueries with GROUP BY are not supported".to_string(),
+            ));
+        }
+        select.projection = right_keys
+            .into_iter()
+            .map(SelectItem::UnnamedExpr)
+            .collect();
+    }
+    Ok(SubqueryCondition {
+        left_keys,
+        subquery,
+        join_type,
+    })
+}
+
+fn get_table_factor_names(relation: &TableFactor) -> Vec<String> {
+    match relation {
+        TableFactor::Table { name, alias, .. } => {
+            let mut names = vec![fullname_from_ident(&name.0)];
+            names.extend(alias.as_ref().map(|alias| alias.name.value.clone()));
+            names
+        }
+        TableFactor::Derived { alias, .. } => alias
+            .as_ref()
+            .map(|alias| alias.name.value.clone())
+            .into_iter()
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Window functions sharing a window, with the names of the fields they are computed into
+type WindowFunctions = Vec<(WindowSpec, Vec<(String, Function)>)>;
+
+/// Replaces the window functions of the projection with references to the fields appended
+/// by the Top-N processors, returning them grouped by window
+fn extract_window_functions(
+    projection: &mut [SelectItem],
+) -> Result<WindowFunctions, PipelineError> {
+    let mut windows: WindowFunctions = vec![];
+    for item in projection.iter_mut() {
+        let (expr, name) = match item {
+            SelectItem::UnnamedExpr(expr) => (&*expr, expr.to_string()),
+            SelectItem::ExprWithAlias { expr, alias } => (&*expr, alias.value.clone()),
+            _ => continue,
+        };
+        let function = match expr {
+            SqlExpr::Function(function) if function.over.is_some() => function.clone(),
+            _ => continue,
+        };
+        let window = function.over.clone().unwrap();
+        if window.window_frame.is_some() {
+            return Err(PipelineError::UnsupportedSqlError(
+                UnsupportedSqlError::WindowFrameError,
+            ));
+        }
+        match windows.iter_mut().find(|(spec, _)| spec == &window) {
+            Some((_, functions)) => functions.push((name.clone(), function)),
+            None => windows.push((window, vec![(name.clone(), function)])),
+        }
+        *item = SelectItem::UnnamedExpr(SqlExpr::Identifier(Ident::new(name)));
+    }
+    Ok(windows)
+}
+
+/// Replaces the `TUMBLE()` or `HOP()` window of the GROUP BY clause with the window start
+/// and end fields appended by the window processor, selecting them if they are not
+fn extract_window(select: &mut Select) -> Result<Option<WindowProcessorFactory>, PipelineError> {
+    let mut window = None;
+    let mut group_by = vec![];
+    for expr in select.group_by.drain(..) {
+        let factory = match &expr {
+            SqlExpr::Function(function) => WindowProcessorFactory::from_function(function)?,
+            _ => None,
+        };
+        match factory {
+            Some(_) if window.is_some() => return Err(WindowError::MultipleWindows.into()),
+            Some(factory) => {
+                window = Some(factory);
+                group_by.push(SqlExpr::Identifier(Ident::new(WINDOW_START)));
+                group_by.push(SqlExpr::Identifier(Ident::new(WINDOW_END)));
+            }
+            None => group_by.push(expr),
+        }
+    }
+    select.group_by = group_by;
+
+    if window.is_some() {
+        for name in [WINDOW_START, WINDOW_END] {
+            let selected = select.projection.iter().any(|item| match item {
+                SelectItem::UnnamedExpr(SqlExpr::Identifier(ident))
+                | SelectItem::ExprWithAlias {
+                    expr: SqlExpr::Identifier(ident),
+                    ..
+                } => ident.value == name,
+                _ => false,
+            });
+            if !selected {
+                select
+                    .projection
+                    .push(SelectItem::UnnamedExpr(SqlExpr::Identifier(Ident::new(
+                        name,
+                    ))));
+            }
+        }
+    }
+    Ok(window)
+}
+
+/// Returns the upper bounds on the columns compared with a constant in `selection`,
+/// such as `rn <= 10`
+fn get_row_number_limits(selection: &Option<SqlExpr>) -> HashMap<String, usize> {
+    let mut limits = HashMap::new();
+    if let Some(selection) = selection {
+        collect_row_number_limits(selection, &mut limits);
+    }
+    limits
+}
+
+fn collect_row_number_limits(expr: &SqlExpr, limits: &mut HashMap<String, usize>) {
+    let (column, op, value) = match expr {
+        SqlExpr::Nested(expr) => return collect_row_number_limits(expr, limits),
+        SqlExpr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            collect_row_number_limits(left, limits);
+            return collect_row_number_limits(right, limits);
+        }
+        SqlExpr::BinaryOp { left, op, right } => match (left.as_ref(), right.as_ref()) {
+            (SqlExpr::Identifier(_) | SqlExpr::CompoundIdentifier(_), value) => {
+                (left.as_ref(), op.clone(), value)
+            }
+            (value, SqlExpr::Identifier(_) | SqlExpr::CompoundIdentifier(_)) => {
+                let op = match op {
+                    BinaryOperator::GtEq => BinaryOperator::LtEq,
+                    BinaryOperator::Gt => BinaryOperator::Lt,
+                    _ => op.clone(),
+                };
+                (right.as_ref(), op, value)
+            }
+            _ => return,
+        },
+        _ => return,
+    };
+
+    let value = match parse_row_count(value, "") {
+        Ok(value) => value,
+        Err(_) => return,
+    };
+    let limit = match op {
+        BinaryOperator::LtEq | BinaryOperator::Eq => value,
+        BinaryOperator::Lt => value.saturating_sub(1),
+        _ => return,
+    };
+    let name = match column {
+        SqlExpr::Identifier(ident) => ident.value.clone(),
+        SqlExpr::CompoundIdentifier(idents) => match idents.last() {
+            Some(ident) => ident.value.clone(),
+            None => return,
+        },
+        _ => return,
+    };
+    limits
+        .entry(name)
+        .and_modify(|current| *current = (*current).min(limit))
+        .or_insert(limit);
+}
+
 /// Returns a vector of input port handles and relative table name
 ///
 /// # Errors
@@ -282,6 +1128,7 @@ pub fn get_from_source(
             let alias_name = alias
                 .as_ref()
                 .map(|a| fullname_from_ident(&[a.name.clone()]));
+            view_to_pipeline(&input_name, pipeline, query_ctx)?;
 
             Ok(NameOrAlias(input_name, alias_name))
         }
@@ -361,6 +1208,21 @@ mod tests {
                 with tbl as (select id, ticker from stocks)
                 select tbl.id from  stocks join tbl on tbl.id = stocks.id;
             "#,
+            r#"
+                SELECT ticker, AVG(price) FROM trades
+                GROUP BY ticker, TUMBLE(ts, INTERVAL '5' MINUTE, INTERVAL '1' HOUR);
+            "#,
+            r#"
+                SELECT COUNT(id), window_start FROM trades
+                GROUP BY HOP(ts, INTERVAL '1' MINUTE, INTERVAL '5' MINUTE);
+            "#,
+            r#"
+                SELECT account, amount,
+                    SUM(amount) OVER (PARTITION BY account ORDER BY ts) AS balance,
+                    LAG(amount, 1, 0) OVER (PARTITION BY account ORDER BY ts) AS previous,
+                    RANK() OVER (ORDER BY amount DESC) AS amount_rank
+                FROM transactions;
+            "#,
         ];
         for sql in statements {
             let _pipeline = statement_to_pipeline(sql).unwrap();
@@ -0,0 +1,3 @@
+pub mod factory;
+pub mod processor;
+mod tests;
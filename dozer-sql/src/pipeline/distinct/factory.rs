@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use crate::pipeline::builder::SchemaSQLContext;
+use dozer_core::dag::{
+    dag::DEFAULT_PORT_HANDLE,
+    errors::ExecutionError,
+    node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
+};
+use dozer_types::types::Schema;
+
+use super::processor::DistinctProcessor;
+
+#[derive(Debug)]
+pub struct DistinctProcessorFactory {
+    stateful: bool,
+}
+
+impl DistinctProcessorFactory {
+    /// Creates a new [`DistinctProcessorFactory`].
+    pub fn new(stateful: bool) -> Self {
+        Self { stateful }
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for DistinctProcessorFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        if self.stateful {
+            vec![OutputPortDef::new(
+                DEFAULT_PORT_HANDLE,
+                OutputPortType::StatefulWithPrimaryKeyLookup {
+                    retr_old_records_for_deletes: true,
+                    retr_old_records_for_updates: true,
+                },
+            )]
+        } else {
+            vec![OutputPortDef::new(
+                DEFAULT_PORT_HANDLE,
+                OutputPortType::Stateless,
+            )]
+        }
+    }
+
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
+        let (schema, ctx) = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(ExecutionError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+
+        // Distinct records are identified by all of their fields
+        let mut output_schema = schema.clone();
+        output_schema.primary_index = (0..output_schema.fields.len()).collect();
+        Ok((output_schema, ctx.clone()))
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        Ok(Box::<DistinctProcessor>::default())
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+        _output_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
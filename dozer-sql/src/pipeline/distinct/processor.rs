@@ -0,0 +1,140 @@
+use crate::pipeline::errors::PipelineError;
+use crate::{deserialize, deserialize_u64};
+use dozer_core::dag::channels::ProcessorChannelForwarder;
+use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
+use dozer_core::dag::epoch::Epoch;
+use dozer_core::dag::errors::ExecutionError;
+use dozer_core::dag::errors::ExecutionError::InternalError;
+use dozer_core::dag::node::{PortHandle, Processor};
+use dozer_core::dag::record_store::RecordReader;
+use dozer_core::storage::common::Database;
+use dozer_core::storage::lmdb_storage::{
+    LmdbEnvironmentManager, LmdbExclusiveTransaction, SharedTransaction,
+};
+use dozer_types::internal_err;
+use dozer_types::types::{Operation, Record};
+use std::collections::HashMap;
+
+/// Distinct Processor, forwards a record the first time it appears and retracts it
+/// when its last occurrence is deleted
+#[derive(Debug, Default)]
+pub struct DistinctProcessor {
+    /// Database to store the occurrences of each record
+    pub db: Option<Database>,
+}
+
+impl DistinctProcessor {
+    fn init_store(&mut self, env: &mut LmdbEnvironmentManager) -> Result<(), PipelineError> {
+        self.db = Some(env.open_database("distinct", false)?);
+
+        Ok(())
+    }
+
+    /// Adds `delta` to the occurrences of `record`, returning the previous and the new count
+    fn update_count(
+        txn: &mut LmdbExclusiveTransaction,
+        db: Database,
+        record: &Record,
+        delta: i64,
+    ) -> Result<(u64, u64), PipelineError> {
+        let key = get_record_key(record);
+        let prev_count = deserialize_u64!(txn.get(db, &key)?);
+        let new_count = (prev_count as i64 + delta).max(0) as u64;
+
+        if new_count == 0 {
+            txn.del(db, &key, None)?;
+        } else {
+            txn.put(db, &key, &new_count.to_be_bytes())?;
+        }
+        Ok((prev_count, new_count))
+    }
+
+    fn insert(
+        txn: &mut LmdbExclusiveTransaction,
+        db: Database,
+        record: &Record,
+    ) -> Result<bool, PipelineError> {
+        let (prev_count, _) = Self::update_count(txn, db, record, 1)?;
+        Ok(prev_count == 0)
+    }
+
+    fn delete(
+        txn: &mut LmdbExclusiveTransaction,
+        db: Database,
+        record: &Record,
+    ) -> Result<bool, PipelineError> {
+        let (prev_count, new_count) = Self::update_count(txn, db, record, -1)?;
+        Ok(prev_count > 0 && new_count == 0)
+    }
+
+    pub(crate) fn distinct(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        db: Database,
+        op: Operation,
+    ) -> Result<Vec<Operation>, PipelineError> {
+        match op {
+            Operation::Insert { new } => {
+                if Self::insert(txn, db, &new)? {
+                    Ok(vec![Operation::Insert { new }])
+                } else {
+                    Ok(vec![])
+                }
+            }
+            Operation::Delete { old } => {
+                if Self::delete(txn, db, &old)? {
+                    Ok(vec![Operation::Delete { old }])
+                } else {
+                    Ok(vec![])
+                }
+            }
+            Operation::Update { old, new } => {
+                if get_record_key(&old) == get_record_key(&new) {
+                    return Ok(vec![]);
+                }
+                let removed = Self::delete(txn, db, &old)?;
+                let added = Self::insert(txn, db, &new)?;
+                match (removed, added) {
+                    (true, true) => Ok(vec![Operation::Update { old, new }]),
+                    (true, false) => Ok(vec![Operation::Delete { old }]),
+                    (false, true) => Ok(vec![Operation::Insert { new }]),
+                    (false, false) => Ok(vec![]),
+                }
+            }
+        }
+    }
+}
+
+fn get_record_key(record: &Record) -> Vec<u8> {
+    record.get_key(&(0..record.values.len()).collect())
+}
+
+impl Processor for DistinctProcessor {
+    fn init(&mut self, state: &mut LmdbEnvironmentManager) -> Result<(), ExecutionError> {
+        internal_err!(self.init_store(state))
+    }
+
+    fn commit(&self, _epoch: &Epoch, _tx: &SharedTransaction) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+        txn: &SharedTransaction,
+        _reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
+    ) -> Result<(), ExecutionError> {
+        match self.db {
+            Some(db) => {
+                let ops = internal_err!(self.distinct(&mut txn.write(), db, op))?;
+                for fop in ops {
+                    fw.send(fop, DEFAULT_PORT_HANDLE)?;
+                }
+                Ok(())
+            }
+            _ => Err(ExecutionError::InvalidDatabase),
+        }
+    }
+}
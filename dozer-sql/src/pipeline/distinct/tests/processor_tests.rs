@@ -0,0 +1,103 @@
+use dozer_core::storage::lmdb_storage::LmdbEnvironmentManager;
+use dozer_types::types::{Field, Operation, Record};
+use tempdir::TempDir;
+
+use crate::pipeline::distinct::processor::DistinctProcessor;
+use dozer_core::dag::node::Processor;
+
+fn country(name: &str) -> Record {
+    Record::new(None, vec![Field::String(name.to_string())], None)
+}
+
+#[test]
+fn test_distinct() {
+    let tmp_dir = TempDir::new("distinct").unwrap();
+    let mut storage = LmdbEnvironmentManager::create(tmp_dir.path(), "distinct_test")
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let mut processor = DistinctProcessor::default();
+    processor
+        .init(&mut storage)
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let tx = storage.create_txn().unwrap();
+
+    let distinct = |op: Operation| -> Vec<Operation> {
+        processor
+            .distinct(&mut tx.write(), processor.db.unwrap(), op)
+            .unwrap_or_else(|e| panic!("{}", e.to_string()))
+    };
+
+    // The first occurrence of a record is forwarded
+    let out = distinct(Operation::Insert {
+        new: country("Italy"),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: country("Italy")
+        }]
+    );
+
+    // Duplicates are not forwarded
+    let out = distinct(Operation::Insert {
+        new: country("Italy"),
+    });
+    assert_eq!(out, vec![]);
+
+    // Moving one of the duplicates adds a new record
+    let out = distinct(Operation::Update {
+        old: country("Italy"),
+        new: country("Singapore"),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: country("Singapore")
+        }]
+    );
+
+    // Moving the last occurrence of a record retracts it
+    let out = distinct(Operation::Update {
+        old: country("Italy"),
+        new: country("Singapore"),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Delete {
+            old: country("Italy")
+        }]
+    );
+
+    let out = distinct(Operation::Update {
+        old: country("Singapore"),
+        new: country("Italy"),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: country("Italy")
+        }]
+    );
+
+    let out = distinct(Operation::Delete {
+        old: country("Singapore"),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Delete {
+            old: country("Singapore")
+        }]
+    );
+
+    // Updating the only occurrence of a record replaces it
+    let out = distinct(Operation::Update {
+        old: country("Italy"),
+        new: country("Germany"),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Update {
+            old: country("Italy"),
+            new: country("Germany")
+        }]
+    );
+}
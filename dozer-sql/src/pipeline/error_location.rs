@@ -0,0 +1,230 @@
+use crate::pipeline::errors::{
+    JoinError, PipelineError, SqlLocation, UnsupportedSqlError, WindowError,
+};
+use dozer_core::dag::errors::ExecutionError;
+use sqlparser::ast::Statement;
+use sqlparser::dialect::Dialect;
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::{Location, Token, TokenWithLocation, Tokenizer};
+use std::ops::Range;
+
+/// SQL text split into tokens, used to locate the errors of its statements
+pub(crate) struct SqlTokens<'a> {
+    sql: &'a str,
+    /// Tokens of the SQL, without the whitespaces
+    tokens: Vec<TokenWithLocation>,
+    /// Range of `tokens` of each statement, in the order they are returned by the parser
+    statements: Vec<Range<usize>>,
+}
+
+impl<'a> SqlTokens<'a> {
+    /// Parses `sql`, returning its statements. Parse errors are located at the token the
+    /// parser did not expect.
+    pub fn parse(
+        sql: &'a str,
+        dialect: &dyn Dialect,
+    ) -> Result<(Self, Vec<Statement>), PipelineError> {
+        let tokens = Tokenizer::new(dialect, sql)
+            .tokenize_with_location()
+            .map_err(|e| {
+                let location = Location {
+                    line: e.line,
+                    column: e.col,
+                };
+                PipelineError::SqlError {
+                    error: Box::new(PipelineError::InvalidQuery(e.message)),
+                    location: sql_location(sql, &location, 1),
+                }
+            })?;
+        let sql_tokens = SqlTokens::new(sql, &tokens);
+
+        let mut parser = Parser::new(dialect).with_tokens_with_locations(tokens);
+        match parser.parse_statements() {
+            Ok(statements) => Ok((sql_tokens, statements)),
+            Err(e) => {
+                let message = match e {
+                    ParserError::TokenizerError(message) | ParserError::ParserError(message) => {
+                        message
+                    }
+                    ParserError::RecursionLimitExceeded => e.to_string(),
+                };
+                let location = sql_tokens.parse_error_location(&message, &parser.peek_token());
+                Err(PipelineError::SqlError {
+                    error: Box::new(PipelineError::InvalidQuery(message)),
+                    location,
+                })
+            }
+        }
+    }
+
+    fn new(sql: &'a str, tokens: &[TokenWithLocation]) -> Self {
+        let tokens: Vec<TokenWithLocation> = tokens
+            .iter()
+            .filter(|t| !matches!(t.token, Token::Whitespace(_)))
+            .cloned()
+            .collect();
+
+        // The parser skips the empty statements between successive semicolons
+        let mut statements = vec![];
+        let mut start = None;
+        for (idx, token) in tokens.iter().enumerate() {
+            match (&token.token, start) {
+                (Token::SemiColon, Some(begin)) => {
+                    statements.push(begin..idx);
+                    start = None;
+                }
+                (Token::SemiColon, None) => (),
+                (_, None) => start = Some(idx),
+                (_, Some(_)) => (),
+            }
+        }
+        if let Some(begin) = start {
+            statements.push(begin..tokens.len());
+        }
+
+        Self {
+            sql,
+            tokens,
+            statements,
+        }
+    }
+
+    /// Location of the name `error` is about, searched in the statement at index `statement`,
+    /// or in all the statements if `None`
+    pub fn locate(&self, statement: Option<usize>, error: &PipelineError) -> Option<SqlLocation> {
+        let range = match statement {
+            Some(statement) => self.statements.get(statement)?.clone(),
+            None => 0..self.tokens.len(),
+        };
+        let token = self.find_word(range, &error_subject(error)?)?;
+        Some(sql_location(
+            self.sql,
+            &token.location,
+            token.token.to_string().chars().count(),
+        ))
+    }
+
+    /// The parser reports the token it found at the end of its message, but it may or may
+    /// not have consumed it already
+    fn parse_error_location(&self, message: &str, next: &TokenWithLocation) -> SqlLocation {
+        let found = message
+            .rsplit_once("found: ")
+            .or_else(|| message.rsplit_once("for token "))
+            .map(|(_, found)| found);
+        if found == Some("EOF") || (found.is_none() && next.token == Token::EOF) {
+            return self.end_location();
+        }
+        let token = found
+            .and_then(|found| {
+                self.tokens
+                    .iter()
+                    .filter(|t| {
+                        next.token == Token::EOF
+                            || (t.location.line, t.location.column)
+                                <= (next.location.line, next.location.column)
+                    })
+                    .filter(|t| t.token.to_string() == found || format!("{:?}", t.token) == found)
+                    .last()
+            })
+            .unwrap_or(next);
+        if token.token == Token::EOF {
+            return self.end_location();
+        }
+        sql_location(
+            self.sql,
+            &token.location,
+            token.token.to_string().chars().count(),
+        )
+    }
+
+    /// Location right after the last token of the SQL
+    fn end_location(&self) -> SqlLocation {
+        let text = self.sql.trim_end();
+        let line = text.lines().count().max(1);
+        let column = text.lines().last().map_or(0, |l| l.chars().count()) + 1;
+        sql_location(
+            self.sql,
+            &Location {
+                line: line as u64,
+                column: column as u64,
+            },
+            1,
+        )
+    }
+
+    /// Finds `name` in the tokens of `range`, preferring an occurrence with the same
+    /// qualifier if `name` is a compound name like `table.column`
+    fn find_word(&self, range: Range<usize>, name: &str) -> Option<&TokenWithLocation> {
+        let mut parts: Vec<&str> = name.split('.').collect();
+        let last = parts.pop()?;
+        let is_word = |token: &TokenWithLocation, value: &str| matches!(&token.token, Token::Word(w) if w.value.eq_ignore_ascii_case(value));
+
+        let tokens = &self.tokens[range.clone()];
+        let qualified = parts.last().and_then(|qualifier| {
+            tokens.windows(3).find_map(|window| {
+                (is_word(&window[0], qualifier)
+                    && window[1].token == Token::Period
+                    && is_word(&window[2], last))
+                .then_some(&window[2])
+            })
+        });
+        qualified.or_else(|| tokens.iter().find(|t| is_word(t, last)))
+    }
+}
+
+/// Name of the column, function or relation an error is about
+fn error_subject(error: &PipelineError) -> Option<String> {
+    let subject = match error {
+        PipelineError::InvalidExpression(name)
+        | PipelineError::InvalidFunction(name)
+        | PipelineError::InvalidOperandType(name)
+        | PipelineError::InvalidFunctionArgument(name, _, _)
+        | PipelineError::InvalidFunctionArgumentType(name, _, _, _)
+        | PipelineError::TooManyArguments(name)
+        | PipelineError::NotEnoughArguments(name)
+        | PipelineError::InvalidNestedAggregationFunction(name)
+        | PipelineError::NameSpaceTooLong(name) => name.as_str(),
+        PipelineError::JoinError(
+            JoinError::FieldError(name)
+            | JoinError::AmbiguousField(name)
+            | JoinError::InvalidFieldSpecified(name)
+            | JoinError::NameSpaceTooLong(name)
+            | JoinError::InvalidRelation(name),
+        ) => name.as_str(),
+        PipelineError::WindowError(
+            WindowError::InvalidArguments(name) | WindowError::InvalidTimestampField(name),
+        ) => name.as_str(),
+        PipelineError::UnsupportedSqlError(error) => match error {
+            UnsupportedSqlError::Recursive => "RECURSIVE",
+            UnsupportedSqlError::OrderByError => "ORDER",
+            UnsupportedSqlError::LimitOffsetError => "LIMIT",
+            UnsupportedSqlError::NotInSubqueryError => "NOT",
+            _ => return None,
+        },
+        PipelineError::InternalExecutionError(ExecutionError::InternalError(error))
+        | PipelineError::InternalError(error) => {
+            return error
+                .downcast_ref::<PipelineError>()
+                .and_then(error_subject)
+        }
+        _ => return None,
+    };
+
+    // Aggregations are reported like `COUNT(DISTINCT)`
+    let subject = subject.split('(').next().unwrap_or(subject).trim();
+    let is_name = !subject.is_empty()
+        && subject
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.');
+    is_name.then(|| subject.to_string())
+}
+
+fn sql_location(sql: &str, location: &Location, length: usize) -> SqlLocation {
+    let line = location.line.max(1) as usize;
+    SqlLocation {
+        line,
+        column: location.column.max(1) as usize,
+        snippet: sql.lines().nth(line - 1).unwrap_or_default().to_string(),
+        length,
+    }
+}
@@ -45,6 +45,10 @@ pub enum PipelineError {
     InvalidValue(String),
     #[error("Invalid query: {0}")]
     InvalidQuery(String),
+    #[error("Arithmetic overflow in function: {0}()")]
+    ArithmeticOverflow(String),
+    #[error("Invalid interval: {0}. Intervals are expressed like INTERVAL '5' MINUTE")]
+    InvalidInterval(String),
     #[error("Invalid relation")]
     InvalidRelation,
     #[error("Invalid relation")]
@@ -59,6 +63,8 @@ pub enum PipelineError {
         "Invalid argument type for function {0}(): type: {1}, expected types: {2}, index: {3}"
     )]
     InvalidFunctionArgumentType(String, FieldType, FieldTypes, usize),
+    #[error("A function named {0}() is already registered")]
+    UdfAlreadyRegistered(String),
     #[error("Invalid cast: from: {from}, to: {to}")]
     InvalidCast { from: Field, to: FieldType },
     #[error("{0}() is invoked from another aggregation function. Nesting of aggregation functions is not possible.")]
@@ -67,6 +73,12 @@ pub enum PipelineError {
     #[error("Currently join supports two level of namespacing. For example, `connection1.field1` is valid, but `connection1.n1.field1` is not.")]
     NameSpaceTooLong(String),
 
+    #[error("{error}\n{location}")]
+    SqlError {
+        error: Box<PipelineError>,
+        location: SqlLocation,
+    },
+
     // Error forwarding
     #[error(transparent)]
     InternalStorageError(#[from] StorageError),
@@ -82,6 +94,43 @@ pub enum PipelineError {
 
     #[error(transparent)]
     JoinError(#[from] JoinError),
+
+    #[error(transparent)]
+    WindowError(#[from] WindowError),
+
+    #[error(transparent)]
+    WasmError(#[from] WasmError),
+}
+
+/// Position of an error in the SQL text, displayed with a caret under the offending token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlLocation {
+    /// Line number, starting from 1
+    pub line: usize,
+    /// Column of the offending token in the line, starting from 1
+    pub column: usize,
+    /// The line of SQL containing the offending token
+    pub snippet: String,
+    /// Length of the offending token, in characters
+    pub length: usize,
+}
+
+impl Display for SqlLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        // Keep the tabs of the snippet, so that the caret lines up with the token
+        let padding: String = self
+            .snippet
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        writeln!(f, "{gutter}--> line {}, column {}", self.line, self.column)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{line_number} | {}", self.snippet)?;
+        write!(f, "{gutter} | {padding}{}", "^".repeat(self.length.max(1)))
+    }
 }
 
 #[derive(Error, Debug)]
@@ -92,15 +141,29 @@ pub enum UnsupportedSqlError {
     CteFromError,
     #[error("Currently only SELECT operations are allowed")]
     SelectOnlyError,
+    #[error("Currently only queries and CREATE VIEW statements are allowed")]
+    StatementError,
+    #[error("Column names are not supported in CREATE VIEW, please alias the columns of the query instead")]
+    ViewColumnsError,
+    #[error("Currently only UNION and UNION ALL set operations are allowed")]
+    UnionOnlyError,
     #[error("Unsupported syntax in fROM clause")]
     JoinTable,
 
     #[error("FROM clause doesn't support \"Comma Syntax\"")]
     FromCommaSyntax,
-    #[error("ORDER BY is not supported in SQL. You could achieve the same by using the ORDER BY operator in the cache and APIs")]
+    #[error("ORDER BY is only supported in SQL together with LIMIT. You could achieve the same by using the ORDER BY operator in the cache and APIs")]
     OrderByError,
-    #[error("Limit and Offset are not supported in SQL. You could achieve the same by using the LIMIT and OFFSET operators in the cache and APIs")]
+    #[error("Limit and Offset are only supported in SQL together with ORDER BY. You could achieve the same by using the LIMIT and OFFSET operators in the cache and APIs")]
     LimitOffsetError,
+    #[error("Subqueries are only supported in IN and EXISTS conditions combined with AND in the WHERE clause")]
+    SubqueryError,
+    #[error(
+        "NOT IN is not supported with a subquery. You could achieve the same by using NOT EXISTS"
+    )]
+    NotInSubqueryError,
+    #[error("Window frames such as ROWS BETWEEN and RANGE are not supported. Window functions are computed over the rows from the start of the partition to the current row and its peers")]
+    WindowFrameError,
 }
 
 #[derive(Error, Debug)]
@@ -122,3 +185,29 @@ pub enum JoinError {
     #[error("Invalid Table name specified")]
     InvalidRelation(String),
 }
+
+#[derive(Error, Debug)]
+pub enum WindowError {
+    #[error("Invalid arguments for window function {0}()")]
+    InvalidArguments(String),
+    #[error("Invalid window interval: {0}. Window intervals are positive and fixed, like INTERVAL '5' MINUTE, so MONTH and YEAR are not supported")]
+    InvalidInterval(String),
+    #[error("Window field {0} is not a timestamp")]
+    InvalidTimestampField(String),
+    #[error("Only one TUMBLE() or HOP() window is allowed in GROUP BY")]
+    MultipleWindows,
+    #[error("Window retention overflows the timestamp range from timestamp {0} ms")]
+    RetentionOverflow(i64),
+}
+
+#[derive(Error, Debug)]
+pub enum WasmError {
+    #[error("Invalid WASM module {0}: {1}")]
+    InvalidModule(String, String),
+    #[error("Function {0} is not exported by WASM module {1}")]
+    FunctionNotFound(String, String),
+    #[error("WASM function {0} must take and return integers or floats, and return one value")]
+    UnsupportedSignature(String),
+    #[error("WASM function {0} failed: {1}")]
+    Trap(String, String),
+}
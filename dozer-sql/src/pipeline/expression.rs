@@ -3,11 +3,15 @@ mod arg_utils;
 pub mod builder;
 pub mod cast;
 pub mod comparison;
+pub mod conditional;
 pub mod execution;
+pub mod interval;
 pub mod logical;
 pub mod mathematical;
 pub mod operator;
 pub mod scalar;
+pub mod udf;
+pub mod wasm;
 
 #[cfg(test)]
 mod tests;
@@ -1,11 +1,17 @@
+use crate::argv;
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::errors::PipelineError::InvalidFunction;
+use crate::pipeline::expression::execution::Expression;
+use dozer_types::types::Field;
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum AggregateFunctionType {
+    ApproxCountDistinct,
+    ApproxPercentile,
     Avg,
     Count,
+    CountDistinct,
     Max,
     Median,
     Min,
@@ -17,6 +23,8 @@ pub enum AggregateFunctionType {
 impl AggregateFunctionType {
     pub(crate) fn new(name: &str) -> Result<AggregateFunctionType, PipelineError> {
         match name {
+            "approx_count_distinct" => Ok(AggregateFunctionType::ApproxCountDistinct),
+            "approx_percentile" => Ok(AggregateFunctionType::ApproxPercentile),
             "avg" => Ok(AggregateFunctionType::Avg),
             "count" => Ok(AggregateFunctionType::Count),
             "max" => Ok(AggregateFunctionType::Max),
@@ -28,13 +36,24 @@ impl AggregateFunctionType {
             _ => Err(InvalidFunction(name.to_string())),
         }
     }
+
+    /// Returns the variant of this function applied to the distinct values of its argument
+    pub(crate) fn distinct(self) -> Result<AggregateFunctionType, PipelineError> {
+        match self {
+            AggregateFunctionType::Count => Ok(AggregateFunctionType::CountDistinct),
+            _ => Err(InvalidFunction(format!("{self}(DISTINCT)"))),
+        }
+    }
 }
 
 impl Display for AggregateFunctionType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            AggregateFunctionType::ApproxCountDistinct => f.write_str("APPROX_COUNT_DISTINCT"),
+            AggregateFunctionType::ApproxPercentile => f.write_str("APPROX_PERCENTILE"),
             AggregateFunctionType::Avg => f.write_str("AVG"),
             AggregateFunctionType::Count => f.write_str("COUNT"),
+            AggregateFunctionType::CountDistinct => f.write_str("COUNT DISTINCT"),
             AggregateFunctionType::Max => f.write_str("MAX"),
             AggregateFunctionType::Median => f.write_str("MEDIAN"),
             AggregateFunctionType::Min => f.write_str("MIN"),
@@ -44,3 +63,27 @@ impl Display for AggregateFunctionType {
         }
     }
 }
+
+/// Percentile of `APPROX_PERCENTILE`, its second argument, which must be a constant
+/// between 0 and 1
+pub(crate) fn get_percentile(args: &[Expression]) -> Result<f64, PipelineError> {
+    let fun = AggregateFunctionType::ApproxPercentile;
+    if args.len() > 2 {
+        return Err(PipelineError::TooManyArguments(fun.to_string()));
+    }
+    match argv!(args, 1, fun)? {
+        Expression::Literal(field) => match field.to_float() {
+            Some(percentile) if field != &Field::Null && (0.0..=1.0).contains(&percentile) => {
+                Ok(percentile)
+            }
+            _ => Err(PipelineError::InvalidFunctionArgument(
+                fun.to_string(),
+                field.clone(),
+                1,
+            )),
+        },
+        _ => Err(PipelineError::InvalidArgument(format!(
+            "{fun}() percentile must be a constant"
+        ))),
+    }
+}
@@ -6,8 +6,9 @@ use dozer_types::{
 };
 
 use sqlparser::ast::{
-    BinaryOperator as SqlBinaryOperator, DataType, Expr as SqlExpr, Expr, Function, FunctionArg,
-    FunctionArgExpr, Ident, TrimWhereField, UnaryOperator as SqlUnaryOperator, Value as SqlValue,
+    BinaryOperator as SqlBinaryOperator, DataType, DateTimeField, Expr as SqlExpr, Expr, Function,
+    FunctionArg, FunctionArgExpr, Ident, JsonOperator, ObjectName, TrimWhereField,
+    UnaryOperator as SqlUnaryOperator, Value as SqlValue,
 };
 
 use crate::pipeline::errors::PipelineError;
@@ -16,11 +17,16 @@ use crate::pipeline::expression::builder::PipelineError::InvalidArgument;
 use crate::pipeline::expression::builder::PipelineError::InvalidExpression;
 use crate::pipeline::expression::builder::PipelineError::InvalidOperator;
 use crate::pipeline::expression::builder::PipelineError::InvalidValue;
+use crate::pipeline::expression::conditional::get_case_type;
 use crate::pipeline::expression::execution::Expression;
 use crate::pipeline::expression::execution::Expression::ScalarFunction;
+use crate::pipeline::expression::interval::parse_interval;
 use crate::pipeline::expression::operator::{BinaryOperatorType, UnaryOperatorType};
-use crate::pipeline::expression::scalar::common::ScalarFunctionType;
-use crate::pipeline::expression::scalar::string::TrimType;
+use crate::pipeline::expression::scalar::common::{get_scalar_function_type, ScalarFunctionType};
+use crate::pipeline::expression::scalar::datetime::evaluate_now;
+use crate::pipeline::expression::scalar::json::get_json_key_path;
+use crate::pipeline::expression::scalar::string::{compile_regex, TrimType};
+use crate::pipeline::expression::udf::get_udf;
 
 use super::cast::CastOperatorType;
 
@@ -98,6 +104,7 @@ impl ExpressionBuilder {
             SqlExpr::Value(SqlValue::SingleQuotedString(s) | SqlValue::DoubleQuotedString(s)) => {
                 parse_sql_string(s)
             }
+            SqlExpr::Value(SqlValue::DollarQuotedString(s)) => parse_sql_string(&s.value),
             SqlExpr::UnaryOp { expr, op } => {
                 self.parse_sql_unary_op(expression_type, op, expr, schema)
             }
@@ -105,6 +112,11 @@ impl ExpressionBuilder {
                 self.parse_sql_binary_op(expression_type, left, op, right, schema)
             }
             SqlExpr::Nested(expr) => self.parse_sql_expression(expression_type, expr, schema),
+            SqlExpr::Function(sql_function) if sql_function.over.is_some() => {
+                Err(InvalidExpression(format!(
+                    "{expression}. Window functions are only supported as SELECT items"
+                )))
+            }
             SqlExpr::Function(sql_function) => match expression_type {
                 BuilderExpressionType::PreAggregation => self.parse_sql_function_pre_aggregation(
                     expression_type,
@@ -139,10 +151,237 @@ impl ExpressionBuilder {
             SqlExpr::Cast { expr, data_type } => {
                 self.parse_sql_cast_operator(expression_type, expr, data_type, schema)
             }
+            SqlExpr::IsNull(expr) => {
+                let (arg, bypass) = self.parse_sql_expression(expression_type, expr, schema)?;
+                if bypass {
+                    return Ok((arg, bypass));
+                }
+                Ok((Box::new(Expression::IsNull { arg }), false))
+            }
+            SqlExpr::IsNotNull(expr) => {
+                let (arg, bypass) = self.parse_sql_expression(expression_type, expr, schema)?;
+                if bypass {
+                    return Ok((arg, bypass));
+                }
+                Ok((Box::new(Expression::IsNotNull { arg }), false))
+            }
+            SqlExpr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let mut args = vec![expr.as_ref()];
+                args.extend(list);
+                let mut args = match self.parse_sql_expressions(expression_type, &args, schema)? {
+                    Ok(args) => args.into_iter(),
+                    Err(bypass) => return Ok((bypass, true)),
+                };
+                Ok((
+                    Box::new(Expression::InList {
+                        expr: Box::new(args.next().unwrap()),
+                        list: args.collect(),
+                        negated: *negated,
+                    }),
+                    false,
+                ))
+            }
+            SqlExpr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                let args = [expr.as_ref(), low.as_ref(), high.as_ref()];
+                let mut args = match self.parse_sql_expressions(expression_type, &args, schema)? {
+                    Ok(args) => args.into_iter().map(Box::new),
+                    Err(bypass) => return Ok((bypass, true)),
+                };
+                Ok((
+                    Box::new(Expression::Between {
+                        expr: args.next().unwrap(),
+                        low: args.next().unwrap(),
+                        high: args.next().unwrap(),
+                        negated: *negated,
+                    }),
+                    false,
+                ))
+            }
+            SqlExpr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+            } => self.parse_sql_case(
+                expression_type,
+                operand,
+                conditions,
+                results,
+                else_result,
+                schema,
+            ),
+            SqlExpr::JsonAccess {
+                left,
+                operator,
+                right,
+            } => self.parse_sql_json_access(expression_type, left, operator, right, schema),
+            SqlExpr::Substring {
+                expr,
+                substring_from,
+                substring_for,
+            } => {
+                let from = substring_from.clone().unwrap_or_else(|| {
+                    Box::new(SqlExpr::Value(SqlValue::Number("1".to_string(), false)))
+                });
+                let mut args = vec![expr.as_ref(), from.as_ref()];
+                args.extend(substring_for.as_deref());
+                self.parse_sql_scalar_function(
+                    expression_type,
+                    ScalarFunctionType::Substring,
+                    &args,
+                    schema,
+                )
+            }
+            SqlExpr::Ceil {
+                expr,
+                field: DateTimeField::NoDateTime,
+            } => self.parse_sql_scalar_function(
+                expression_type,
+                ScalarFunctionType::Ceil,
+                &[expr],
+                schema,
+            ),
+            SqlExpr::Floor {
+                expr,
+                field: DateTimeField::NoDateTime,
+            } => self.parse_sql_scalar_function(
+                expression_type,
+                ScalarFunctionType::Floor,
+                &[expr],
+                schema,
+            ),
+            SqlExpr::Position { expr, r#in } => self.parse_sql_scalar_function(
+                expression_type,
+                ScalarFunctionType::Position,
+                &[expr, r#in],
+                schema,
+            ),
+            SqlExpr::ILike {
+                negated,
+                expr,
+                pattern,
+                escape_char,
+            } => {
+                // ILIKE is LIKE over the lowercase values
+                let lower = |expr: &Expr| {
+                    SqlExpr::Function(Function {
+                        name: ObjectName(vec![Ident::new("LOWER")]),
+                        args: vec![FunctionArg::Unnamed(FunctionArgExpr::Expr(expr.clone()))],
+                        over: None,
+                        distinct: false,
+                        special: false,
+                    })
+                };
+                self.parse_sql_like_operator(
+                    expression_type,
+                    negated,
+                    &lower(expr),
+                    &lower(pattern),
+                    escape_char,
+                    schema,
+                )
+            }
+            SqlExpr::Extract { field, expr } => {
+                let (arg, bypass) = self.parse_sql_expression(expression_type, expr, schema)?;
+                if bypass {
+                    return Ok((arg, bypass));
+                }
+                Ok((
+                    Box::new(ScalarFunction {
+                        fun: ScalarFunctionType::Extract,
+                        args: vec![
+                            Expression::Literal(Field::String(field.to_string().to_lowercase())),
+                            *arg,
+                        ],
+                    }),
+                    false,
+                ))
+            }
             _ => Err(InvalidExpression(format!("{expression:?}"))),
         }
     }
 
+    fn parse_sql_scalar_function(
+        &self,
+        expression_type: &BuilderExpressionType,
+        fun: ScalarFunctionType,
+        args: &[&Expr],
+        schema: &Schema,
+    ) -> Result<(Box<Expression>, bool), PipelineError> {
+        let mut arg_exprs = vec![];
+        for arg in args {
+            let (arg, bypass) = self.parse_sql_expression(expression_type, arg, schema)?;
+            if bypass {
+                return Ok((arg, bypass));
+            }
+            arg_exprs.push(*arg);
+        }
+        Ok((build_scalar_function(fun, arg_exprs, schema)?, false))
+    }
+
+    fn parse_sql_json_access(
+        &self,
+        expression_type: &BuilderExpressionType,
+        left: &Expr,
+        operator: &JsonOperator,
+        right: &Expr,
+        schema: &Schema,
+    ) -> Result<(Box<Expression>, bool), PipelineError> {
+        // The parser groups `a -> 'b' -> 'c'` as `a -> ('b' -> 'c')`
+        if let SqlExpr::JsonAccess {
+            left: key,
+            operator: next_operator,
+            right: next_right,
+        } = right
+        {
+            let left = SqlExpr::JsonAccess {
+                left: Box::new(left.clone()),
+                operator: *operator,
+                right: key.clone(),
+            };
+            return self.parse_sql_json_access(
+                expression_type,
+                &left,
+                next_operator,
+                next_right,
+                schema,
+            );
+        }
+
+        let fun = match operator {
+            JsonOperator::Arrow => ScalarFunctionType::JsonExtract,
+            JsonOperator::LongArrow => ScalarFunctionType::JsonValue,
+            _ => return Err(InvalidOperator(format!("{operator:?}"))),
+        };
+        let path = match right {
+            SqlExpr::Value(SqlValue::SingleQuotedString(key)) => get_json_key_path(key),
+            SqlExpr::Value(SqlValue::Number(index, _)) if index.parse::<usize>().is_ok() => {
+                format!("$[{index}]")
+            }
+            _ => return Err(InvalidArgument(right.to_string())),
+        };
+        let (arg, bypass) = self.parse_sql_expression(expression_type, left, schema)?;
+        if bypass {
+            return Ok((arg, bypass));
+        }
+        Ok((
+            Box::new(ScalarFunction {
+                fun,
+                args: vec![*arg, Expression::Literal(Field::String(path))],
+            }),
+            false,
+        ))
+    }
+
     fn parse_sql_trim_function(
         &self,
         expression_type: &BuilderExpressionType,
@@ -190,19 +429,16 @@ impl ExpressionBuilder {
                 }
             }
 
-            return Ok((
-                Box::new(ScalarFunction {
-                    fun: function,
-                    args: arg_exprs,
-                }),
-                false,
-            ));
+            return Ok((build_scalar_function(function, arg_exprs, schema)?, false));
         };
         if AggregateFunctionType::new(&name).is_ok() {
             let arg = sql_function.args.first().unwrap();
             let r = self.parse_sql_function_arg(expression_type, arg, schema)?;
             return Ok((r.0, false)); // switch bypass to true, since the argument of this Aggregation must be the final result
         };
+        if let Some(result) = self.parse_sql_udf(expression_type, sql_function, schema)? {
+            return Ok(result);
+        }
         Err(InvalidExpression(format!("{expression:?}")))
     }
 
@@ -233,19 +469,16 @@ impl ExpressionBuilder {
                 }
             }
 
-            return Ok((
-                Box::new(ScalarFunction {
-                    fun: function,
-                    args: arg_exprs,
-                }),
-                false,
-            ));
+            return Ok((build_scalar_function(function, arg_exprs, schema)?, false));
         };
         if AggregateFunctionType::new(&name).is_ok() {
             let arg = sql_function.args.first().unwrap();
             let r = self.parse_sql_function_arg(expression_type, arg, schema)?;
             return Ok((r.0, true)); // switch bypass to true, since the argument of this Aggregation must be the final result
         };
+        if let Some(result) = self.parse_sql_udf(expression_type, sql_function, schema)? {
+            return Ok(result);
+        }
         Err(InvalidExpression(format!("{expression:?}")))
     }
 
@@ -276,16 +509,15 @@ impl ExpressionBuilder {
                 }
             }
 
-            return Ok((
-                Box::new(ScalarFunction {
-                    fun: function,
-                    args: arg_exprs,
-                }),
-                false,
-            ));
+            return Ok((build_scalar_function(function, arg_exprs, schema)?, false));
         };
 
         if let Ok(function) = AggregateFunctionType::new(&name) {
+            let function = if sql_function.distinct {
+                function.distinct()?
+            } else {
+                function
+            };
             let mut arg_exprs = vec![];
             for arg in &sql_function.args {
                 let r = self.parse_sql_function_arg(expression_type, arg, schema);
@@ -312,11 +544,43 @@ impl ExpressionBuilder {
             ));
         };
 
+        if let Some(result) = self.parse_sql_udf(expression_type, sql_function, schema)? {
+            return Ok(result);
+        }
+
         Err(InvalidExpression(format!(
             "Unsupported Expression: {expression:?}"
         )))
     }
 
+    /// Builds a call to a registered user-defined function, if `sql_function` names one
+    fn parse_sql_udf(
+        &self,
+        expression_type: &BuilderExpressionType,
+        sql_function: &Function,
+        schema: &Schema,
+    ) -> Result<Option<(Box<Expression>, bool)>, PipelineError> {
+        let udf = match get_udf(&sql_function.name.to_string()) {
+            Some(udf) => udf,
+            None => return Ok(None),
+        };
+        let mut arg_exprs = vec![];
+        for arg in &sql_function.args {
+            let (arg, bypass) = self.parse_sql_function_arg(expression_type, arg, schema)?;
+            if bypass {
+                return Ok(Some((arg, bypass)));
+            }
+            arg_exprs.push(*arg);
+        }
+        Ok(Some((
+            Box::new(Expression::ScalarUdf {
+                udf,
+                args: arg_exprs,
+            }),
+            false,
+        )))
+    }
+
     fn parse_sql_function_arg(
         &self,
         expression_type: &BuilderExpressionType,
@@ -372,6 +636,38 @@ impl ExpressionBuilder {
         right: &SqlExpr,
         schema: &Schema,
     ) -> Result<(Box<Expression>, bool), PipelineError> {
+        match (left, op, right) {
+            (arg, SqlBinaryOperator::Plus | SqlBinaryOperator::Minus, SqlExpr::Interval { .. })
+            | (SqlExpr::Interval { .. }, SqlBinaryOperator::Plus, arg) => {
+                let interval = if matches!(left, SqlExpr::Interval { .. }) {
+                    left
+                } else {
+                    right
+                };
+                let (arg, bypass) = self.parse_sql_expression(expression_type, arg, schema)?;
+                if bypass {
+                    return Ok((arg, bypass));
+                }
+                return Ok((
+                    Box::new(Expression::IntervalOperator {
+                        arg,
+                        interval: parse_interval(interval)?,
+                        subtract: matches!(op, SqlBinaryOperator::Minus),
+                    }),
+                    false,
+                ));
+            }
+            (_, SqlBinaryOperator::StringConcat, _) => {
+                return self.parse_sql_scalar_function(
+                    expression_type,
+                    ScalarFunctionType::Concat,
+                    &[left, right],
+                    schema,
+                );
+            }
+            _ => {}
+        }
+
         let (left_op, bypass_left) = self.parse_sql_expression(expression_type, left, schema)?;
         if bypass_left {
             return Ok((left_op, bypass_left));
@@ -400,7 +696,6 @@ impl ExpressionBuilder {
 
             // BinaryOperator::BitwiseAnd => ...
             // BinaryOperator::BitwiseOr => ...
-            // BinaryOperator::StringConcat => ...
             _ => return Err(InvalidOperator(format!("{op:?}"))),
         };
 
@@ -414,6 +709,60 @@ impl ExpressionBuilder {
         ))
     }
 
+    /// Parses the expressions in order, returning the first one to bypass as an error
+    fn parse_sql_expressions(
+        &self,
+        expression_type: &BuilderExpressionType,
+        expressions: &[&SqlExpr],
+        schema: &Schema,
+    ) -> Result<Result<Vec<Expression>, Box<Expression>>, PipelineError> {
+        let mut parsed = vec![];
+        for expression in expressions {
+            let (expression, bypass) =
+                self.parse_sql_expression(expression_type, expression, schema)?;
+            if bypass {
+                return Ok(Err(expression));
+            }
+            parsed.push(*expression);
+        }
+        Ok(Ok(parsed))
+    }
+
+    fn parse_sql_case(
+        &self,
+        expression_type: &BuilderExpressionType,
+        operand: &Option<Box<SqlExpr>>,
+        conditions: &[SqlExpr],
+        results: &[SqlExpr],
+        else_result: &Option<Box<SqlExpr>>,
+        schema: &Schema,
+    ) -> Result<(Box<Expression>, Bypass), PipelineError> {
+        let mut args: Vec<&SqlExpr> = operand.iter().map(|e| e.as_ref()).collect();
+        args.extend(conditions);
+        args.extend(results);
+        args.extend(else_result.iter().map(|e| e.as_ref()));
+        let mut args = match self.parse_sql_expressions(expression_type, &args, schema)? {
+            Ok(args) => args.into_iter(),
+            Err(bypass) => return Ok((bypass, true)),
+        };
+
+        let operand = operand.as_ref().map(|_| Box::new(args.next().unwrap()));
+        let conditions = args.by_ref().take(conditions.len()).collect();
+        let results: Vec<Expression> = args.by_ref().take(results.len()).collect();
+        let else_result = args.next().map(Box::new);
+        let return_type = get_case_type(&results, &else_result, schema)?.return_type;
+        Ok((
+            Box::new(Expression::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+                return_type,
+            }),
+            false,
+        ))
+    }
+
     fn parse_sql_number(&self, n: &str) -> Result<(Box<Expression>, Bypass), PipelineError> {
         match n.parse::<i64>() {
             Ok(n) => Ok((Box::new(Expression::Literal(Field::Int(n))), false)),
@@ -465,27 +814,50 @@ impl ExpressionBuilder {
     ) -> Result<(Box<Expression>, bool), PipelineError> {
         let expression = self.parse_sql_expression(expression_type, expr, schema)?;
         let cast_to = match data_type {
-            DataType::Decimal(_) => CastOperatorType::Decimal,
-            DataType::Binary(_) => CastOperatorType::Binary,
-            DataType::Float(_) => CastOperatorType::Float,
-            DataType::Int(_) => CastOperatorType::Int,
-            DataType::Integer(_) => CastOperatorType::Int,
-            DataType::UnsignedInt(_) => CastOperatorType::UInt,
-            DataType::UnsignedInteger(_) => CastOperatorType::UInt,
+            DataType::Decimal(_) | DataType::Numeric(_) | DataType::Dec(_) => {
+                CastOperatorType::Decimal
+            }
+            DataType::Binary(_) | DataType::Varbinary(_) | DataType::Blob(_) | DataType::Bytea => {
+                CastOperatorType::Binary
+            }
+            DataType::Float(_) | DataType::Real | DataType::Double | DataType::DoublePrecision => {
+                CastOperatorType::Float
+            }
+            DataType::Int(_)
+            | DataType::Integer(_)
+            | DataType::TinyInt(_)
+            | DataType::SmallInt(_)
+            | DataType::MediumInt(_)
+            | DataType::BigInt(_) => CastOperatorType::Int,
+            DataType::UnsignedInt(_)
+            | DataType::UnsignedInteger(_)
+            | DataType::UnsignedTinyInt(_)
+            | DataType::UnsignedSmallInt(_)
+            | DataType::UnsignedMediumInt(_)
+            | DataType::UnsignedBigInt(_) => CastOperatorType::UInt,
             DataType::Boolean => CastOperatorType::Boolean,
             DataType::Date => CastOperatorType::Date,
-            DataType::Timestamp(..) => CastOperatorType::Timestamp,
+            DataType::Timestamp(..) | DataType::Datetime(_) => CastOperatorType::Timestamp,
             DataType::Text => CastOperatorType::Text,
-            DataType::String => CastOperatorType::String,
-            DataType::Custom(name, ..) => {
-                if name.to_string().to_lowercase() == "bson" {
-                    CastOperatorType::Bson
-                } else {
-                    Err(PipelineError::InvalidFunction(format!(
-                        "Unsupported Cast type {name}"
-                    )))?
-                }
-            }
+            DataType::String
+            | DataType::Char(_)
+            | DataType::Character(_)
+            | DataType::CharVarying(_)
+            | DataType::CharacterVarying(_)
+            | DataType::Varchar(_)
+            | DataType::Nvarchar(_)
+            | DataType::Uuid => CastOperatorType::String,
+            DataType::Custom(name, ..) => match name.to_string().to_lowercase().as_str() {
+                "bson" => CastOperatorType::Bson,
+                // Postgres aliases, which are not keywords of the parser
+                "int2" | "int4" | "int8" => CastOperatorType::Int,
+                "float4" | "float8" => CastOperatorType::Float,
+                "bool" => CastOperatorType::Boolean,
+                "timestamptz" => CastOperatorType::Timestamp,
+                _ => Err(PipelineError::InvalidFunction(format!(
+                    "Unsupported Cast type {name}"
+                )))?,
+            },
             _ => Err(PipelineError::InvalidFunction(format!(
                 "Unsupported Cast type {data_type}"
             )))?,
@@ -508,6 +880,37 @@ pub fn fullname_from_ident(ident: &[Ident]) -> String {
     ident_tokens.join(".")
 }
 
+/// Builds a call to a scalar function. `NOW()` is evaluated once, when the expression is built,
+/// so that the records retracted by the stateful operators are the same as the ones they
+/// inserted. Regular expressions are compiled once too, when they are literals.
+fn build_scalar_function(
+    fun: ScalarFunctionType,
+    args: Vec<Expression>,
+    schema: &Schema,
+) -> Result<Box<Expression>, PipelineError> {
+    if fun == ScalarFunctionType::Now {
+        if !args.is_empty() {
+            return Err(PipelineError::TooManyArguments(fun.to_string()));
+        }
+        return Ok(Box::new(Expression::Literal(evaluate_now())));
+    }
+    if let Some(regex) = compile_regex(&fun, &args)? {
+        return Ok(Box::new(Expression::RegexFunction { fun, args, regex }));
+    }
+    if matches!(
+        fun,
+        ScalarFunctionType::Coalesce | ScalarFunctionType::Ifnull
+    ) {
+        let return_type = get_scalar_function_type(&fun, &args, schema)?.return_type;
+        return Ok(Box::new(Expression::CoalesceFunction {
+            fun,
+            args,
+            return_type,
+        }));
+    }
+    Ok(Box::new(ScalarFunction { fun, args }))
+}
+
 fn parse_sql_string(s: &str) -> Result<(Box<Expression>, bool), PipelineError> {
     Ok((
         Box::new(Expression::Literal(Field::String(s.to_owned()))),
@@ -584,13 +987,10 @@ pub fn get_field_index(
             let table_name = comp_ident.first().expect("table_name is expected");
             let field_name = comp_ident.last().expect("field_name is expected");
 
-            let field_idx = get_field_idx(field_name, schema);
-            if let Some((idx, fd)) = field_idx {
-                if tables_matches(table_name, fd) {
-                    return Ok(Some(idx));
-                }
-            }
-            Ok(None)
+            // Joined tables can have fields with the same name
+            Ok(schema.fields.iter().position(|fd| {
+                fd.name == field_name.value && tables_matches(table_name, fd.clone())
+            }))
         }
     }
 }
@@ -8,6 +8,7 @@ use dozer_types::{
 use crate::pipeline::errors::{FieldTypes, PipelineError};
 
 use super::execution::{Expression, ExpressionExecutor, ExpressionType};
+use super::scalar::json::{json_value_to_field, parse_json};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum CastOperatorType {
@@ -49,7 +50,13 @@ impl CastOperatorType {
         arg: &Expression,
         record: &Record,
     ) -> Result<Field, PipelineError> {
-        let field = arg.evaluate(record, schema)?;
+        let field = match arg.evaluate(record, schema)? {
+            // JSON values are cast by their content
+            Field::Bson(bytes) if !matches!(self, CastOperatorType::Bson) => {
+                parse_json(&bytes).map_or(Field::Bson(bytes), |value| json_value_to_field(&value))
+            }
+            field => field,
+        };
         match self {
             CastOperatorType::UInt => {
                 if let Some(value) = field.to_uint() {
@@ -171,15 +178,26 @@ impl CastOperatorType {
     ) -> Result<ExpressionType, PipelineError> {
         let (expected_input_type, return_type) = match self {
             CastOperatorType::UInt => (
-                vec![FieldType::Int, FieldType::String, FieldType::UInt],
+                vec![
+                    FieldType::Bson,
+                    FieldType::Int,
+                    FieldType::String,
+                    FieldType::UInt,
+                ],
                 FieldType::UInt,
             ),
             CastOperatorType::Int => (
-                vec![FieldType::Int, FieldType::String, FieldType::UInt],
+                vec![
+                    FieldType::Bson,
+                    FieldType::Int,
+                    FieldType::String,
+                    FieldType::UInt,
+                ],
                 FieldType::Int,
             ),
             CastOperatorType::Float => (
                 vec![
+                    FieldType::Bson,
                     FieldType::Decimal,
                     FieldType::Float,
                     FieldType::Int,
@@ -191,6 +209,7 @@ impl CastOperatorType {
             CastOperatorType::Boolean => (
                 vec![
                     FieldType::Boolean,
+                    FieldType::Bson,
                     FieldType::Decimal,
                     FieldType::Float,
                     FieldType::Int,
@@ -202,6 +221,7 @@ impl CastOperatorType {
                 vec![
                     FieldType::Binary,
                     FieldType::Boolean,
+                    FieldType::Bson,
                     FieldType::Date,
                     FieldType::Decimal,
                     FieldType::Float,
@@ -217,6 +237,7 @@ impl CastOperatorType {
                 vec![
                     FieldType::Binary,
                     FieldType::Boolean,
+                    FieldType::Bson,
                     FieldType::Date,
                     FieldType::Decimal,
                     FieldType::Float,
@@ -240,10 +261,13 @@ impl CastOperatorType {
                 FieldType::Decimal,
             ),
             CastOperatorType::Timestamp => (
-                vec![FieldType::String, FieldType::Timestamp],
+                vec![FieldType::Bson, FieldType::String, FieldType::Timestamp],
                 FieldType::Timestamp,
             ),
-            CastOperatorType::Date => (vec![FieldType::Date, FieldType::String], FieldType::Date),
+            CastOperatorType::Date => (
+                vec![FieldType::Bson, FieldType::Date, FieldType::String],
+                FieldType::Date,
+            ),
             CastOperatorType::Bson => (vec![FieldType::Bson], FieldType::Bson),
         };
 
@@ -0,0 +1,244 @@
+use std::cmp::Ordering;
+
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType, Record, Schema, SourceDefinition};
+
+/// Compares two values, returning `None` if any of them is null
+pub(crate) fn compare_fields(
+    left: &Field,
+    right: &Field,
+    op: &str,
+) -> Result<Option<Ordering>, PipelineError> {
+    let invalid_operand = || PipelineError::InvalidOperandType(op.to_string());
+    let to_float = |field: &Field| field.to_float().ok_or_else(invalid_operand);
+    let to_decimal = |field: &Field| field.to_decimal().ok_or_else(invalid_operand);
+    let ordering = match (left, right) {
+        (Field::Null, _) | (_, Field::Null) => return Ok(None),
+        // Numbers of different types are compared in their common type
+        (Field::Int(_) | Field::UInt(_) | Field::Decimal(_), Field::Float(r)) => {
+            OrderedFloat(to_float(left)?).cmp(r)
+        }
+        (Field::Float(l), Field::Int(_) | Field::UInt(_) | Field::Decimal(_)) => {
+            l.cmp(&OrderedFloat(to_float(right)?))
+        }
+        (Field::Int(_) | Field::UInt(_), Field::Decimal(r)) => to_decimal(left)?.cmp(r),
+        (Field::Decimal(l), Field::Int(_) | Field::UInt(_)) => l.cmp(&to_decimal(right)?),
+        (Field::Int(l), Field::UInt(r)) => (*l as i128).cmp(&(*r as i128)),
+        (Field::UInt(l), Field::Int(r)) => (*l as i128).cmp(&(*r as i128)),
+        (Field::String(l) | Field::Text(l), Field::String(r) | Field::Text(r)) => l.cmp(r),
+        (l, r) if std::mem::discriminant(l) == std::mem::discriminant(r) => l.cmp(r),
+        _ => return Err(invalid_operand()),
+    };
+    Ok(Some(ordering))
+}
+
+pub fn evaluate_is_null(
+    schema: &Schema,
+    arg: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    Ok(Field::Boolean(arg.evaluate(record, schema)? == Field::Null))
+}
+
+/// `expr [NOT] IN (list)`, null if `expr` is null or if it is not found and the list
+/// contains a null
+pub fn evaluate_in_list(
+    schema: &Schema,
+    expr: &Expression,
+    list: &[Expression],
+    negated: bool,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let value = expr.evaluate(record, schema)?;
+    if value == Field::Null {
+        return Ok(Field::Null);
+    }
+
+    let mut has_null = false;
+    for item in list {
+        match compare_fields(&value, &item.evaluate(record, schema)?, "IN")? {
+            Some(Ordering::Equal) => return Ok(Field::Boolean(!negated)),
+            Some(_) => {}
+            None => has_null = true,
+        }
+    }
+    if has_null {
+        Ok(Field::Null)
+    } else {
+        Ok(Field::Boolean(negated))
+    }
+}
+
+/// `expr [NOT] BETWEEN low AND high`, null if the result depends on a null value
+pub fn evaluate_between(
+    schema: &Schema,
+    expr: &Expression,
+    low: &Expression,
+    high: &Expression,
+    negated: bool,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let value = expr.evaluate(record, schema)?;
+    let above_low = compare_fields(&value, &low.evaluate(record, schema)?, "BETWEEN")?
+        .map(|ordering| ordering != Ordering::Less);
+    let below_high = compare_fields(&value, &high.evaluate(record, schema)?, "BETWEEN")?
+        .map(|ordering| ordering != Ordering::Greater);
+
+    match (above_low, below_high) {
+        (Some(false), _) | (_, Some(false)) => Ok(Field::Boolean(negated)),
+        (Some(true), Some(true)) => Ok(Field::Boolean(!negated)),
+        _ => Ok(Field::Null),
+    }
+}
+
+/// `CASE [operand] WHEN condition THEN result ... [ELSE else_result] END`.
+/// Null conditions, and null operands, never match. The result is converted to the
+/// `return_type` given by [`get_case_type`].
+pub fn evaluate_case(
+    schema: &Schema,
+    operand: &Option<Box<Expression>>,
+    conditions: &[Expression],
+    results: &[Expression],
+    else_result: &Option<Box<Expression>>,
+    return_type: FieldType,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let operand = match operand {
+        Some(operand) => Some(operand.evaluate(record, schema)?),
+        None => None,
+    };
+
+    for (condition, result) in conditions.iter().zip(results) {
+        let condition = condition.evaluate(record, schema)?;
+        let matched = match &operand {
+            Some(operand) => compare_fields(operand, &condition, "CASE")? == Some(Ordering::Equal),
+            None => condition == Field::Boolean(true),
+        };
+        if matched {
+            return convert_case_result(result.evaluate(record, schema)?, return_type);
+        }
+    }
+
+    match else_result {
+        Some(else_result) => {
+            convert_case_result(else_result.evaluate(record, schema)?, return_type)
+        }
+        None => Ok(Field::Null),
+    }
+}
+
+fn convert_case_result(result: Field, return_type: FieldType) -> Result<Field, PipelineError> {
+    convert_field(&result, return_type).ok_or(PipelineError::InvalidCast {
+        from: result,
+        to: return_type,
+    })
+}
+
+/// Returns the type of `expression`, `None` for the `NULL` literal
+pub(crate) fn get_operand_type(
+    expression: &Expression,
+    schema: &Schema,
+) -> Result<Option<ExpressionType>, PipelineError> {
+    match expression {
+        Expression::Literal(Field::Null) => Ok(None),
+        _ => Ok(Some(expression.get_type(schema)?)),
+    }
+}
+
+/// Returns the type both `left` and `right` are promoted to, numbers being promoted along
+/// `Int`, `Decimal` and `Float`
+pub(crate) fn get_common_type(left: FieldType, right: FieldType) -> Option<FieldType> {
+    match (left, right) {
+        (l, r) if l == r => Some(l),
+        (FieldType::Int, FieldType::UInt) | (FieldType::UInt, FieldType::Int) => {
+            Some(FieldType::Int)
+        }
+        (FieldType::Int | FieldType::UInt | FieldType::Decimal, FieldType::Float)
+        | (FieldType::Float, FieldType::Int | FieldType::UInt | FieldType::Decimal) => {
+            Some(FieldType::Float)
+        }
+        (FieldType::Int | FieldType::UInt, FieldType::Decimal)
+        | (FieldType::Decimal, FieldType::Int | FieldType::UInt) => Some(FieldType::Decimal),
+        (FieldType::String, FieldType::Text) | (FieldType::Text, FieldType::String) => {
+            Some(FieldType::Text)
+        }
+        _ => None,
+    }
+}
+
+/// Converts a value of one of the types merged by [`get_common_type`] to `return_type`.
+/// Returns `None` if the value is out of the range of `return_type`.
+pub(crate) fn convert_field(field: &Field, return_type: FieldType) -> Option<Field> {
+    match (field, return_type) {
+        (Field::UInt(_), FieldType::Int) => field.to_int().map(Field::Int),
+        (Field::Int(_) | Field::UInt(_) | Field::Decimal(_), FieldType::Float) => {
+            field.to_float().map(|v| Field::Float(OrderedFloat(v)))
+        }
+        (Field::Int(_) | Field::UInt(_), FieldType::Decimal) => {
+            field.to_decimal().map(Field::Decimal)
+        }
+        (Field::String(v), FieldType::Text) => Some(Field::Text(v.clone())),
+        _ => Some(field.clone()),
+    }
+}
+
+/// Returns the type of a predicate over `args`, which is null when any of them is
+pub fn get_predicate_type(
+    args: &[&Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    let mut nullable = false;
+    for arg in args {
+        nullable |= match get_operand_type(arg, schema)? {
+            Some(arg_type) => arg_type.nullable,
+            None => true,
+        };
+    }
+    Ok(ExpressionType::new(
+        FieldType::Boolean,
+        nullable,
+        SourceDefinition::Dynamic,
+    ))
+}
+
+pub fn get_case_type(
+    results: &[Expression],
+    else_result: &Option<Box<Expression>>,
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    // Without ELSE, the records matching no condition are null
+    let mut nullable = else_result.is_none();
+    let mut return_type = None;
+    for result in results.iter().chain(else_result.iter().map(|e| e.as_ref())) {
+        match get_operand_type(result, schema)? {
+            Some(result_type) => {
+                nullable |= result_type.nullable;
+                return_type = match return_type {
+                    None => Some(result_type.return_type),
+                    Some(return_type) => Some(
+                        get_common_type(return_type, result_type.return_type).ok_or_else(|| {
+                            PipelineError::InvalidExpression(format!(
+                                "CASE results have different types: {return_type:?} and {:?}",
+                                result_type.return_type
+                            ))
+                        })?,
+                    ),
+                };
+            }
+            None => nullable = true,
+        }
+    }
+
+    match return_type {
+        Some(return_type) => Ok(ExpressionType::new(
+            return_type,
+            nullable,
+            SourceDefinition::Dynamic,
+        )),
+        None => Err(PipelineError::InvalidExpression(
+            "CASE results cannot all be null".to_string(),
+        )),
+    }
+}
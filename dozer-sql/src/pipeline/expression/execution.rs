@@ -3,12 +3,24 @@ use crate::pipeline::errors::PipelineError;
 
 use crate::pipeline::expression::operator::{BinaryOperatorType, UnaryOperatorType};
 use crate::pipeline::expression::scalar::common::{get_scalar_function_type, ScalarFunctionType};
-use crate::pipeline::expression::scalar::string::{evaluate_trim, validate_trim, TrimType};
+use crate::pipeline::expression::scalar::datetime::{
+    evaluate_interval_operator, get_interval_operator_type,
+};
+use crate::pipeline::expression::scalar::null::evaluate_coalesce;
+use crate::pipeline::expression::scalar::string::{
+    evaluate_string_function, evaluate_trim, validate_trim, CompiledRegex, TrimType,
+};
 use dozer_types::types::{Field, FieldType, Record, Schema, SourceDefinition};
 
-use super::aggregate::AggregateFunctionType;
+use super::aggregate::{get_percentile, AggregateFunctionType};
 use super::cast::CastOperatorType;
+use super::conditional::{
+    evaluate_between, evaluate_case, evaluate_in_list, evaluate_is_null, get_case_type,
+    get_predicate_type,
+};
+use super::interval::Interval;
 use super::scalar::string::{evaluate_like, get_like_operator_type};
+use super::udf::UdfFunction;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Expression {
@@ -29,6 +41,10 @@ pub enum Expression {
         fun: ScalarFunctionType,
         args: Vec<Expression>,
     },
+    ScalarUdf {
+        udf: UdfFunction,
+        args: Vec<Expression>,
+    },
     AggregateFunction {
         fun: AggregateFunctionType,
         args: Vec<Expression>,
@@ -47,6 +63,49 @@ pub enum Expression {
         pattern: Box<Expression>,
         escape: Option<char>,
     },
+    IsNull {
+        arg: Box<Expression>,
+    },
+    IsNotNull {
+        arg: Box<Expression>,
+    },
+    InList {
+        expr: Box<Expression>,
+        list: Vec<Expression>,
+        negated: bool,
+    },
+    Between {
+        expr: Box<Expression>,
+        low: Box<Expression>,
+        high: Box<Expression>,
+        negated: bool,
+    },
+    Case {
+        operand: Option<Box<Expression>>,
+        conditions: Vec<Expression>,
+        results: Vec<Expression>,
+        else_result: Option<Box<Expression>>,
+        /// Type the results are converted to, validated when the expression is built
+        return_type: FieldType,
+    },
+    IntervalOperator {
+        arg: Box<Expression>,
+        interval: Interval,
+        subtract: bool,
+    },
+    /// `REGEXP_LIKE` or `REGEXP_REPLACE` whose pattern and flags are literals
+    RegexFunction {
+        fun: ScalarFunctionType,
+        args: Vec<Expression>,
+        regex: CompiledRegex,
+    },
+    /// `COALESCE` or `IFNULL`, whose arguments are converted to the `return_type` validated
+    /// when the expression is built
+    CoalesceFunction {
+        fun: ScalarFunctionType,
+        args: Vec<Expression>,
+        return_type: FieldType,
+    },
 }
 
 pub struct ExpressionType {
@@ -88,6 +147,7 @@ impl ExpressionExecutor for Expression {
                 right,
             } => operator.evaluate(schema, left, right, record),
             Expression::ScalarFunction { fun, args } => fun.evaluate(schema, args, record),
+            Expression::ScalarUdf { udf, args } => udf.evaluate(schema, args, record),
             Expression::UnaryOperator { operator, arg } => operator.evaluate(schema, arg, record),
             Expression::AggregateFunction { fun, args: _ } => {
                 Err(PipelineError::InvalidExpression(format!(
@@ -101,6 +161,50 @@ impl ExpressionExecutor for Expression {
                 escape,
             } => evaluate_like(schema, arg, pattern, *escape, record),
             Expression::Cast { arg, typ } => typ.evaluate(schema, arg, record),
+            Expression::IsNull { arg } => evaluate_is_null(schema, arg, record),
+            Expression::IsNotNull { arg } => match evaluate_is_null(schema, arg, record)? {
+                Field::Boolean(is_null) => Ok(Field::Boolean(!is_null)),
+                field => Ok(field),
+            },
+            Expression::InList {
+                expr,
+                list,
+                negated,
+            } => evaluate_in_list(schema, expr, list, *negated, record),
+            Expression::Between {
+                expr,
+                low,
+                high,
+                negated,
+            } => evaluate_between(schema, expr, low, high, *negated, record),
+            Expression::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+                return_type,
+            } => evaluate_case(
+                schema,
+                operand,
+                conditions,
+                results,
+                else_result,
+                *return_type,
+                record,
+            ),
+            Expression::IntervalOperator {
+                arg,
+                interval,
+                subtract,
+            } => evaluate_interval_operator(schema, arg, interval, *subtract, record),
+            Expression::RegexFunction { fun, args, regex } => {
+                evaluate_string_function(fun, schema, args, Some(regex), record)
+            }
+            Expression::CoalesceFunction {
+                fun,
+                args,
+                return_type,
+            } => evaluate_coalesce(fun.clone(), schema, args, *return_type, record),
         }
     }
 
@@ -128,6 +232,7 @@ impl ExpressionExecutor for Expression {
                 right,
             } => get_binary_operator_type(left, operator, right, schema),
             Expression::ScalarFunction { fun, args } => get_scalar_function_type(fun, args, schema),
+            Expression::ScalarUdf { udf, args } => udf.get_type(args, schema),
             Expression::AggregateFunction { fun, args } => {
                 get_aggregate_function_type(fun, args, schema)
             }
@@ -142,6 +247,29 @@ impl ExpressionExecutor for Expression {
                 escape: _,
             } => get_like_operator_type(arg, pattern, schema),
             Expression::Cast { arg, typ } => typ.get_return_type(schema, arg),
+            Expression::IsNull { .. } | Expression::IsNotNull { .. } => Ok(ExpressionType::new(
+                FieldType::Boolean,
+                false,
+                SourceDefinition::Dynamic,
+            )),
+            Expression::InList { expr, list, .. } => {
+                let mut args = vec![expr.as_ref()];
+                args.extend(list);
+                get_predicate_type(&args, schema)
+            }
+            Expression::Between {
+                expr, low, high, ..
+            } => get_predicate_type(&[expr, low, high], schema),
+            Expression::Case {
+                results,
+                else_result,
+                ..
+            } => get_case_type(results, else_result, schema),
+            Expression::IntervalOperator { arg, .. } => get_interval_operator_type(arg, schema),
+            Expression::RegexFunction { fun, args, .. }
+            | Expression::CoalesceFunction { fun, args, .. } => {
+                get_scalar_function_type(fun, args, schema)
+            }
         }
     }
 }
@@ -262,16 +390,27 @@ fn get_aggregate_function_type(
     schema: &Schema,
 ) -> Result<ExpressionType, PipelineError> {
     match function {
-        AggregateFunctionType::Avg => Ok(ExpressionType::new(
-            FieldType::Float,
+        AggregateFunctionType::ApproxCountDistinct => Ok(ExpressionType::new(
+            FieldType::Int,
             false,
             SourceDefinition::Dynamic,
         )),
-        AggregateFunctionType::Count => Ok(ExpressionType::new(
-            FieldType::Int,
+        AggregateFunctionType::ApproxPercentile => {
+            get_percentile(args)?;
+            Ok(ExpressionType::new(
+                FieldType::Float,
+                true,
+                SourceDefinition::Dynamic,
+            ))
+        }
+        AggregateFunctionType::Avg => Ok(ExpressionType::new(
+            FieldType::Float,
             false,
             SourceDefinition::Dynamic,
         )),
+        AggregateFunctionType::Count | AggregateFunctionType::CountDistinct => Ok(
+            ExpressionType::new(FieldType::Int, false, SourceDefinition::Dynamic),
+        ),
         AggregateFunctionType::Max => argv!(args, 0, AggregateFunctionType::Max)?.get_type(schema),
         AggregateFunctionType::Median => {
             argv!(args, 0, AggregateFunctionType::Median)?.get_type(schema)
@@ -0,0 +1,112 @@
+use crate::pipeline::errors::PipelineError;
+use dozer_types::chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, TimeZone};
+use sqlparser::ast::{Expr as SqlExpr, Value};
+
+/// Interval of an `INTERVAL` expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interval {
+    /// Fixed duration, from milliseconds to weeks
+    Duration(Duration),
+    /// Calendar months, whose length varies. Years are counted as 12 months.
+    Months(i32),
+}
+
+impl Interval {
+    /// Adds (or subtracts) the interval to `ts`, returning `None` if the result is out of range.
+    /// Months keep the day of the month, clamped to the last day of the resulting month.
+    pub fn add_to(
+        &self,
+        ts: DateTime<FixedOffset>,
+        subtract: bool,
+    ) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Interval::Duration(duration) if subtract => ts.checked_sub_signed(*duration),
+            Interval::Duration(duration) => ts.checked_add_signed(*duration),
+            Interval::Months(months) => {
+                let months = if subtract {
+                    months.checked_neg()?
+                } else {
+                    *months
+                };
+                add_months(ts, months)
+            }
+        }
+    }
+}
+
+fn add_months(ts: DateTime<FixedOffset>, months: i32) -> Option<DateTime<FixedOffset>> {
+    let local = ts.naive_local();
+    let total = (local.year() * 12 + local.month0() as i32).checked_add(months)?;
+    let (year, month) = (total.div_euclid(12), total.rem_euclid(12) as u32 + 1);
+
+    let mut day = local.day();
+    let date = loop {
+        match NaiveDate::from_ymd_opt(year, month, day) {
+            Some(date) => break date,
+            None if day > 28 => day -= 1,
+            None => return None,
+        }
+    };
+    ts.offset()
+        .from_local_datetime(&date.and_time(local.time()))
+        .single()
+}
+
+/// Parses an interval such as `INTERVAL '5' MINUTE`, `INTERVAL '5 minutes'` or
+/// `INTERVAL '1' YEAR`
+pub(crate) fn parse_interval(expr: &SqlExpr) -> Result<Interval, PipelineError> {
+    let invalid = || PipelineError::InvalidInterval(expr.to_string());
+    let (value, leading_field) = match expr {
+        SqlExpr::Interval {
+            value,
+            leading_field,
+            last_field: None,
+            ..
+        } => (value, leading_field),
+        _ => return Err(invalid()),
+    };
+    let value = match value.as_ref() {
+        SqlExpr::Value(Value::SingleQuotedString(value) | Value::Number(value, _)) => value,
+        _ => return Err(invalid()),
+    };
+
+    let (amount, unit) = match leading_field {
+        Some(field) => (value.trim(), field.to_string()),
+        None => {
+            let mut parts = value.split_whitespace();
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(amount), Some(unit), None) => (amount, unit.to_uppercase()),
+                _ => return Err(invalid()),
+            }
+        }
+    };
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let months = |per_unit: i64| {
+        amount
+            .checked_mul(per_unit)
+            .and_then(|months| i32::try_from(months).ok())
+            .map(Interval::Months)
+            .ok_or_else(invalid)
+    };
+
+    // The constructors of `Duration` panic when out of range, so the amount is checked first
+    let duration = |millis_per_unit: i64| {
+        amount
+            .checked_mul(millis_per_unit)
+            .map(|millis| Interval::Duration(Duration::milliseconds(millis)))
+            .ok_or_else(invalid)
+    };
+
+    let interval = match unit.strip_suffix('S').unwrap_or(&unit) {
+        "MILLISECOND" => duration(1)?,
+        "SECOND" => duration(1000)?,
+        "MINUTE" => duration(60 * 1000)?,
+        "HOUR" => duration(60 * 60 * 1000)?,
+        "DAY" => duration(24 * 60 * 60 * 1000)?,
+        "WEEK" => duration(7 * 24 * 60 * 60 * 1000)?,
+        "MONTH" => months(1)?,
+        "YEAR" => months(12)?,
+        _ => return Err(invalid()),
+    };
+    Ok(interval)
+}
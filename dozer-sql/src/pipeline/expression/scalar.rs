@@ -1,4 +1,7 @@
 pub mod common;
+pub mod datetime;
+pub mod json;
+pub mod null;
 pub mod number;
 pub mod string;
 
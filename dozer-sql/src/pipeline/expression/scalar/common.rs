@@ -1,9 +1,22 @@
 use crate::argv;
 use crate::pipeline::errors::PipelineError;
-use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
-use crate::pipeline::expression::scalar::number::{evaluate_abs, evaluate_round};
+use crate::pipeline::expression::execution::{Expression, ExpressionType};
+use crate::pipeline::expression::scalar::datetime::{
+    evaluate_date_trunc, evaluate_extract, evaluate_now, evaluate_to_char, evaluate_to_timestamp,
+    validate_date_trunc, validate_extract, validate_to_char, validate_to_timestamp,
+};
+use crate::pipeline::expression::scalar::json::{
+    evaluate_json_extract, evaluate_json_value, validate_json_extract, validate_json_value,
+};
+use crate::pipeline::expression::scalar::null::{
+    evaluate_coalesce, evaluate_nullif, validate_coalesce, validate_nullif,
+};
+use crate::pipeline::expression::scalar::number::{
+    evaluate_abs, evaluate_number_function, evaluate_round, validate_number_function,
+};
 use crate::pipeline::expression::scalar::string::{
-    evaluate_concat, evaluate_length, evaluate_ucase, validate_concat, validate_ucase,
+    evaluate_concat, evaluate_length, evaluate_string_function, evaluate_ucase, validate_concat,
+    validate_string_function_type, validate_ucase,
 };
 
 use dozer_types::types::{Field, FieldType, Record, Schema};
@@ -17,6 +30,35 @@ pub enum ScalarFunctionType {
     Ucase,
     Concat,
     Length,
+    Coalesce,
+    Nullif,
+    Ifnull,
+    Extract,
+    DateTrunc,
+    Now,
+    ToTimestamp,
+    ToChar,
+    JsonExtract,
+    JsonValue,
+    Lower,
+    Substring,
+    Replace,
+    Position,
+    SplitPart,
+    Lpad,
+    Rpad,
+    Reverse,
+    RegexpLike,
+    RegexpReplace,
+    Ceil,
+    Floor,
+    Power,
+    Sqrt,
+    Log,
+    Ln,
+    Exp,
+    Mod,
+    Sign,
 }
 
 impl Display for ScalarFunctionType {
@@ -27,6 +69,35 @@ impl Display for ScalarFunctionType {
             ScalarFunctionType::Ucase => f.write_str("UCASE"),
             ScalarFunctionType::Concat => f.write_str("CONCAT"),
             ScalarFunctionType::Length => f.write_str("LENGTH"),
+            ScalarFunctionType::Coalesce => f.write_str("COALESCE"),
+            ScalarFunctionType::Nullif => f.write_str("NULLIF"),
+            ScalarFunctionType::Ifnull => f.write_str("IFNULL"),
+            ScalarFunctionType::Extract => f.write_str("EXTRACT"),
+            ScalarFunctionType::DateTrunc => f.write_str("DATE_TRUNC"),
+            ScalarFunctionType::Now => f.write_str("NOW"),
+            ScalarFunctionType::ToTimestamp => f.write_str("TO_TIMESTAMP"),
+            ScalarFunctionType::ToChar => f.write_str("TO_CHAR"),
+            ScalarFunctionType::JsonExtract => f.write_str("JSON_EXTRACT"),
+            ScalarFunctionType::JsonValue => f.write_str("JSON_VALUE"),
+            ScalarFunctionType::Lower => f.write_str("LOWER"),
+            ScalarFunctionType::Substring => f.write_str("SUBSTRING"),
+            ScalarFunctionType::Replace => f.write_str("REPLACE"),
+            ScalarFunctionType::Position => f.write_str("POSITION"),
+            ScalarFunctionType::SplitPart => f.write_str("SPLIT_PART"),
+            ScalarFunctionType::Lpad => f.write_str("LPAD"),
+            ScalarFunctionType::Rpad => f.write_str("RPAD"),
+            ScalarFunctionType::Reverse => f.write_str("REVERSE"),
+            ScalarFunctionType::RegexpLike => f.write_str("REGEXP_LIKE"),
+            ScalarFunctionType::RegexpReplace => f.write_str("REGEXP_REPLACE"),
+            ScalarFunctionType::Ceil => f.write_str("CEIL"),
+            ScalarFunctionType::Floor => f.write_str("FLOOR"),
+            ScalarFunctionType::Power => f.write_str("POWER"),
+            ScalarFunctionType::Sqrt => f.write_str("SQRT"),
+            ScalarFunctionType::Log => f.write_str("LOG"),
+            ScalarFunctionType::Ln => f.write_str("LN"),
+            ScalarFunctionType::Exp => f.write_str("EXP"),
+            ScalarFunctionType::Mod => f.write_str("MOD"),
+            ScalarFunctionType::Sign => f.write_str("SIGN"),
         }
     }
 }
@@ -37,12 +108,17 @@ pub(crate) fn get_scalar_function_type(
     schema: &Schema,
 ) -> Result<ExpressionType, PipelineError> {
     match function {
-        ScalarFunctionType::Abs => argv!(args, 0, ScalarFunctionType::Abs)?.get_type(schema),
-        ScalarFunctionType::Round => Ok(ExpressionType::new(
-            FieldType::Int,
-            true,
-            dozer_types::types::SourceDefinition::Dynamic,
-        )),
+        ScalarFunctionType::Abs
+        | ScalarFunctionType::Round
+        | ScalarFunctionType::Ceil
+        | ScalarFunctionType::Floor
+        | ScalarFunctionType::Power
+        | ScalarFunctionType::Sqrt
+        | ScalarFunctionType::Log
+        | ScalarFunctionType::Ln
+        | ScalarFunctionType::Exp
+        | ScalarFunctionType::Mod
+        | ScalarFunctionType::Sign => validate_number_function(function, args, schema),
         ScalarFunctionType::Ucase => {
             validate_ucase(argv!(args, 0, ScalarFunctionType::Ucase)?, schema)
         }
@@ -52,6 +128,73 @@ pub(crate) fn get_scalar_function_type(
             false,
             dozer_types::types::SourceDefinition::Dynamic,
         )),
+        ScalarFunctionType::Coalesce => {
+            validate_coalesce(ScalarFunctionType::Coalesce, args, schema)
+        }
+        ScalarFunctionType::Nullif => {
+            if args.len() > 2 {
+                return Err(PipelineError::TooManyArguments(function.to_string()));
+            }
+            validate_nullif(
+                argv!(args, 0, ScalarFunctionType::Nullif)?,
+                argv!(args, 1, ScalarFunctionType::Nullif)?,
+                schema,
+            )
+        }
+        ScalarFunctionType::Ifnull => {
+            argv!(args, 1, ScalarFunctionType::Ifnull)?;
+            if args.len() > 2 {
+                return Err(PipelineError::TooManyArguments(function.to_string()));
+            }
+            validate_coalesce(ScalarFunctionType::Ifnull, args, schema)
+        }
+        ScalarFunctionType::Extract => validate_extract(
+            argv!(args, 0, ScalarFunctionType::Extract)?,
+            argv!(args, 1, ScalarFunctionType::Extract)?,
+            schema,
+        ),
+        ScalarFunctionType::DateTrunc => validate_date_trunc(
+            argv!(args, 0, ScalarFunctionType::DateTrunc)?,
+            argv!(args, 1, ScalarFunctionType::DateTrunc)?,
+            schema,
+        ),
+        ScalarFunctionType::Now => Ok(ExpressionType::new(
+            FieldType::Timestamp,
+            false,
+            dozer_types::types::SourceDefinition::Dynamic,
+        )),
+        ScalarFunctionType::ToTimestamp => validate_to_timestamp(
+            argv!(args, 0, ScalarFunctionType::ToTimestamp)?,
+            argv!(args, 1, ScalarFunctionType::ToTimestamp)?,
+            schema,
+        ),
+        ScalarFunctionType::ToChar => validate_to_char(
+            argv!(args, 0, ScalarFunctionType::ToChar)?,
+            argv!(args, 1, ScalarFunctionType::ToChar)?,
+            schema,
+        ),
+        ScalarFunctionType::JsonExtract => validate_json_extract(
+            argv!(args, 0, ScalarFunctionType::JsonExtract)?,
+            argv!(args, 1, ScalarFunctionType::JsonExtract)?,
+            schema,
+        ),
+        ScalarFunctionType::JsonValue => validate_json_value(
+            argv!(args, 0, ScalarFunctionType::JsonValue)?,
+            argv!(args, 1, ScalarFunctionType::JsonValue)?,
+            schema,
+        ),
+        ScalarFunctionType::Lower
+        | ScalarFunctionType::Substring
+        | ScalarFunctionType::Replace
+        | ScalarFunctionType::Position
+        | ScalarFunctionType::SplitPart
+        | ScalarFunctionType::Lpad
+        | ScalarFunctionType::Rpad
+        | ScalarFunctionType::Reverse
+        | ScalarFunctionType::RegexpLike
+        | ScalarFunctionType::RegexpReplace => {
+            validate_string_function_type(function, args, schema)
+        }
     }
 }
 
@@ -63,6 +206,34 @@ impl ScalarFunctionType {
             "ucase" => Ok(ScalarFunctionType::Ucase),
             "concat" => Ok(ScalarFunctionType::Concat),
             "length" => Ok(ScalarFunctionType::Length),
+            "coalesce" => Ok(ScalarFunctionType::Coalesce),
+            "nullif" => Ok(ScalarFunctionType::Nullif),
+            "ifnull" => Ok(ScalarFunctionType::Ifnull),
+            "extract" | "date_part" => Ok(ScalarFunctionType::Extract),
+            "date_trunc" => Ok(ScalarFunctionType::DateTrunc),
+            "now" => Ok(ScalarFunctionType::Now),
+            "to_timestamp" => Ok(ScalarFunctionType::ToTimestamp),
+            "to_char" => Ok(ScalarFunctionType::ToChar),
+            "json_extract" => Ok(ScalarFunctionType::JsonExtract),
+            "json_value" => Ok(ScalarFunctionType::JsonValue),
+            "lower" => Ok(ScalarFunctionType::Lower),
+            "substring" | "substr" => Ok(ScalarFunctionType::Substring),
+            "replace" => Ok(ScalarFunctionType::Replace),
+            "split_part" => Ok(ScalarFunctionType::SplitPart),
+            "lpad" => Ok(ScalarFunctionType::Lpad),
+            "rpad" => Ok(ScalarFunctionType::Rpad),
+            "reverse" => Ok(ScalarFunctionType::Reverse),
+            "regexp_like" => Ok(ScalarFunctionType::RegexpLike),
+            "regexp_replace" => Ok(ScalarFunctionType::RegexpReplace),
+            "ceil" | "ceiling" => Ok(ScalarFunctionType::Ceil),
+            "floor" => Ok(ScalarFunctionType::Floor),
+            "power" | "pow" => Ok(ScalarFunctionType::Power),
+            "sqrt" => Ok(ScalarFunctionType::Sqrt),
+            "log" => Ok(ScalarFunctionType::Log),
+            "ln" => Ok(ScalarFunctionType::Ln),
+            "exp" => Ok(ScalarFunctionType::Exp),
+            "mod" => Ok(ScalarFunctionType::Mod),
+            "sign" => Ok(ScalarFunctionType::Sign),
             _ => Err(PipelineError::InvalidFunction(name.to_string())),
         }
     }
@@ -90,6 +261,74 @@ impl ScalarFunctionType {
             ScalarFunctionType::Length => {
                 evaluate_length(schema, argv!(args, 0, ScalarFunctionType::Length)?, record)
             }
+            ScalarFunctionType::Coalesce | ScalarFunctionType::Ifnull => {
+                let return_type = get_scalar_function_type(self, args, schema)?.return_type;
+                evaluate_coalesce(self.clone(), schema, args, return_type, record)
+            }
+            ScalarFunctionType::Nullif => evaluate_nullif(
+                schema,
+                argv!(args, 0, ScalarFunctionType::Nullif)?,
+                argv!(args, 1, ScalarFunctionType::Nullif)?,
+                record,
+            ),
+            ScalarFunctionType::Extract => evaluate_extract(
+                schema,
+                argv!(args, 0, ScalarFunctionType::Extract)?,
+                argv!(args, 1, ScalarFunctionType::Extract)?,
+                record,
+            ),
+            ScalarFunctionType::DateTrunc => evaluate_date_trunc(
+                schema,
+                argv!(args, 0, ScalarFunctionType::DateTrunc)?,
+                argv!(args, 1, ScalarFunctionType::DateTrunc)?,
+                record,
+            ),
+            ScalarFunctionType::Now => Ok(evaluate_now()),
+            ScalarFunctionType::ToTimestamp => evaluate_to_timestamp(
+                schema,
+                argv!(args, 0, ScalarFunctionType::ToTimestamp)?,
+                argv!(args, 1, ScalarFunctionType::ToTimestamp)?,
+                record,
+            ),
+            ScalarFunctionType::ToChar => evaluate_to_char(
+                schema,
+                argv!(args, 0, ScalarFunctionType::ToChar)?,
+                argv!(args, 1, ScalarFunctionType::ToChar)?,
+                record,
+            ),
+            ScalarFunctionType::JsonExtract => evaluate_json_extract(
+                schema,
+                argv!(args, 0, ScalarFunctionType::JsonExtract)?,
+                argv!(args, 1, ScalarFunctionType::JsonExtract)?,
+                record,
+            ),
+            ScalarFunctionType::JsonValue => evaluate_json_value(
+                schema,
+                argv!(args, 0, ScalarFunctionType::JsonValue)?,
+                argv!(args, 1, ScalarFunctionType::JsonValue)?,
+                record,
+            ),
+            ScalarFunctionType::Lower
+            | ScalarFunctionType::Substring
+            | ScalarFunctionType::Replace
+            | ScalarFunctionType::Position
+            | ScalarFunctionType::SplitPart
+            | ScalarFunctionType::Lpad
+            | ScalarFunctionType::Rpad
+            | ScalarFunctionType::Reverse
+            | ScalarFunctionType::RegexpLike
+            | ScalarFunctionType::RegexpReplace => {
+                evaluate_string_function(self, schema, args, None, record)
+            }
+            ScalarFunctionType::Ceil
+            | ScalarFunctionType::Floor
+            | ScalarFunctionType::Power
+            | ScalarFunctionType::Sqrt
+            | ScalarFunctionType::Log
+            | ScalarFunctionType::Ln
+            | ScalarFunctionType::Exp
+            | ScalarFunctionType::Mod
+            | ScalarFunctionType::Sign => evaluate_number_function(self, schema, args, record),
         }
     }
 }
@@ -0,0 +1,466 @@
+use std::fmt::Write;
+
+use crate::arg_str;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::arg_utils::validate_arg_type;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
+use crate::pipeline::expression::interval::Interval;
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
+use dozer_types::chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc,
+};
+use dozer_types::types::{Field, FieldType, Record, Schema, SourceDefinition};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateTimePart {
+    Year,
+    Quarter,
+    Month,
+    Week,
+    Day,
+    Dow,
+    Doy,
+    Hour,
+    Minute,
+    Second,
+    Millisecond,
+    Microsecond,
+    Epoch,
+}
+
+impl DateTimePart {
+    pub fn new(name: &str) -> Result<DateTimePart, PipelineError> {
+        let name = name.to_lowercase();
+        match name.strip_suffix('s').unwrap_or(&name) {
+            "year" => Ok(DateTimePart::Year),
+            "quarter" => Ok(DateTimePart::Quarter),
+            "month" => Ok(DateTimePart::Month),
+            "week" => Ok(DateTimePart::Week),
+            "day" => Ok(DateTimePart::Day),
+            "dow" => Ok(DateTimePart::Dow),
+            "doy" => Ok(DateTimePart::Doy),
+            "hour" => Ok(DateTimePart::Hour),
+            "minute" => Ok(DateTimePart::Minute),
+            "second" => Ok(DateTimePart::Second),
+            "millisecond" => Ok(DateTimePart::Millisecond),
+            "microsecond" => Ok(DateTimePart::Microsecond),
+            "epoch" => Ok(DateTimePart::Epoch),
+            _ => Err(PipelineError::InvalidValue(format!(
+                "Unsupported date/time part: {name}"
+            ))),
+        }
+    }
+}
+
+/// Returns the value of a `Timestamp` or `Date` field as a timestamp, dates being at midnight UTC
+fn to_timestamp(field: &Field) -> Option<DateTime<FixedOffset>> {
+    match field {
+        Field::Timestamp(ts) => Some(*ts),
+        Field::Date(date) => Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?).into()),
+        _ => None,
+    }
+}
+
+fn validate_part(
+    part: &Expression,
+    schema: &Schema,
+    function: ScalarFunctionType,
+) -> Result<(), PipelineError> {
+    validate_arg_type(
+        part,
+        vec![FieldType::String, FieldType::Text],
+        schema,
+        function,
+        0,
+    )?;
+    // Catch typos at build time when the part is a literal
+    if let Expression::Literal(Field::String(name) | Field::Text(name)) = part {
+        DateTimePart::new(name)?;
+    }
+    Ok(())
+}
+
+fn evaluate_part(
+    part: &Expression,
+    schema: &Schema,
+    record: &Record,
+    function: ScalarFunctionType,
+) -> Result<DateTimePart, PipelineError> {
+    let f = part.evaluate(record, schema)?;
+    DateTimePart::new(&arg_str!(f, function, 0)?)
+}
+
+pub(crate) fn validate_extract(
+    part: &Expression,
+    arg: &Expression,
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    validate_part(part, schema, ScalarFunctionType::Extract)?;
+    let arg_type = validate_arg_type(
+        arg,
+        vec![FieldType::Timestamp, FieldType::Date],
+        schema,
+        ScalarFunctionType::Extract,
+        1,
+    )?;
+    Ok(ExpressionType::new(
+        FieldType::Int,
+        arg_type.nullable,
+        SourceDefinition::Dynamic,
+    ))
+}
+
+pub(crate) fn evaluate_extract(
+    schema: &Schema,
+    part: &Expression,
+    arg: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    let ts = match to_timestamp(&f) {
+        Some(ts) => ts,
+        None if f == Field::Null => return Ok(Field::Null),
+        None => {
+            return Err(PipelineError::InvalidFunctionArgument(
+                ScalarFunctionType::Extract.to_string(),
+                f,
+                1,
+            ))
+        }
+    };
+
+    let value = match evaluate_part(part, schema, record, ScalarFunctionType::Extract)? {
+        DateTimePart::Year => ts.year() as i64,
+        DateTimePart::Quarter => (ts.month0() / 3 + 1) as i64,
+        DateTimePart::Month => ts.month() as i64,
+        DateTimePart::Week => ts.iso_week().week() as i64,
+        DateTimePart::Day => ts.day() as i64,
+        DateTimePart::Dow => ts.weekday().num_days_from_sunday() as i64,
+        DateTimePart::Doy => ts.ordinal() as i64,
+        DateTimePart::Hour => ts.hour() as i64,
+        DateTimePart::Minute => ts.minute() as i64,
+        DateTimePart::Second => ts.second() as i64,
+        DateTimePart::Millisecond => (ts.second() * 1_000 + ts.timestamp_subsec_millis()) as i64,
+        DateTimePart::Microsecond => {
+            (ts.second() * 1_000_000 + ts.timestamp_subsec_micros()) as i64
+        }
+        DateTimePart::Epoch => ts.timestamp(),
+    };
+    Ok(Field::Int(value))
+}
+
+pub(crate) fn validate_date_trunc(
+    part: &Expression,
+    arg: &Expression,
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    validate_part(part, schema, ScalarFunctionType::DateTrunc)?;
+    validate_arg_type(
+        arg,
+        vec![FieldType::Timestamp, FieldType::Date],
+        schema,
+        ScalarFunctionType::DateTrunc,
+        1,
+    )
+}
+
+/// Truncates `ts` to the start of the given `part`, in the timestamp's own time zone
+fn truncate(ts: DateTime<FixedOffset>, part: DateTimePart) -> Option<DateTime<FixedOffset>> {
+    let local = ts.naive_local();
+    let date = local.date();
+    let truncated = match part {
+        DateTimePart::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1)?.and_hms_opt(0, 0, 0)?,
+        DateTimePart::Quarter => {
+            NaiveDate::from_ymd_opt(date.year(), date.month0() / 3 * 3 + 1, 1)?
+                .and_hms_opt(0, 0, 0)?
+        }
+        DateTimePart::Month => {
+            NaiveDate::from_ymd_opt(date.year(), date.month(), 1)?.and_hms_opt(0, 0, 0)?
+        }
+        // Weeks start on Monday, as in ISO 8601
+        DateTimePart::Week => (date - Duration::days(date.weekday().num_days_from_monday() as i64))
+            .and_hms_opt(0, 0, 0)?,
+        DateTimePart::Day => date.and_hms_opt(0, 0, 0)?,
+        DateTimePart::Hour => date.and_hms_opt(local.hour(), 0, 0)?,
+        DateTimePart::Minute => date.and_hms_opt(local.hour(), local.minute(), 0)?,
+        DateTimePart::Second => date.and_hms_opt(local.hour(), local.minute(), local.second())?,
+        DateTimePart::Millisecond => date.and_hms_milli_opt(
+            local.hour(),
+            local.minute(),
+            local.second(),
+            local.nanosecond() / 1_000_000,
+        )?,
+        DateTimePart::Microsecond => local,
+        DateTimePart::Dow | DateTimePart::Doy | DateTimePart::Epoch => return None,
+    };
+    ts.offset().from_local_datetime(&truncated).single()
+}
+
+pub(crate) fn evaluate_date_trunc(
+    schema: &Schema,
+    part: &Expression,
+    arg: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    let ts = match to_timestamp(&f) {
+        Some(ts) => ts,
+        None if f == Field::Null => return Ok(Field::Null),
+        None => {
+            return Err(PipelineError::InvalidFunctionArgument(
+                ScalarFunctionType::DateTrunc.to_string(),
+                f,
+                1,
+            ))
+        }
+    };
+
+    let part = evaluate_part(part, schema, record, ScalarFunctionType::DateTrunc)?;
+    let truncated = truncate(ts, part)
+        .ok_or_else(|| PipelineError::InvalidValue(format!("Cannot truncate {f} to {part:?}")))?;
+    Ok(match f {
+        Field::Date(_) => Field::Date(truncated.date_naive()),
+        _ => Field::Timestamp(truncated),
+    })
+}
+
+/// Current timestamp, evaluated when the expression is built
+pub(crate) fn evaluate_now() -> Field {
+    Field::Timestamp(Utc::now().into())
+}
+
+pub(crate) fn validate_to_timestamp(
+    arg: &Expression,
+    format: &Expression,
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    let arg_type = validate_arg_type(
+        arg,
+        vec![FieldType::String, FieldType::Text],
+        schema,
+        ScalarFunctionType::ToTimestamp,
+        0,
+    )?;
+    validate_arg_type(
+        format,
+        vec![FieldType::String, FieldType::Text],
+        schema,
+        ScalarFunctionType::ToTimestamp,
+        1,
+    )?;
+    Ok(ExpressionType::new(
+        FieldType::Timestamp,
+        arg_type.nullable,
+        SourceDefinition::Dynamic,
+    ))
+}
+
+/// Parses `value` with a format, as translated by [`to_chrono_format`]. Values without a time
+/// zone are in UTC, and values without a time are at midnight.
+pub(crate) fn evaluate_to_timestamp(
+    schema: &Schema,
+    arg: &Expression,
+    format: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    if f == Field::Null {
+        return Ok(f);
+    }
+    let value = arg_str!(f, ScalarFunctionType::ToTimestamp, 0)?;
+    let format = format.evaluate(record, schema)?;
+    let format = to_chrono_format(&arg_str!(format, ScalarFunctionType::ToTimestamp, 1)?);
+
+    let ts = DateTime::parse_from_str(&value, &format)
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(&value, &format)
+                .ok()
+                .or_else(|| {
+                    NaiveDate::parse_from_str(&value, &format)
+                        .ok()
+                        .and_then(|date| date.and_hms_opt(0, 0, 0))
+                })
+                .map(|ts| Utc.from_utc_datetime(&ts).into())
+        })
+        .ok_or_else(|| {
+            PipelineError::InvalidFunctionArgument(
+                ScalarFunctionType::ToTimestamp.to_string(),
+                Field::String(value.clone()),
+                0,
+            )
+        })?;
+    Ok(Field::Timestamp(ts))
+}
+
+pub(crate) fn validate_to_char(
+    arg: &Expression,
+    format: &Expression,
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    let arg_type = validate_arg_type(
+        arg,
+        vec![FieldType::Timestamp, FieldType::Date],
+        schema,
+        ScalarFunctionType::ToChar,
+        0,
+    )?;
+    validate_arg_type(
+        format,
+        vec![FieldType::String, FieldType::Text],
+        schema,
+        ScalarFunctionType::ToChar,
+        1,
+    )?;
+    Ok(ExpressionType::new(
+        FieldType::String,
+        arg_type.nullable,
+        SourceDefinition::Dynamic,
+    ))
+}
+
+/// Formats a timestamp or date with a format, as translated by [`to_chrono_format`]
+pub(crate) fn evaluate_to_char(
+    schema: &Schema,
+    arg: &Expression,
+    format: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    let format = format.evaluate(record, schema)?;
+    let format_str = arg_str!(format, ScalarFunctionType::ToChar, 1)?;
+    let chrono_format = to_chrono_format(&format_str);
+
+    let mut result = String::new();
+    let written = match &f {
+        Field::Null => return Ok(Field::Null),
+        Field::Timestamp(ts) => write!(result, "{}", ts.format(&chrono_format)),
+        Field::Date(date) => write!(result, "{}", date.format(&chrono_format)),
+        _ => {
+            return Err(PipelineError::InvalidFunctionArgument(
+                ScalarFunctionType::ToChar.to_string(),
+                f,
+                0,
+            ))
+        }
+    };
+    // Formatting fails on invalid specifiers, or on time specifiers for dates
+    written.map_err(|_| {
+        PipelineError::InvalidFunctionArgument(
+            ScalarFunctionType::ToChar.to_string(),
+            Field::String(format_str),
+            1,
+        )
+    })?;
+    Ok(Field::String(result))
+}
+
+/// Template patterns of PostgreSQL, with the `chrono` specifiers they translate to. Earlier
+/// patterns take precedence, and the numeric ones are case-insensitive.
+const TEMPLATE_PATTERNS: [(&str, &str, bool); 22] = [
+    ("HH24", "%H", false),
+    ("HH12", "%I", false),
+    ("YYYY", "%Y", false),
+    ("Month", "%B", true),
+    ("DDD", "%j", false),
+    ("Mon", "%b", true),
+    ("Day", "%A", true),
+    ("Dy", "%a", true),
+    ("HH", "%I", false),
+    ("YY", "%y", false),
+    ("MM", "%m", false),
+    ("DD", "%d", false),
+    ("MI", "%M", false),
+    ("SS", "%S", false),
+    ("MS", "%3f", false),
+    ("US", "%6f", false),
+    ("AM", "%p", true),
+    ("PM", "%p", true),
+    ("am", "%P", true),
+    ("pm", "%P", true),
+    ("TZ", "%Z", false),
+    ("OF", "%:z", false),
+];
+
+/// Translates a format of `TO_CHAR` and `TO_TIMESTAMP` to a `chrono` format string. Formats
+/// containing `%` are `chrono` format strings already, the others are PostgreSQL templates
+/// such as `'YYYY-MM-DD HH24:MI:SS'`, where text in double quotes and the characters that are
+/// not part of a pattern are copied as is.
+fn to_chrono_format(format: &str) -> String {
+    if format.contains('%') {
+        return format.to_string();
+    }
+
+    let mut result = String::new();
+    let mut rest = format;
+    while let Some(c) = rest.chars().next() {
+        if c == '"' {
+            let end = rest[1..].find('"').map_or(rest.len(), |end| end + 1);
+            result.push_str(&rest[1..end]);
+            rest = rest.get(end + 1..).unwrap_or_default();
+            continue;
+        }
+
+        let pattern = TEMPLATE_PATTERNS
+            .iter()
+            .find(
+                |(pattern, _, case_sensitive)| match rest.get(..pattern.len()) {
+                    Some(prefix) if *case_sensitive => prefix == *pattern,
+                    Some(prefix) => prefix.eq_ignore_ascii_case(pattern),
+                    None => false,
+                },
+            );
+        match pattern {
+            Some((pattern, specifier, _)) => {
+                result.push_str(specifier);
+                rest = &rest[pattern.len()..];
+            }
+            None => {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    result
+}
+
+pub(crate) fn get_interval_operator_type(
+    arg: &Expression,
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    let arg_type = arg.get_type(schema)?;
+    match arg_type.return_type {
+        FieldType::Timestamp | FieldType::Date => Ok(ExpressionType::new(
+            FieldType::Timestamp,
+            arg_type.nullable,
+            SourceDefinition::Dynamic,
+        )),
+        other => Err(PipelineError::InvalidExpression(format!(
+            "Intervals can only be added to timestamps and dates, not {other:?}"
+        ))),
+    }
+}
+
+/// Adds `interval` to a timestamp or date, dates resulting in timestamps
+pub(crate) fn evaluate_interval_operator(
+    schema: &Schema,
+    arg: &Expression,
+    interval: &Interval,
+    subtract: bool,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    let ts = match to_timestamp(&f) {
+        Some(ts) => ts,
+        None if f == Field::Null => return Ok(Field::Null),
+        None => {
+            return Err(PipelineError::InvalidExpression(format!(
+                "Intervals can only be added to timestamps and dates, not {f}"
+            )))
+        }
+    };
+    interval
+        .add_to(ts, subtract)
+        .map(Field::Timestamp)
+        .ok_or_else(|| PipelineError::InvalidValue(format!("Timestamp out of range: {f}")))
+}
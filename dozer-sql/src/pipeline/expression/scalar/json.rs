@@ -0,0 +1,206 @@
+use crate::arg_str;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::arg_utils::validate_arg_type;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::serde_json::{self, Value};
+use dozer_types::types::{Field, FieldType, Record, Schema, SourceDefinition};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonPathElement {
+    Key(String),
+    Index(usize),
+}
+
+/// Parses a JSON path such as `$.a.b`, `$.a[0]` or `$["a.b"]`
+pub fn parse_json_path(path: &str) -> Result<Vec<JsonPathElement>, PipelineError> {
+    let invalid = || PipelineError::InvalidValue(format!("Invalid JSON path: {path}"));
+    let mut chars = path.trim().chars().peekable();
+    if chars.next() != Some('$') {
+        return Err(invalid());
+    }
+
+    let mut elements = vec![];
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                let mut key = String::new();
+                while let Some(c) = chars.next_if(|c| *c != '.' && *c != '[') {
+                    key.push(c);
+                }
+                if key.is_empty() {
+                    return Err(invalid());
+                }
+                elements.push(JsonPathElement::Key(key));
+            }
+            '[' => match chars.next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let mut key = String::new();
+                    loop {
+                        match chars.next().ok_or_else(invalid)? {
+                            '\\' => key.push(chars.next().ok_or_else(invalid)?),
+                            c if c == quote => break,
+                            c => key.push(c),
+                        }
+                    }
+                    if chars.next() != Some(']') {
+                        return Err(invalid());
+                    }
+                    elements.push(JsonPathElement::Key(key));
+                }
+                Some(c) => {
+                    let mut index = String::from(c);
+                    loop {
+                        match chars.next().ok_or_else(invalid)? {
+                            ']' => break,
+                            c => index.push(c),
+                        }
+                    }
+                    let index = index.trim().parse().map_err(|_| invalid())?;
+                    elements.push(JsonPathElement::Index(index));
+                }
+                None => return Err(invalid()),
+            },
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(elements)
+}
+
+/// Returns the path of the `key` member of an object, as extracted by the `->` operator
+pub fn get_json_key_path(key: &str) -> String {
+    let escaped = key.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("$[\"{escaped}\"]")
+}
+
+/// Parses the JSON document stored in a `Bson` field
+pub(crate) fn parse_json(bytes: &[u8]) -> Option<Value> {
+    serde_json::from_slice(bytes).ok()
+}
+
+fn lookup<'a>(value: &'a Value, path: &[JsonPathElement]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, element| match element {
+        JsonPathElement::Key(key) => value.as_object()?.get(key),
+        JsonPathElement::Index(index) => value.as_array()?.get(*index),
+    })
+}
+
+/// Converts a JSON value to the matching field type, objects and arrays being returned as text
+pub(crate) fn json_value_to_field(value: &Value) -> Field {
+    match value {
+        Value::Null => Field::Null,
+        Value::Bool(v) => Field::Boolean(*v),
+        Value::Number(v) => match (v.as_i64(), v.as_u64(), v.as_f64()) {
+            (Some(v), _, _) => Field::Int(v),
+            (_, Some(v), _) => Field::UInt(v),
+            (_, _, v) => Field::Float(OrderedFloat(v.unwrap_or_default())),
+        },
+        Value::String(v) => Field::String(v.clone()),
+        Value::Array(_) | Value::Object(_) => Field::String(value.to_string()),
+    }
+}
+
+fn validate_json_function(
+    function: ScalarFunctionType,
+    arg: &Expression,
+    path: &Expression,
+    return_type: FieldType,
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    validate_arg_type(arg, vec![FieldType::Bson], schema, function.clone(), 0)?;
+    validate_arg_type(
+        path,
+        vec![FieldType::String, FieldType::Text],
+        schema,
+        function,
+        1,
+    )?;
+    if let Expression::Literal(Field::String(path) | Field::Text(path)) = path {
+        parse_json_path(path)?;
+    }
+    // Missing members are null
+    Ok(ExpressionType::new(
+        return_type,
+        true,
+        SourceDefinition::Dynamic,
+    ))
+}
+
+pub(crate) fn validate_json_extract(
+    arg: &Expression,
+    path: &Expression,
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    validate_json_function(
+        ScalarFunctionType::JsonExtract,
+        arg,
+        path,
+        FieldType::Bson,
+        schema,
+    )
+}
+
+pub(crate) fn validate_json_value(
+    arg: &Expression,
+    path: &Expression,
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    validate_json_function(
+        ScalarFunctionType::JsonValue,
+        arg,
+        path,
+        FieldType::String,
+        schema,
+    )
+}
+
+fn evaluate_json_path(
+    function: ScalarFunctionType,
+    schema: &Schema,
+    arg: &Expression,
+    path: &Expression,
+    record: &Record,
+) -> Result<Option<Value>, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    let document = match &f {
+        Field::Null => return Ok(None),
+        Field::Bson(bytes) => parse_json(bytes),
+        _ => None,
+    }
+    .ok_or_else(|| PipelineError::InvalidFunctionArgument(function.to_string(), f.clone(), 0))?;
+
+    let path = path.evaluate(record, schema)?;
+    let path = parse_json_path(&arg_str!(path, function, 1)?)?;
+    Ok(lookup(&document, &path).cloned())
+}
+
+/// Returns the JSON value at `path`, or null if there is none
+pub(crate) fn evaluate_json_extract(
+    schema: &Schema,
+    arg: &Expression,
+    path: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let value = evaluate_json_path(ScalarFunctionType::JsonExtract, schema, arg, path, record)?;
+    Ok(match value {
+        Some(Value::Null) | None => Field::Null,
+        Some(value) => Field::Bson(value.to_string().into_bytes()),
+    })
+}
+
+/// Returns the value at `path` as text. Strings are unquoted, and objects and arrays are
+/// returned as JSON, as for the `->>` operator.
+pub(crate) fn evaluate_json_value(
+    schema: &Schema,
+    arg: &Expression,
+    path: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let value = evaluate_json_path(ScalarFunctionType::JsonValue, schema, arg, path, record)?;
+    Ok(match value {
+        Some(Value::Null) | None => Field::Null,
+        Some(Value::String(value)) => Field::String(value),
+        Some(value) => Field::String(value.to_string()),
+    })
+}
@@ -0,0 +1,118 @@
+use std::cmp::Ordering;
+
+use crate::pipeline::errors::{FieldTypes, PipelineError};
+use crate::pipeline::expression::conditional::{
+    compare_fields, convert_field, get_common_type, get_operand_type,
+};
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
+use dozer_types::types::{Field, FieldType, Record, Schema, SourceDefinition};
+
+pub(crate) fn validate_coalesce(
+    function: ScalarFunctionType,
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    if args.is_empty() {
+        return Err(PipelineError::NotEnoughArguments(function.to_string()));
+    }
+
+    // The result is only null when all the arguments are
+    let mut nullable = true;
+    let mut return_type: Option<FieldType> = None;
+    for (index, arg) in args.iter().enumerate() {
+        let arg_type = match get_operand_type(arg, schema)? {
+            Some(arg_type) => arg_type,
+            None => continue,
+        };
+        nullable &= arg_type.nullable;
+        return_type = match return_type {
+            None => Some(arg_type.return_type),
+            Some(return_type) => Some(
+                get_common_type(return_type, arg_type.return_type).ok_or_else(|| {
+                    PipelineError::InvalidFunctionArgumentType(
+                        function.to_string(),
+                        arg_type.return_type,
+                        FieldTypes::new(vec![return_type]),
+                        index,
+                    )
+                })?,
+            ),
+        };
+    }
+
+    match return_type {
+        Some(return_type) => Ok(ExpressionType::new(
+            return_type,
+            nullable,
+            SourceDefinition::Dynamic,
+        )),
+        None => Err(PipelineError::InvalidExpression(format!(
+            "{function}() arguments cannot all be null"
+        ))),
+    }
+}
+
+/// Returns the first non-null argument, converted to the `return_type` given by
+/// [`validate_coalesce`]
+pub(crate) fn evaluate_coalesce(
+    function: ScalarFunctionType,
+    schema: &Schema,
+    args: &[Expression],
+    return_type: FieldType,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    for (index, arg) in args.iter().enumerate() {
+        let f = arg.evaluate(record, schema)?;
+        if f != Field::Null {
+            return convert_field(&f, return_type).ok_or_else(|| {
+                PipelineError::InvalidFunctionArgument(function.to_string(), f, index)
+            });
+        }
+    }
+    Ok(Field::Null)
+}
+
+pub(crate) fn validate_nullif(
+    arg: &Expression,
+    other: &Expression,
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    let arg_type = get_operand_type(arg, schema)?.ok_or_else(|| {
+        PipelineError::InvalidExpression(format!(
+            "{}() first argument cannot be null",
+            ScalarFunctionType::Nullif
+        ))
+    })?;
+    if let Some(other_type) = get_operand_type(other, schema)? {
+        if get_common_type(arg_type.return_type, other_type.return_type).is_none() {
+            return Err(PipelineError::InvalidFunctionArgumentType(
+                ScalarFunctionType::Nullif.to_string(),
+                other_type.return_type,
+                FieldTypes::new(vec![arg_type.return_type]),
+                1,
+            ));
+        }
+    }
+    // The result is null whenever the arguments are equal
+    Ok(ExpressionType::new(
+        arg_type.return_type,
+        true,
+        SourceDefinition::Dynamic,
+    ))
+}
+
+pub(crate) fn evaluate_nullif(
+    schema: &Schema,
+    arg: &Expression,
+    other: &Expression,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let f = arg.evaluate(record, schema)?;
+    let other = other.evaluate(record, schema)?;
+    let function = ScalarFunctionType::Nullif.to_string();
+    match compare_fields(&f, &other, &function)? {
+        Some(Ordering::Equal) => Ok(Field::Null),
+        _ => Ok(f),
+    }
+}
@@ -1,10 +1,19 @@
 use crate::pipeline::errors::PipelineError;
 use crate::pipeline::errors::PipelineError::InvalidFunctionArgument;
-use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use crate::pipeline::expression::arg_utils::validate_arg_type;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
 use crate::pipeline::expression::scalar::common::ScalarFunctionType;
 use dozer_types::ordered_float::OrderedFloat;
-use dozer_types::types::{Field, Record, Schema};
-use num_traits::Float;
+use dozer_types::rust_decimal::{Decimal, RoundingStrategy};
+use dozer_types::types::{Field, FieldType, Record, Schema, SourceDefinition};
+use num_traits::{Float, Signed, Zero};
+
+const NUMERIC_TYPES: [FieldType; 4] = [
+    FieldType::Int,
+    FieldType::UInt,
+    FieldType::Float,
+    FieldType::Decimal,
+];
 
 pub(crate) fn evaluate_abs(
     schema: &Schema,
@@ -13,8 +22,14 @@ pub(crate) fn evaluate_abs(
 ) -> Result<Field, PipelineError> {
     let value = arg.evaluate(record, schema)?;
     match value {
-        Field::Int(i) => Ok(Field::Int(i.abs())),
+        Field::Int(i) => i
+            .checked_abs()
+            .map(Field::Int)
+            .ok_or_else(|| InvalidFunctionArgument(ScalarFunctionType::Abs.to_string(), value, 0)),
+        Field::UInt(u) => Ok(Field::UInt(u)),
         Field::Float(f) => Ok(Field::Float(f.abs())),
+        Field::Decimal(d) => Ok(Field::Decimal(d.abs())),
+        Field::Null => Ok(Field::Null),
         _ => Err(PipelineError::InvalidFunctionArgument(
             ScalarFunctionType::Abs.to_string(),
             value,
@@ -23,6 +38,33 @@ pub(crate) fn evaluate_abs(
     }
 }
 
+/// Rounds `value` to a multiple of `10^-places`, with halves rounded away from zero
+fn round_int(value: i64, places: i32) -> i64 {
+    if places >= 0 {
+        return value;
+    }
+    let factor = match 10_i128.checked_pow(places.unsigned_abs()) {
+        Some(factor) => factor,
+        None => return 0,
+    };
+    let rounded = (value as i128).abs() + factor / 2;
+    let rounded = rounded / factor * factor * (value as i128).signum();
+    rounded.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+/// Returns `None` if the rounded value is out of the range of decimals
+fn round_decimal(value: Decimal, places: i32) -> Option<Decimal> {
+    if places >= 0 {
+        return Some(
+            value.round_dp_with_strategy(places as u32, RoundingStrategy::MidpointAwayFromZero),
+        );
+    }
+    match 10_i64.checked_pow(places.unsigned_abs()).map(Decimal::from) {
+        Some(factor) => (value / factor).round().checked_mul(factor),
+        None => Some(Decimal::ZERO),
+    }
+}
+
 pub(crate) fn evaluate_round(
     schema: &Schema,
     arg: &Expression,
@@ -33,7 +75,8 @@ pub(crate) fn evaluate_round(
     let mut places = 0;
     if let Some(expression) = decimals {
         match expression.evaluate(record, schema)? {
-            Field::Int(i) => places = i as i32,
+            Field::Int(i) => places = i.clamp(i32::MIN as i64, i32::MAX as i64) as i32,
+            Field::UInt(u) => places = u.min(i32::MAX as u64) as i32,
             Field::Float(f) => places = f.round().0 as i32,
             _ => {} // Truncate value to 0 decimals
         }
@@ -41,9 +84,15 @@ pub(crate) fn evaluate_round(
     let order = OrderedFloat(10.0_f64.powi(places));
 
     match value {
-        Field::Int(i) => Ok(Field::Int(i)),
+        Field::Int(i) => Ok(Field::Int(round_int(i, places))),
+        Field::UInt(u) => Ok(Field::UInt(
+            round_int(u.min(i64::MAX as u64) as i64, places) as u64,
+        )),
         Field::Float(f) => Ok(Field::Float((f * order).round() / order)),
-        Field::Decimal(_) => Err(PipelineError::InvalidOperandType("ROUND()".to_string())),
+        Field::Decimal(d) => round_decimal(d, places).map(Field::Decimal).ok_or_else(|| {
+            InvalidFunctionArgument(ScalarFunctionType::Round.to_string(), value, 0)
+        }),
+        Field::Null => Ok(Field::Null),
         _ => Err(InvalidFunctionArgument(
             ScalarFunctionType::Round.to_string(),
             value,
@@ -51,3 +100,207 @@ pub(crate) fn evaluate_round(
         )),
     }
 }
+
+/// Returns the type of `MOD`, which is the widest of the argument types
+fn get_mod_type(left: FieldType, right: FieldType) -> FieldType {
+    match (left, right) {
+        (FieldType::Decimal, _) | (_, FieldType::Decimal) => FieldType::Decimal,
+        (FieldType::Float, _) | (_, FieldType::Float) => FieldType::Float,
+        (FieldType::UInt, FieldType::UInt) => FieldType::UInt,
+        _ => FieldType::Int,
+    }
+}
+
+pub(crate) fn validate_number_function(
+    function: &ScalarFunctionType,
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    let (min_args, max_args) = match function {
+        ScalarFunctionType::Round | ScalarFunctionType::Log => (1, 2),
+        ScalarFunctionType::Power | ScalarFunctionType::Mod => (2, 2),
+        _ => (1, 1),
+    };
+    if args.len() < min_args {
+        return Err(PipelineError::NotEnoughArguments(function.to_string()));
+    }
+    if args.len() > max_args {
+        return Err(PipelineError::TooManyArguments(function.to_string()));
+    }
+
+    let mut arg_types = vec![];
+    for (idx, arg) in args.iter().enumerate() {
+        let expected = match (function, idx) {
+            (ScalarFunctionType::Round, 1) => vec![FieldType::Int, FieldType::UInt],
+            _ => NUMERIC_TYPES.to_vec(),
+        };
+        arg_types.push(validate_arg_type(
+            arg,
+            expected,
+            schema,
+            function.clone(),
+            idx,
+        )?);
+    }
+    let nullable = arg_types.iter().any(|arg_type| arg_type.nullable);
+
+    let return_type = match function {
+        ScalarFunctionType::Abs
+        | ScalarFunctionType::Round
+        | ScalarFunctionType::Ceil
+        | ScalarFunctionType::Floor
+        | ScalarFunctionType::Sign => arg_types[0].return_type,
+        ScalarFunctionType::Mod => get_mod_type(arg_types[0].return_type, arg_types[1].return_type),
+        // Irrational results are approximated
+        _ => FieldType::Float,
+    };
+    Ok(ExpressionType::new(
+        return_type,
+        nullable,
+        SourceDefinition::Dynamic,
+    ))
+}
+
+fn float_arg(
+    value: &Field,
+    function: &ScalarFunctionType,
+    idx: usize,
+) -> Result<f64, PipelineError> {
+    match value {
+        Field::Int(_) | Field::UInt(_) | Field::Float(_) | Field::Decimal(_) => value.to_float(),
+        _ => None,
+    }
+    .ok_or_else(|| InvalidFunctionArgument(function.to_string(), value.clone(), idx))
+}
+
+/// Returns `result`, or an error if it is not a number, e.g. the logarithm of a negative value
+fn float_result(
+    result: f64,
+    value: &Field,
+    function: &ScalarFunctionType,
+) -> Result<Field, PipelineError> {
+    if result.is_finite() {
+        Ok(Field::Float(OrderedFloat(result)))
+    } else {
+        Err(InvalidFunctionArgument(
+            function.to_string(),
+            value.clone(),
+            0,
+        ))
+    }
+}
+
+fn evaluate_mod(
+    function: &ScalarFunctionType,
+    left: Field,
+    right: Field,
+) -> Result<Field, PipelineError> {
+    let invalid =
+        |value: &Field, idx| InvalidFunctionArgument(function.to_string(), value.clone(), idx);
+    let typ = match (&left, &right) {
+        (Field::Decimal(_), _) | (_, Field::Decimal(_)) => FieldType::Decimal,
+        (Field::Float(_), _) | (_, Field::Float(_)) => FieldType::Float,
+        (Field::UInt(_), Field::UInt(_)) => FieldType::UInt,
+        _ => FieldType::Int,
+    };
+    match typ {
+        FieldType::Decimal => {
+            let l = left.to_decimal().ok_or_else(|| invalid(&left, 0))?;
+            let r = right.to_decimal().ok_or_else(|| invalid(&right, 1))?;
+            l.checked_rem(r)
+                .map(Field::Decimal)
+                .ok_or_else(|| invalid(&right, 1))
+        }
+        FieldType::Float => {
+            let l = float_arg(&left, function, 0)?;
+            let r = float_arg(&right, function, 1)?;
+            if r.is_zero() {
+                return Err(invalid(&right, 1));
+            }
+            Ok(Field::Float(OrderedFloat(l % r)))
+        }
+        FieldType::UInt => {
+            let l = left.to_uint().ok_or_else(|| invalid(&left, 0))?;
+            let r = right.to_uint().ok_or_else(|| invalid(&right, 1))?;
+            l.checked_rem(r)
+                .map(Field::UInt)
+                .ok_or_else(|| invalid(&right, 1))
+        }
+        _ => {
+            let l = left.to_int().ok_or_else(|| invalid(&left, 0))?;
+            let r = right.to_int().ok_or_else(|| invalid(&right, 1))?;
+            l.checked_rem(r)
+                .map(Field::Int)
+                .ok_or_else(|| invalid(&right, 1))
+        }
+    }
+}
+
+pub(crate) fn evaluate_number_function(
+    function: &ScalarFunctionType,
+    schema: &Schema,
+    args: &[Expression],
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let mut values = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg.evaluate(record, schema)? {
+            Field::Null => return Ok(Field::Null),
+            value => values.push(value),
+        }
+    }
+    let value = values
+        .first()
+        .cloned()
+        .ok_or_else(|| PipelineError::NotEnoughArguments(function.to_string()))?;
+
+    match (function, &value) {
+        (ScalarFunctionType::Ceil, Field::Float(f)) => Ok(Field::Float(f.ceil())),
+        (ScalarFunctionType::Ceil, Field::Decimal(d)) => Ok(Field::Decimal(d.ceil())),
+        (ScalarFunctionType::Floor, Field::Float(f)) => Ok(Field::Float(f.floor())),
+        (ScalarFunctionType::Floor, Field::Decimal(d)) => Ok(Field::Decimal(d.floor())),
+        (ScalarFunctionType::Ceil | ScalarFunctionType::Floor, Field::Int(_) | Field::UInt(_)) => {
+            Ok(value)
+        }
+        (ScalarFunctionType::Sign, Field::Int(i)) => Ok(Field::Int(i.signum())),
+        (ScalarFunctionType::Sign, Field::UInt(u)) => Ok(Field::UInt((*u).min(1))),
+        (ScalarFunctionType::Sign, Field::Float(f)) if f.is_zero() => Ok(Field::Float(*f)),
+        (ScalarFunctionType::Sign, Field::Float(f)) => Ok(Field::Float(f.signum())),
+        (ScalarFunctionType::Sign, Field::Decimal(d)) => Ok(Field::Decimal(d.signum())),
+        (ScalarFunctionType::Mod, _) => evaluate_mod(function, value, values[1].clone()),
+        (ScalarFunctionType::Power, _) => {
+            let base = float_arg(&value, function, 0)?;
+            let exponent = float_arg(&values[1], function, 1)?;
+            float_result(base.powf(exponent), &value, function)
+        }
+        (ScalarFunctionType::Sqrt, _) => {
+            float_result(float_arg(&value, function, 0)?.sqrt(), &value, function)
+        }
+        (ScalarFunctionType::Exp, _) => {
+            float_result(float_arg(&value, function, 0)?.exp(), &value, function)
+        }
+        (ScalarFunctionType::Ln | ScalarFunctionType::Log, _) => {
+            // LOG(x) is the base 10 logarithm, and LOG(b, x) the base b one
+            let (base, x) = match values.get(1) {
+                Some(x) => (Some(float_arg(&value, function, 0)?), x),
+                None => (None, &value),
+            };
+            let x = float_arg(x, function, values.len() - 1)?;
+            if x <= 0.0 {
+                return Err(InvalidFunctionArgument(
+                    function.to_string(),
+                    values[values.len() - 1].clone(),
+                    values.len() - 1,
+                ));
+            }
+            let result = match (function, base) {
+                (ScalarFunctionType::Ln, _) => x.ln(),
+                (_, Some(base)) if base > 0.0 && base != 1.0 => x.ln() / base.ln(),
+                (_, Some(_)) => f64::NAN,
+                (_, None) => x.log10(),
+            };
+            float_result(result, &value, function)
+        }
+        _ => Err(InvalidFunctionArgument(function.to_string(), value, 0)),
+    }
+}
@@ -7,8 +7,10 @@ use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, Exp
 use crate::pipeline::expression::arg_utils::validate_arg_type;
 use crate::pipeline::expression::scalar::common::ScalarFunctionType;
 
-use dozer_types::types::{Field, FieldType, Record, Schema};
+use dozer_types::types::{Field, FieldType, Record, Schema, SourceDefinition};
 use like::{Escape, Like};
+use regex::{Regex, RegexBuilder};
+use std::borrow::Cow;
 
 pub(crate) fn validate_ucase(
     arg: &Expression,
@@ -160,13 +162,18 @@ pub(crate) fn get_like_operator_type(
         0,
     )?;
 
-    validate_arg_type(
+    let arg_type = validate_arg_type(
         arg,
         vec![FieldType::String, FieldType::Text],
         schema,
         ScalarFunctionType::Concat,
         0,
-    )
+    )?;
+    Ok(ExpressionType::new(
+        FieldType::Boolean,
+        arg_type.nullable,
+        SourceDefinition::Dynamic,
+    ))
 }
 
 pub(crate) fn evaluate_like(
@@ -199,3 +206,338 @@ pub(crate) fn evaluate_like(
         .map_err(|e| PipelineError::InvalidArgument(e.to_string()))?;
     Ok(result)
 }
+
+const STRING_TYPES: [FieldType; 2] = [FieldType::String, FieldType::Text];
+const INTEGER_TYPES: [FieldType; 2] = [FieldType::Int, FieldType::UInt];
+
+/// Validates the arguments of a string function, of which the first `required` are mandatory,
+/// returning the type of the first argument. The result is nullable if any argument is.
+fn validate_string_function(
+    function: ScalarFunctionType,
+    args: &[Expression],
+    expected: &[&[FieldType]],
+    required: usize,
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    if args.len() < required {
+        return Err(PipelineError::NotEnoughArguments(function.to_string()));
+    }
+    if args.len() > expected.len() {
+        return Err(PipelineError::TooManyArguments(function.to_string()));
+    }
+
+    let mut nullable = false;
+    let mut return_type = FieldType::String;
+    for (idx, (arg, expected)) in args.iter().zip(expected).enumerate() {
+        let arg_type = validate_arg_type(arg, expected.to_vec(), schema, function.clone(), idx)?;
+        nullable |= arg_type.nullable;
+        if idx == 0 {
+            return_type = arg_type.return_type;
+        }
+    }
+    Ok(ExpressionType::new(
+        return_type,
+        nullable,
+        SourceDefinition::Dynamic,
+    ))
+}
+
+/// Evaluates the arguments of a string function, returning `None` if any of them is null
+fn evaluate_string_args(
+    args: &[Expression],
+    schema: &Schema,
+    record: &Record,
+) -> Result<Option<Vec<Field>>, PipelineError> {
+    let mut values = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg.evaluate(record, schema)? {
+            Field::Null => return Ok(None),
+            value => values.push(value),
+        }
+    }
+    Ok(Some(values))
+}
+
+fn string_arg(
+    values: &[Field],
+    idx: usize,
+    function: &ScalarFunctionType,
+) -> Result<String, PipelineError> {
+    let value = values[idx].clone();
+    arg_str!(value, function, idx)
+}
+
+fn int_arg(
+    values: &[Field],
+    idx: usize,
+    function: &ScalarFunctionType,
+) -> Result<i64, PipelineError> {
+    values[idx].to_int().ok_or_else(|| {
+        PipelineError::InvalidFunctionArgument(function.to_string(), values[idx].clone(), idx)
+    })
+}
+
+/// Returns a string of the same type as the first argument of the function
+fn string_result(value: String, values: &[Field]) -> Field {
+    match values.first() {
+        Some(Field::Text(_)) => Field::Text(value),
+        _ => Field::String(value),
+    }
+}
+
+pub(crate) fn validate_string_function_type(
+    function: &ScalarFunctionType,
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, PipelineError> {
+    let (expected, required): (&[&[FieldType]], usize) = match function {
+        ScalarFunctionType::Lower | ScalarFunctionType::Reverse => (&[&STRING_TYPES], 1),
+        ScalarFunctionType::Substring => (&[&STRING_TYPES, &INTEGER_TYPES, &INTEGER_TYPES], 2),
+        ScalarFunctionType::Replace => (&[&STRING_TYPES, &STRING_TYPES, &STRING_TYPES], 3),
+        ScalarFunctionType::Position => (&[&STRING_TYPES, &STRING_TYPES], 2),
+        ScalarFunctionType::SplitPart => (&[&STRING_TYPES, &STRING_TYPES, &INTEGER_TYPES], 3),
+        ScalarFunctionType::Lpad | ScalarFunctionType::Rpad => {
+            (&[&STRING_TYPES, &INTEGER_TYPES, &STRING_TYPES], 2)
+        }
+        ScalarFunctionType::RegexpLike => (&[&STRING_TYPES, &STRING_TYPES, &STRING_TYPES], 2),
+        ScalarFunctionType::RegexpReplace => (
+            &[&STRING_TYPES, &STRING_TYPES, &STRING_TYPES, &STRING_TYPES],
+            3,
+        ),
+        _ => return Err(PipelineError::InvalidFunction(function.to_string())),
+    };
+    let expression_type =
+        validate_string_function(function.clone(), args, expected, required, schema)?;
+
+    let return_type = match function {
+        ScalarFunctionType::Position => FieldType::UInt,
+        ScalarFunctionType::RegexpLike => FieldType::Boolean,
+        _ => expression_type.return_type,
+    };
+    // Invalid regular expressions are caught at build time when they are literals
+    if let (
+        ScalarFunctionType::RegexpLike | ScalarFunctionType::RegexpReplace,
+        Some(Expression::Literal(Field::String(pattern) | Field::Text(pattern))),
+    ) = (function, args.get(1))
+    {
+        build_regex(pattern, "", function)?;
+    }
+    Ok(ExpressionType::new(
+        return_type,
+        expression_type.nullable,
+        SourceDefinition::Dynamic,
+    ))
+}
+
+/// Builds a regular expression, using the PostgreSQL `i` flag for case insensitive matching
+/// Regular expression of `REGEXP_LIKE` or `REGEXP_REPLACE` whose pattern and flags are
+/// literals, compiled when the expression is built
+#[derive(Debug, Clone)]
+pub struct CompiledRegex(Regex);
+
+impl PartialEq for CompiledRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+/// Compiles the regular expression of a call to `function`, if its pattern and flags are
+/// literals
+pub(crate) fn compile_regex(
+    function: &ScalarFunctionType,
+    args: &[Expression],
+) -> Result<Option<CompiledRegex>, PipelineError> {
+    let flags_idx = match function {
+        ScalarFunctionType::RegexpLike => 2,
+        ScalarFunctionType::RegexpReplace => 3,
+        _ => return Ok(None),
+    };
+    let literal = |idx: usize| match args.get(idx) {
+        Some(Expression::Literal(Field::String(s) | Field::Text(s))) => Some(Some(s.as_str())),
+        Some(_) => None,
+        None => Some(None),
+    };
+    match (literal(1), literal(flags_idx)) {
+        (Some(Some(pattern)), Some(flags)) => Ok(Some(CompiledRegex(build_regex(
+            pattern,
+            flags.unwrap_or_default(),
+            function,
+        )?))),
+        _ => Ok(None),
+    }
+}
+
+fn build_regex(
+    pattern: &str,
+    flags: &str,
+    function: &ScalarFunctionType,
+) -> Result<Regex, PipelineError> {
+    if let Some(flag) = flags.chars().find(|flag| !matches!(flag, 'i' | 'g')) {
+        return Err(PipelineError::InvalidArgument(format!(
+            "Unsupported flag '{flag}' for function {function}()"
+        )));
+    }
+    RegexBuilder::new(pattern)
+        .case_insensitive(flags.contains('i'))
+        .build()
+        .map_err(|e| PipelineError::InvalidArgument(e.to_string()))
+}
+
+/// Longest string `LPAD` and `RPAD` return, in characters. PostgreSQL caps them at 1 GB, with
+/// up to 4 bytes per character.
+const MAX_PAD_LENGTH: i64 = (1 << 30) / 4;
+
+/// Pads `value` to `length` characters, truncating it if it is longer
+fn pad(value: &str, length: i64, fill: &str, left: bool) -> String {
+    let length = length.max(0) as usize;
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() >= length || fill.is_empty() {
+        return chars.into_iter().take(length).collect();
+    }
+    let padding: String = fill.chars().cycle().take(length - chars.len()).collect();
+    if left {
+        padding + value
+    } else {
+        value.to_string() + &padding
+    }
+}
+
+/// Evaluates a string function. The regular expression functions use `regex` if it is
+/// compiled already.
+pub(crate) fn evaluate_string_function(
+    function: &ScalarFunctionType,
+    schema: &Schema,
+    args: &[Expression],
+    regex: Option<&CompiledRegex>,
+    record: &Record,
+) -> Result<Field, PipelineError> {
+    let values = match evaluate_string_args(args, schema, record)? {
+        Some(values) => values,
+        None => return Ok(Field::Null),
+    };
+    let value = string_arg(&values, 0, function)?;
+
+    Ok(match function {
+        ScalarFunctionType::Lower => string_result(value.to_lowercase(), &values),
+        ScalarFunctionType::Reverse => string_result(value.chars().rev().collect(), &values),
+        ScalarFunctionType::Substring => {
+            // Positions start at 1, and the characters before it count towards the length
+            let start = int_arg(&values, 1, function)?.saturating_sub(1);
+            let end = match values.get(2) {
+                Some(_) => {
+                    let length = int_arg(&values, 2, function)?;
+                    if length < 0 {
+                        return Err(PipelineError::InvalidFunctionArgument(
+                            function.to_string(),
+                            values[2].clone(),
+                            2,
+                        ));
+                    }
+                    Some(start.saturating_add(length))
+                }
+                None => None,
+            };
+            let substring = value
+                .chars()
+                .enumerate()
+                .filter(|(idx, _)| {
+                    let idx = *idx as i64;
+                    idx >= start && !matches!(end, Some(end) if idx >= end)
+                })
+                .map(|(_, c)| c)
+                .collect();
+            string_result(substring, &values)
+        }
+        ScalarFunctionType::Replace => {
+            let from = string_arg(&values, 1, function)?;
+            let to = string_arg(&values, 2, function)?;
+            let replaced = if from.is_empty() {
+                value
+            } else {
+                value.replace(&from, &to)
+            };
+            string_result(replaced, &values)
+        }
+        ScalarFunctionType::Position => {
+            // The position of the substring in the second argument, starting at 1, or 0
+            let haystack = string_arg(&values, 1, function)?;
+            let position = haystack
+                .find(&value)
+                .map_or(0, |idx| haystack[..idx].chars().count() as u64 + 1);
+            Field::UInt(position)
+        }
+        ScalarFunctionType::SplitPart => {
+            let delimiter = string_arg(&values, 1, function)?;
+            let n = int_arg(&values, 2, function)?;
+            let parts: Vec<&str> = if delimiter.is_empty() {
+                vec![value.as_str()]
+            } else {
+                value.split(delimiter.as_str()).collect()
+            };
+            // Negative indexes count from the end, as in PostgreSQL
+            let part = match n {
+                0 => {
+                    return Err(PipelineError::InvalidFunctionArgument(
+                        function.to_string(),
+                        values[2].clone(),
+                        2,
+                    ))
+                }
+                n if n > 0 => parts.get(n as usize - 1),
+                n => parts
+                    .len()
+                    .checked_sub(n.unsigned_abs() as usize)
+                    .and_then(|idx| parts.get(idx)),
+            };
+            string_result(part.unwrap_or(&"").to_string(), &values)
+        }
+        ScalarFunctionType::Lpad | ScalarFunctionType::Rpad => {
+            let length = int_arg(&values, 1, function)?;
+            if length > MAX_PAD_LENGTH {
+                return Err(PipelineError::InvalidFunctionArgument(
+                    function.to_string(),
+                    values[1].clone(),
+                    1,
+                ));
+            }
+            let fill = match values.get(2) {
+                Some(_) => string_arg(&values, 2, function)?,
+                None => " ".to_string(),
+            };
+            let left = matches!(function, ScalarFunctionType::Lpad);
+            string_result(pad(&value, length, &fill, left), &values)
+        }
+        ScalarFunctionType::RegexpLike => {
+            let pattern = string_arg(&values, 1, function)?;
+            let flags = match values.get(2) {
+                Some(_) => string_arg(&values, 2, function)?,
+                None => String::new(),
+            };
+            let regex = match regex {
+                Some(CompiledRegex(regex)) => Cow::Borrowed(regex),
+                None => Cow::Owned(build_regex(&pattern, &flags, function)?),
+            };
+            Field::Boolean(regex.is_match(&value))
+        }
+        ScalarFunctionType::RegexpReplace => {
+            // Only the first match is replaced, unless the `g` flag is set
+            let pattern = string_arg(&values, 1, function)?;
+            let replacement = string_arg(&values, 2, function)?;
+            let flags = match values.get(3) {
+                Some(_) => string_arg(&values, 3, function)?,
+                None => String::new(),
+            };
+            let regex = match regex {
+                Some(CompiledRegex(regex)) => Cow::Borrowed(regex),
+                None => Cow::Owned(build_regex(&pattern, &flags, function)?),
+            };
+            let replaced = if flags.contains('g') {
+                regex.replace_all(&value, replacement.as_str())
+            } else {
+                regex.replace(&value, replacement.as_str())
+            };
+            string_result(replaced.into_owned(), &values)
+        }
+        _ => return Err(PipelineError::InvalidFunction(function.to_string())),
+    })
+}
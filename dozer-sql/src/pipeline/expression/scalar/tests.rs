@@ -1,8 +1,20 @@
 #[cfg(test)]
 mod cast;
 #[cfg(test)]
+mod datetime;
+#[cfg(test)]
+mod json;
+#[cfg(test)]
+mod null;
+#[cfg(test)]
 mod number;
 #[cfg(test)]
+mod postgres;
+#[cfg(test)]
 mod scalar_common;
 #[cfg(test)]
 mod string;
+#[cfg(test)]
+mod udf;
+#[cfg(test)]
+mod wasm;
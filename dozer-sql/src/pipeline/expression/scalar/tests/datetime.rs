@@ -0,0 +1,261 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::builder::{BuilderExpressionType, ExpressionBuilder};
+use crate::pipeline::expression::execution::ExpressionExecutor;
+use crate::pipeline::expression::scalar::tests::scalar_common::run_scalar_fct;
+use crate::pipeline::tests::utils::get_select;
+use dozer_types::chrono::{DateTime, NaiveDate};
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+use sqlparser::ast::SelectItem;
+use std::thread::sleep;
+use std::time::Duration;
+
+fn get_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("ts"),
+                FieldType::Timestamp,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("d"),
+                FieldType::Date,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn get_input() -> Vec<Field> {
+    vec![
+        Field::Timestamp(DateTime::parse_from_rfc3339("2023-02-15T13:45:30.250+02:00").unwrap()),
+        Field::Date(NaiveDate::from_ymd_opt(2023, 2, 15).unwrap()),
+    ]
+}
+
+fn timestamp(value: &str) -> Field {
+    Field::Timestamp(DateTime::parse_from_rfc3339(value).unwrap())
+}
+
+#[test]
+fn test_extract() {
+    let cases = [
+        ("SELECT EXTRACT(YEAR FROM ts) FROM users", 2023),
+        ("SELECT EXTRACT(QUARTER FROM ts) FROM users", 1),
+        ("SELECT EXTRACT(MONTH FROM ts) FROM users", 2),
+        ("SELECT EXTRACT(DAY FROM ts) FROM users", 15),
+        ("SELECT EXTRACT(DOW FROM ts) FROM users", 3),
+        ("SELECT EXTRACT(HOUR FROM ts) FROM users", 13),
+        ("SELECT EXTRACT(MINUTE FROM ts) FROM users", 45),
+        ("SELECT EXTRACT(MILLISECONDS FROM ts) FROM users", 30_250),
+        ("SELECT EXTRACT(EPOCH FROM ts) FROM users", 1_676_461_530),
+        ("SELECT EXTRACT(HOUR FROM d) FROM users", 0),
+        ("SELECT DATE_PART('doy', d) FROM users", 46),
+    ];
+    for (sql, expected) in cases {
+        let f = run_scalar_fct(sql, get_schema(), get_input());
+        assert_eq!(f, Field::Int(expected), "{sql}");
+    }
+
+    let f = run_scalar_fct(
+        "SELECT EXTRACT(YEAR FROM ts) FROM users",
+        get_schema(),
+        vec![Field::Null, Field::Null],
+    );
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+fn test_date_trunc() {
+    let cases = [
+        ("year", "2023-01-01T00:00:00+02:00"),
+        ("quarter", "2023-01-01T00:00:00+02:00"),
+        ("month", "2023-02-01T00:00:00+02:00"),
+        ("week", "2023-02-13T00:00:00+02:00"),
+        ("day", "2023-02-15T00:00:00+02:00"),
+        ("hour", "2023-02-15T13:00:00+02:00"),
+        ("minute", "2023-02-15T13:45:00+02:00"),
+        ("second", "2023-02-15T13:45:30+02:00"),
+    ];
+    for (part, expected) in cases {
+        let f = run_scalar_fct(
+            &format!("SELECT DATE_TRUNC('{part}', ts) FROM users"),
+            get_schema(),
+            get_input(),
+        );
+        assert_eq!(f, timestamp(expected), "{part}");
+    }
+
+    let f = run_scalar_fct(
+        "SELECT DATE_TRUNC('month', d) FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::Date(NaiveDate::from_ymd_opt(2023, 2, 1).unwrap()));
+}
+
+#[test]
+fn test_to_timestamp_and_to_char() {
+    let f = run_scalar_fct(
+        "SELECT TO_TIMESTAMP('2023-02-15 13:45:30', '%Y-%m-%d %H:%M:%S') FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, timestamp("2023-02-15T13:45:30+00:00"));
+
+    let f = run_scalar_fct(
+        "SELECT TO_TIMESTAMP('15/02/2023', '%d/%m/%Y') FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, timestamp("2023-02-15T00:00:00+00:00"));
+
+    let f = run_scalar_fct(
+        "SELECT TO_CHAR(ts, '%Y-%m-%d %H:%M') FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::String("2023-02-15 13:45".to_string()));
+
+    let f = run_scalar_fct(
+        "SELECT TO_CHAR(d, '%d %b %Y') FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::String("15 Feb 2023".to_string()));
+}
+
+#[test]
+fn test_template_patterns() {
+    let f = run_scalar_fct(
+        "SELECT TO_TIMESTAMP('2023-02-15 13:45:30', 'YYYY-MM-DD HH24:MI:SS') FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, timestamp("2023-02-15T13:45:30+00:00"));
+
+    let f = run_scalar_fct(
+        "SELECT TO_TIMESTAMP('15/02/2023', 'dd/mm/yyyy') FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, timestamp("2023-02-15T00:00:00+00:00"));
+
+    let f = run_scalar_fct(
+        "SELECT TO_CHAR(ts, 'Dy DD Mon YYYY HH12:MI PM') FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::String("Wed 15 Feb 2023 01:45 PM".to_string()));
+
+    // Quoted text is not translated
+    let f = run_scalar_fct(
+        "SELECT TO_CHAR(ts, 'YYYY-MM-DD\"T\"HH24:MI:SS.MS') FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::String("2023-02-15T13:45:30.250".to_string()));
+
+    let f = run_scalar_fct(
+        "SELECT TO_CHAR(d, 'Day, DDD\"th day\"') FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::String("Wednesday, 046th day".to_string()));
+}
+
+#[test]
+fn test_interval_arithmetic() {
+    let f = run_scalar_fct(
+        "SELECT ts + INTERVAL '2' HOUR FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, timestamp("2023-02-15T15:45:30.250+02:00"));
+
+    let f = run_scalar_fct(
+        "SELECT ts - INTERVAL '1 day' FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, timestamp("2023-02-14T13:45:30.250+02:00"));
+
+    let f = run_scalar_fct(
+        "SELECT INTERVAL '30' MINUTE + d FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, timestamp("2023-02-15T00:30:00+00:00"));
+
+    let f = run_scalar_fct(
+        "SELECT DATE_TRUNC('hour', ts + INTERVAL '20' MINUTE) FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, timestamp("2023-02-15T14:00:00+02:00"));
+
+    // Calendar intervals keep the day of the month, clamped to the end of shorter months
+    let f = run_scalar_fct(
+        "SELECT ts + INTERVAL '1' MONTH FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, timestamp("2023-03-15T13:45:30.250+02:00"));
+
+    let f = run_scalar_fct(
+        "SELECT ts - INTERVAL '2 years' FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, timestamp("2021-02-15T13:45:30.250+02:00"));
+
+    let f = run_scalar_fct(
+        "SELECT ts + INTERVAL '349' DAY + INTERVAL '1' MONTH FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, timestamp("2024-02-29T13:45:30.250+02:00"));
+
+    // Intervals out of the range of a duration are rejected when the expression is built
+    let select = get_select("SELECT ts + INTERVAL '200000000000000' DAY FROM users").unwrap();
+    let SelectItem::UnnamedExpr(expr) = &select.projection[0] else {
+        unreachable!()
+    };
+    assert!(matches!(
+        ExpressionBuilder {}.build(&BuilderExpressionType::FullExpression, expr, &get_schema()),
+        Err(PipelineError::InvalidInterval(_))
+    ));
+}
+
+#[test]
+fn test_now() {
+    let select = get_select("SELECT NOW() FROM users").unwrap();
+    let expression = match &select.projection[0] {
+        SelectItem::UnnamedExpr(expr) => ExpressionBuilder {}
+            .build(&BuilderExpressionType::FullExpression, expr, &get_schema())
+            .unwrap(),
+        item => panic!("unexpected select item {item}"),
+    };
+
+    // NOW() is evaluated once, so that every operation gets the same value
+    let record = Record::new(None, get_input(), None);
+    let first = expression.evaluate(&record, &get_schema()).unwrap();
+    sleep(Duration::from_millis(2));
+    assert!(matches!(first, Field::Timestamp(_)));
+    assert_eq!(expression.evaluate(&record, &get_schema()).unwrap(), first);
+
+    assert!(get_select("SELECT NOW(1) FROM users")
+        .map(|select| match &select.projection[0] {
+            SelectItem::UnnamedExpr(expr) => ExpressionBuilder {}
+                .build(&BuilderExpressionType::FullExpression, expr, &get_schema())
+                .is_err(),
+            _ => false,
+        })
+        .unwrap());
+}
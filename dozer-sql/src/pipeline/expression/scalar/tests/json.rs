@@ -0,0 +1,130 @@
+use crate::pipeline::expression::scalar::json::{parse_json_path, JsonPathElement};
+use crate::pipeline::expression::scalar::tests::scalar_common::run_scalar_fct;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+
+fn get_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("payload"),
+                FieldType::Bson,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn get_input() -> Vec<Field> {
+    vec![Field::Bson(
+        br#"{"user": {"name": "John", "age": 42, "tags": ["a", "b"]}, "a.b": 1.5, "empty": null}"#
+            .to_vec(),
+    )]
+}
+
+#[test]
+fn test_parse_json_path() {
+    assert_eq!(
+        parse_json_path("$.a.b[1]['c.d'][\"e\\\"\"]").unwrap(),
+        vec![
+            JsonPathElement::Key("a".to_string()),
+            JsonPathElement::Key("b".to_string()),
+            JsonPathElement::Index(1),
+            JsonPathElement::Key("c.d".to_string()),
+            JsonPathElement::Key("e\"".to_string()),
+        ]
+    );
+    assert_eq!(parse_json_path("$").unwrap(), vec![]);
+    assert!(parse_json_path("a.b").is_err());
+    assert!(parse_json_path("$.").is_err());
+    assert!(parse_json_path("$[x]").is_err());
+    assert!(parse_json_path("$['a'").is_err());
+}
+
+#[test]
+fn test_json_extract() {
+    let f = run_scalar_fct(
+        "SELECT JSON_EXTRACT(payload, '$.user.tags') FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::Bson(br#"["a","b"]"#.to_vec()));
+
+    let f = run_scalar_fct(
+        "SELECT payload -> 'user' -> 'name' FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::Bson(br#""John""#.to_vec()));
+
+    let f = run_scalar_fct(
+        "SELECT JSON_EXTRACT(payload, '$.user.missing') FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::Null);
+
+    let f = run_scalar_fct(
+        "SELECT payload -> 'empty' FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+fn test_json_value() {
+    let f = run_scalar_fct(
+        "SELECT JSON_VALUE(payload, '$.user.name') FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::String("John".to_string()));
+
+    let f = run_scalar_fct(
+        "SELECT payload -> 'user' -> 'tags' ->> 1 FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::String("b".to_string()));
+
+    let f = run_scalar_fct(
+        "SELECT payload ->> 'a.b' FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::String("1.5".to_string()));
+
+    let f = run_scalar_fct(
+        "SELECT JSON_VALUE(payload, '$.user.name') FROM users",
+        get_schema(),
+        vec![Field::Null],
+    );
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+fn test_json_cast() {
+    let f = run_scalar_fct(
+        "SELECT CAST(payload -> 'user' -> 'age' AS INT) FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::Int(42));
+
+    let f = run_scalar_fct(
+        "SELECT CAST(JSON_EXTRACT(payload, '$[\"a.b\"]') AS FLOAT) FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::Float(OrderedFloat(1.5)));
+
+    let f = run_scalar_fct(
+        "SELECT CAST(payload -> 'user' -> 'name' AS STRING) FROM users",
+        get_schema(),
+        get_input(),
+    );
+    assert_eq!(f, Field::String("John".to_string()));
+}
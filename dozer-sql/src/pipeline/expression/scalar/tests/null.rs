@@ -0,0 +1,217 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::builder::{BuilderExpressionType, ExpressionBuilder};
+use crate::pipeline::expression::execution::Expression::{
+    CoalesceFunction, Column, Literal, ScalarFunction,
+};
+use crate::pipeline::expression::execution::ExpressionExecutor;
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
+use crate::pipeline::expression::scalar::tests::scalar_common::run_scalar_fct;
+use crate::pipeline::tests::utils::get_select;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+use sqlparser::ast::SelectItem;
+
+fn get_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("a"),
+                FieldType::Int,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("b"),
+                FieldType::Float,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("c"),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+#[test]
+fn test_coalesce() {
+    let input = vec![
+        Field::Null,
+        Field::Float(OrderedFloat(2.5)),
+        Field::String("x".to_string()),
+    ];
+    let f = run_scalar_fct(
+        "SELECT COALESCE(NULL, a, b) FROM USERS",
+        get_schema(),
+        input.clone(),
+    );
+    assert_eq!(f, Field::Float(OrderedFloat(2.5)));
+
+    let f = run_scalar_fct(
+        "SELECT COALESCE(c, 'y') FROM USERS",
+        get_schema(),
+        input.clone(),
+    );
+    assert_eq!(f, Field::String("x".to_string()));
+
+    let f = run_scalar_fct("SELECT COALESCE(a, NULL) FROM USERS", get_schema(), input);
+    assert_eq!(f, Field::Null);
+
+    // Integers are converted to the common type of the arguments
+    let f = run_scalar_fct(
+        "SELECT COALESCE(a, b) FROM USERS",
+        get_schema(),
+        vec![Field::Int(1), Field::Float(OrderedFloat(2.5)), Field::Null],
+    );
+    assert_eq!(f, Field::Float(OrderedFloat(1.0)));
+}
+
+#[test]
+fn test_coalesce_errors() {
+    let schema = get_schema();
+
+    // Incompatible arguments are rejected when the expression is built
+    let select = get_select("SELECT COALESCE(a, c) FROM USERS").unwrap();
+    let SelectItem::UnnamedExpr(expr) = &select.projection[0] else {
+        unreachable!()
+    };
+    assert!(matches!(
+        ExpressionBuilder {}.build(&BuilderExpressionType::FullExpression, expr, &schema),
+        Err(PipelineError::InvalidFunctionArgumentType(
+            _,
+            FieldType::String,
+            _,
+            1
+        ))
+    ));
+
+    // Values out of the range of the common type are errors
+    let coalesce = CoalesceFunction {
+        fun: ScalarFunctionType::Coalesce,
+        args: vec![Literal(Field::UInt(u64::MAX)), Column { index: 0 }],
+        return_type: FieldType::Int,
+    };
+    let record = Record::new(
+        None,
+        vec![Field::Int(1), Field::Float(OrderedFloat(2.5)), Field::Null],
+        None,
+    );
+    assert!(matches!(
+        coalesce.evaluate(&record, &schema),
+        Err(PipelineError::InvalidFunctionArgument(_, Field::UInt(_), 0))
+    ));
+}
+
+#[test]
+fn test_ifnull() {
+    let f = run_scalar_fct(
+        "SELECT IFNULL(a, 0) FROM USERS",
+        get_schema(),
+        vec![Field::Null, Field::Float(OrderedFloat(2.5)), Field::Null],
+    );
+    assert_eq!(f, Field::Int(0));
+
+    let f = run_scalar_fct(
+        "SELECT IFNULL(a, 0) FROM USERS",
+        get_schema(),
+        vec![Field::Int(3), Field::Float(OrderedFloat(2.5)), Field::Null],
+    );
+    assert_eq!(f, Field::Int(3));
+}
+
+#[test]
+fn test_nullif() {
+    let input = vec![
+        Field::Int(1),
+        Field::Float(OrderedFloat(1.0)),
+        Field::String("x".to_string()),
+    ];
+    let f = run_scalar_fct(
+        "SELECT NULLIF(a, b) FROM USERS",
+        get_schema(),
+        input.clone(),
+    );
+    assert_eq!(f, Field::Null);
+
+    let f = run_scalar_fct(
+        "SELECT NULLIF(a, 2) FROM USERS",
+        get_schema(),
+        input.clone(),
+    );
+    assert_eq!(f, Field::Int(1));
+
+    let f = run_scalar_fct("SELECT NULLIF(c, NULL) FROM USERS", get_schema(), input);
+    assert_eq!(f, Field::String("x".to_string()));
+}
+
+#[test]
+fn test_null_functions_types() {
+    let schema = get_schema();
+    let coalesce = |args| ScalarFunction {
+        fun: ScalarFunctionType::Coalesce,
+        args,
+    };
+
+    // The result is only nullable when all the arguments are
+    let t = coalesce(vec![Column { index: 0 }, Column { index: 1 }])
+        .get_type(&schema)
+        .unwrap();
+    assert_eq!(t.return_type, FieldType::Float);
+    assert!(!t.nullable);
+
+    let t = coalesce(vec![Column { index: 0 }, Literal(Field::Null)])
+        .get_type(&schema)
+        .unwrap();
+    assert_eq!(t.return_type, FieldType::Int);
+    assert!(t.nullable);
+
+    let t = coalesce(vec![
+        Column { index: 0 },
+        Literal(Field::Decimal(Decimal::new(1, 1))),
+    ])
+    .get_type(&schema)
+    .unwrap();
+    assert_eq!(t.return_type, FieldType::Decimal);
+    assert!(!t.nullable);
+
+    assert!(coalesce(vec![]).get_type(&schema).is_err());
+    assert!(coalesce(vec![Literal(Field::Null)])
+        .get_type(&schema)
+        .is_err());
+    assert!(coalesce(vec![Column { index: 0 }, Column { index: 2 }])
+        .get_type(&schema)
+        .is_err());
+
+    let t = ScalarFunction {
+        fun: ScalarFunctionType::Nullif,
+        args: vec![Column { index: 1 }, Literal(Field::Int(0))],
+    }
+    .get_type(&schema)
+    .unwrap();
+    assert_eq!(t.return_type, FieldType::Float);
+    assert!(t.nullable);
+
+    let ifnull = |args| ScalarFunction {
+        fun: ScalarFunctionType::Ifnull,
+        args,
+    };
+    assert!(ifnull(vec![Column { index: 0 }]).get_type(&schema).is_err());
+    assert!(ifnull(vec![
+        Column { index: 0 },
+        Column { index: 1 },
+        Column { index: 1 }
+    ])
+    .get_type(&schema)
+    .is_err());
+}
@@ -1,8 +1,14 @@
-use crate::pipeline::expression::execution::Expression::Literal;
+use crate::pipeline::expression::builder::{BuilderExpressionType, ExpressionBuilder};
+use crate::pipeline::expression::execution::Expression::{Column, Literal, ScalarFunction};
+use crate::pipeline::expression::execution::ExpressionExecutor;
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
 use crate::pipeline::expression::scalar::number::evaluate_round;
 use crate::pipeline::expression::scalar::tests::scalar_common::run_scalar_fct;
+use crate::pipeline::tests::utils::get_select;
 use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
 use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+use sqlparser::ast::SelectItem;
 
 #[test]
 fn test_abs() {
@@ -83,4 +89,167 @@ fn test_round() {
             .unwrap_or_else(|e| panic!("{}", e.to_string())),
         Field::Float(OrderedFloat(3.0))
     );
+
+    // The number of decimals is clamped to the range of i32 instead of wrapping
+    let v = Box::new(Literal(Field::Int(1234)));
+    let d = &Box::new(Literal(Field::Int(1 << 32)));
+    assert_eq!(
+        evaluate_round(&Schema::empty(), &v, Some(d), &row)
+            .unwrap_or_else(|e| panic!("{}", e.to_string())),
+        Field::Int(1234)
+    );
+    let d = &Box::new(Literal(Field::UInt(u64::MAX)));
+    assert_eq!(
+        evaluate_round(&Schema::empty(), &v, Some(d), &row)
+            .unwrap_or_else(|e| panic!("{}", e.to_string())),
+        Field::Int(1234)
+    );
+    let d = &Box::new(Literal(Field::Int(-(1 << 32))));
+    assert_eq!(
+        evaluate_round(&Schema::empty(), &v, Some(d), &row)
+            .unwrap_or_else(|e| panic!("{}", e.to_string())),
+        Field::Int(0)
+    );
+
+    let v = Box::new(Literal(Field::Decimal(Decimal::MAX)));
+    let d = &Box::new(Literal(Field::Int(-1)));
+    assert!(evaluate_round(&Schema::empty(), &v, Some(d), &row).is_err());
+}
+
+fn get_number_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("i"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("u"),
+                FieldType::UInt,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("f"),
+                FieldType::Float,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("d"),
+                FieldType::Decimal,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn get_number_input() -> Vec<Field> {
+    vec![
+        Field::Int(-1250),
+        Field::UInt(7),
+        Field::Float(OrderedFloat(-2.5)),
+        Field::Decimal(Decimal::new(12345, 3)),
+    ]
+}
+
+#[test]
+fn test_number_functions() {
+    let cases = [
+        ("ABS(i)", Field::Int(1250)),
+        ("ABS(d)", Field::Decimal(Decimal::new(12345, 3))),
+        ("ROUND(i, -2)", Field::Int(-1300)),
+        ("ROUND(d, 2)", Field::Decimal(Decimal::new(1235, 2))),
+        ("ROUND(d, -1)", Field::Decimal(Decimal::new(10, 0))),
+        ("ROUND(f)", Field::Float(OrderedFloat(-3.0))),
+        ("CEIL(f)", Field::Float(OrderedFloat(-2.0))),
+        ("CEILING(d)", Field::Decimal(Decimal::new(13, 0))),
+        ("FLOOR(f)", Field::Float(OrderedFloat(-3.0))),
+        ("FLOOR(d)", Field::Decimal(Decimal::new(12, 0))),
+        ("FLOOR(u)", Field::UInt(7)),
+        ("SIGN(i)", Field::Int(-1)),
+        ("SIGN(u)", Field::UInt(1)),
+        ("SIGN(f)", Field::Float(OrderedFloat(-1.0))),
+        ("MOD(i, 300)", Field::Int(-50)),
+        ("MOD(u, 4)", Field::Int(3)),
+        ("MOD(d, 5)", Field::Decimal(Decimal::new(2345, 3))),
+        ("MOD(f, 2)", Field::Float(OrderedFloat(-0.5))),
+        ("POWER(u, 2)", Field::Float(OrderedFloat(49.0))),
+        ("POW(2, f)", Field::Float(OrderedFloat(2.0_f64.powf(-2.5)))),
+        ("SQRT(ABS(i) * 2)", Field::Float(OrderedFloat(50.0))),
+        ("LOG(100)", Field::Float(OrderedFloat(2.0))),
+        ("LOG(2, 8)", Field::Float(OrderedFloat(3.0))),
+        ("LN(EXP(u))", Field::Float(OrderedFloat(7.0))),
+    ];
+    for (function, expected) in cases {
+        let f = run_scalar_fct(
+            &format!("SELECT {function} FROM users"),
+            get_number_schema(),
+            get_number_input(),
+        );
+        assert_eq!(f, expected, "{function}");
+    }
+
+    let mut input = get_number_input();
+    input[2] = Field::Null;
+    let f = run_scalar_fct("SELECT CEIL(f) FROM users", get_number_schema(), input);
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+fn test_number_functions_types() {
+    let schema = get_number_schema();
+    let get_type = |fun, args| ScalarFunction { fun, args }.get_type(&schema);
+
+    let t = get_type(ScalarFunctionType::Round, vec![Column { index: 3 }]).unwrap();
+    assert_eq!(t.return_type, FieldType::Decimal);
+    assert!(!t.nullable);
+
+    let t = get_type(
+        ScalarFunctionType::Mod,
+        vec![Column { index: 1 }, Column { index: 2 }],
+    )
+    .unwrap();
+    assert_eq!(t.return_type, FieldType::Float);
+    assert!(t.nullable);
+
+    let t = get_type(ScalarFunctionType::Sqrt, vec![Column { index: 0 }]).unwrap();
+    assert_eq!(t.return_type, FieldType::Float);
+
+    assert!(get_type(
+        ScalarFunctionType::Floor,
+        vec![Literal(Field::String("1".to_string()))]
+    )
+    .is_err());
+    assert!(get_type(ScalarFunctionType::Power, vec![Column { index: 0 }]).is_err());
+}
+
+#[test]
+fn test_number_functions_domain() {
+    for function in ["SQRT(i)", "LN(i)", "LOG(1, u)", "MOD(u, 0)", "MOD(f, 0)"] {
+        let select = get_select(&format!("SELECT {function} FROM users")).unwrap();
+        let row = Record::new(None, get_number_input(), None);
+        let schema = get_number_schema();
+        let builder = ExpressionBuilder {};
+        let expression = match &select.projection[0] {
+            SelectItem::UnnamedExpr(expr) => builder
+                .build(&BuilderExpressionType::FullExpression, expr, &schema)
+                .unwrap(),
+            _ => unreachable!(),
+        };
+        assert!(expression.evaluate(&row, &schema).is_err(), "{function}");
+    }
 }
@@ -0,0 +1,53 @@
+use crate::pipeline::expression::scalar::tests::scalar_common::run_scalar_fct_with_dialect;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+use sqlparser::dialect::PostgreSqlDialect;
+
+fn get_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("i"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("s"),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+#[test]
+fn test_postgres_syntax() {
+    let cases = [
+        ("s::text", Field::Text("Alice".to_string())),
+        ("i::varchar", Field::String("42".to_string())),
+        ("i::double precision", Field::Float(OrderedFloat(42.0))),
+        ("i::numeric", Field::Decimal(Decimal::new(42, 0))),
+        ("'7'::int8", Field::Int(7)),
+        ("s || '-' || i::text", Field::Text("Alice-42".to_string())),
+        ("s ILIKE 'al%'", Field::Boolean(true)),
+        ("s NOT ILIKE 'AL%'", Field::Boolean(false)),
+        ("$$it's$$", Field::String("it's".to_string())),
+        ("$tag$a b$tag$", Field::String("a b".to_string())),
+    ];
+    for (expression, expected) in cases {
+        let f = run_scalar_fct_with_dialect(
+            &format!("SELECT {expression} FROM users"),
+            &PostgreSqlDialect {},
+            get_schema(),
+            vec![Field::Int(42), Field::String("Alice".to_string())],
+        );
+        assert_eq!(f, expected, "{expression}");
+    }
+}
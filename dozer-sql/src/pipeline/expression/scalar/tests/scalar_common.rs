@@ -1,10 +1,13 @@
 use crate::pipeline::builder::SchemaSQLContext;
-use crate::pipeline::{projection::factory::ProjectionProcessorFactory, tests::utils::get_select};
+use crate::pipeline::{
+    projection::factory::ProjectionProcessorFactory, tests::utils::get_select_with_dialect,
+};
 use dozer_core::dag::channels::ProcessorChannelForwarder;
 use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
 use dozer_core::dag::node::ProcessorFactory;
 use dozer_core::storage::lmdb_storage::LmdbEnvironmentManager;
 use dozer_types::types::{Field, Operation, Record, Schema};
+use sqlparser::dialect::{AnsiDialect, Dialect};
 use std::collections::HashMap;
 use tempdir::TempDir;
 
@@ -24,7 +27,16 @@ impl ProcessorChannelForwarder for TestChannelForwarder {
 }
 
 pub(crate) fn run_scalar_fct(sql: &str, schema: Schema, input: Vec<Field>) -> Field {
-    let select = get_select(sql).unwrap();
+    run_scalar_fct_with_dialect(sql, &AnsiDialect {}, schema, input)
+}
+
+pub(crate) fn run_scalar_fct_with_dialect(
+    sql: &str,
+    dialect: &dyn Dialect,
+    schema: Schema,
+    input: Vec<Field>,
+) -> Field {
+    let select = get_select_with_dialect(sql, dialect).unwrap();
     let processor_factory = ProjectionProcessorFactory::_new(select.projection);
     processor_factory
         .get_output_schema(
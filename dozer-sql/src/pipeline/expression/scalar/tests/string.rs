@@ -1,4 +1,7 @@
-use crate::pipeline::expression::execution::Expression::Literal;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::execution::Expression::{Column, Literal, ScalarFunction};
+use crate::pipeline::expression::execution::ExpressionExecutor;
+use crate::pipeline::expression::scalar::common::ScalarFunctionType;
 use crate::pipeline::expression::scalar::{
     string::evaluate_like, tests::scalar_common::run_scalar_fct,
 };
@@ -435,3 +438,162 @@ fn test_like_escape() {
     );
     assert_eq!(f, Field::String("J%".to_string()));
 }
+
+fn get_name_schema(typ: FieldType) -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(String::from("name"), typ, true, SourceDefinition::Dynamic),
+            false,
+        )
+        .clone()
+}
+
+#[test]
+fn test_string_functions() {
+    let cases = [
+        ("LOWER(name)", "john.doe@example.com"),
+        ("REVERSE(name)", "MOC.ELPMAXE@EOD.NHOJ"),
+        ("SUBSTRING(name FROM 6 FOR 3)", "DOE"),
+        ("SUBSTRING(name, 10)", "EXAMPLE.COM"),
+        ("SUBSTRING(name FROM -1 FOR 3)", "J"),
+        ("SUBSTR(name, 1, 4)", "JOHN"),
+        ("REPLACE(name, '.', '_')", "JOHN_DOE@EXAMPLE_COM"),
+        ("SPLIT_PART(name, '@', 2)", "EXAMPLE.COM"),
+        ("SPLIT_PART(name, '.', -1)", "COM"),
+        ("SPLIT_PART(name, '.', 5)", ""),
+        ("LPAD(SPLIT_PART(name, '.', 1), 6, 'xy')", "xyJOHN"),
+        ("RPAD(SPLIT_PART(name, '.', 1), 6)", "JOHN  "),
+        ("LPAD(name, 3)", "JOH"),
+        (
+            "REGEXP_REPLACE(name, '[AEIOU]', '*')",
+            "J*HN.DOE@EXAMPLE.COM",
+        ),
+        (
+            "REGEXP_REPLACE(name, '[aeiou]', '*', 'gi')",
+            "J*HN.D**@*X*MPL*.C*M",
+        ),
+        (
+            "REGEXP_REPLACE(name, '(\\w+)\\.(\\w+)@.*', '$2 $1')",
+            "DOE JOHN",
+        ),
+    ];
+    for (function, expected) in cases {
+        let f = run_scalar_fct(
+            &format!("SELECT {function} FROM users"),
+            get_name_schema(FieldType::String),
+            vec![Field::String("JOHN.DOE@EXAMPLE.COM".to_string())],
+        );
+        assert_eq!(f, Field::String(expected.to_string()), "{function}");
+    }
+
+    let f = run_scalar_fct(
+        "SELECT LOWER(name) FROM users",
+        get_name_schema(FieldType::Text),
+        vec![Field::Text("John".to_string())],
+    );
+    assert_eq!(f, Field::Text("john".to_string()));
+
+    let f = run_scalar_fct(
+        "SELECT SUBSTRING(name, 2, 2) FROM users",
+        get_name_schema(FieldType::String),
+        vec![Field::Null],
+    );
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+fn test_string_function_bounds() {
+    let schema = get_name_schema(FieldType::String);
+    let record = Record::new(None, vec![Field::String("JOHN".to_string())], None);
+    let evaluate = |fun, arg| {
+        ScalarFunction {
+            fun,
+            args: vec![Column { index: 0 }, Literal(Field::Int(arg))],
+        }
+        .evaluate(&record, &schema)
+    };
+
+    assert_eq!(
+        evaluate(ScalarFunctionType::Substring, i64::MIN).unwrap(),
+        Field::String("JOHN".to_string())
+    );
+    assert!(matches!(
+        evaluate(ScalarFunctionType::Lpad, 1 << 30),
+        Err(PipelineError::InvalidFunctionArgument(_, Field::Int(_), 1))
+    ));
+    assert!(matches!(
+        evaluate(ScalarFunctionType::Rpad, i64::MAX),
+        Err(PipelineError::InvalidFunctionArgument(_, Field::Int(_), 1))
+    ));
+}
+
+#[test]
+fn test_string_predicates() {
+    let cases = [
+        ("POSITION('DOE' IN name)", Field::UInt(6)),
+        ("POSITION('X' IN LOWER(name))", Field::UInt(0)),
+        ("name ILIKE 'john%'", Field::Boolean(true)),
+        ("name NOT ILIKE '%doe'", Field::Boolean(true)),
+        ("REGEXP_LIKE(name, '^J.*M$')", Field::Boolean(true)),
+        ("REGEXP_LIKE(name, 'doe')", Field::Boolean(false)),
+        ("REGEXP_LIKE(name, 'doe', 'i')", Field::Boolean(true)),
+        // Patterns which are not literals are compiled for each record
+        ("REGEXP_LIKE(name, LOWER(name), 'i')", Field::Boolean(true)),
+        ("REGEXP_LIKE(name, LOWER(name))", Field::Boolean(false)),
+    ];
+    for (function, expected) in cases {
+        let f = run_scalar_fct(
+            &format!("SELECT {function} FROM users"),
+            get_name_schema(FieldType::String),
+            vec![Field::String("JOHN.DOE@EXAMPLE.COM".to_string())],
+        );
+        assert_eq!(f, expected, "{function}");
+    }
+}
+
+#[test]
+fn test_string_functions_validation() {
+    let schema = get_name_schema(FieldType::String);
+    let validate = |fun, args| ScalarFunction { fun, args }.get_type(&schema);
+
+    let t = validate(
+        ScalarFunctionType::SplitPart,
+        vec![
+            Column { index: 0 },
+            Literal(Field::String(".".to_string())),
+            Literal(Field::Int(1)),
+        ],
+    )
+    .unwrap();
+    assert_eq!(t.return_type, FieldType::String);
+    assert!(t.nullable);
+
+    assert!(matches!(
+        validate(
+            ScalarFunctionType::Lpad,
+            vec![Column { index: 0 }, Literal(Field::String("5".to_string()))],
+        ),
+        Err(PipelineError::InvalidFunctionArgumentType(
+            _,
+            FieldType::String,
+            _,
+            1
+        ))
+    ));
+    assert!(matches!(
+        validate(ScalarFunctionType::Replace, vec![Column { index: 0 }]),
+        Err(PipelineError::NotEnoughArguments(_))
+    ));
+    assert!(matches!(
+        validate(
+            ScalarFunctionType::Reverse,
+            vec![Column { index: 0 }, Column { index: 0 }]
+        ),
+        Err(PipelineError::TooManyArguments(_))
+    ));
+    assert!(validate(
+        ScalarFunctionType::RegexpLike,
+        vec![Column { index: 0 }, Literal(Field::String("(".to_string()))],
+    )
+    .is_err());
+}
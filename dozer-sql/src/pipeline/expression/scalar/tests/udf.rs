@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::builder::{BuilderExpressionType, ExpressionBuilder};
+use crate::pipeline::expression::execution::ExpressionExecutor;
+use crate::pipeline::expression::scalar::tests::scalar_common::run_scalar_fct;
+use crate::pipeline::expression::udf::{get_udf, register_udf, ScalarUdf};
+use crate::pipeline::tests::utils::get_select;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+use sqlparser::ast::SelectItem;
+
+/// Repeats a string a given number of times
+struct RepeatUdf;
+
+impl ScalarUdf for RepeatUdf {
+    fn name(&self) -> &str {
+        "test_repeat"
+    }
+
+    fn validate_args(&self, args: &[FieldType]) -> Result<(), PipelineError> {
+        match args {
+            [FieldType::String, FieldType::Int] => Ok(()),
+            _ => Err(PipelineError::InvalidFunction(format!(
+                "{}() expects a string and an integer",
+                self.name()
+            ))),
+        }
+    }
+
+    fn return_type(&self, _args: &[FieldType]) -> FieldType {
+        FieldType::String
+    }
+
+    fn evaluate(&self, args: &[Field]) -> Result<Field, PipelineError> {
+        match args {
+            [Field::String(s), Field::Int(n)] => Ok(Field::String(s.repeat(*n as usize))),
+            _ => Ok(Field::Null),
+        }
+    }
+}
+
+fn get_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("name"),
+                FieldType::String,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("count"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn build(sql: &str, schema: &Schema) -> Result<(), PipelineError> {
+    let select = get_select(sql).unwrap();
+    match &select.projection[0] {
+        SelectItem::UnnamedExpr(expr) => ExpressionBuilder {}
+            .build(&BuilderExpressionType::FullExpression, expr, schema)?
+            .get_type(schema)
+            .map(|_| ()),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_udf() {
+    register_udf(Arc::new(RepeatUdf)).unwrap();
+    assert!(get_udf("TEST_REPEAT").is_some());
+    assert!(matches!(
+        register_udf(Arc::new(RepeatUdf)),
+        Err(PipelineError::UdfAlreadyRegistered(_))
+    ));
+
+    let f = run_scalar_fct(
+        "SELECT TEST_REPEAT(name, count) FROM users",
+        get_schema(),
+        vec![Field::String("ab".to_string()), Field::Int(3)],
+    );
+    assert_eq!(f, Field::String("ababab".to_string()));
+
+    let f = run_scalar_fct(
+        "SELECT test_repeat(name, 2) FROM users",
+        get_schema(),
+        vec![Field::Null, Field::Int(3)],
+    );
+    assert_eq!(f, Field::Null);
+
+    let schema = get_schema();
+    assert!(build("SELECT test_repeat(name, count) FROM users", &schema).is_ok());
+    assert!(matches!(
+        build("SELECT test_repeat(count, name) FROM users", &schema),
+        Err(PipelineError::InvalidFunction(_))
+    ));
+    assert!(build("SELECT test_unknown(name) FROM users", &schema).is_err());
+}
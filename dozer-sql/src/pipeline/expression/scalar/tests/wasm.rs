@@ -0,0 +1,183 @@
+use crate::pipeline::errors::{PipelineError, WasmError};
+use crate::pipeline::expression::builder::{BuilderExpressionType, ExpressionBuilder};
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use crate::pipeline::expression::scalar::tests::scalar_common::run_scalar_fct;
+use crate::pipeline::expression::wasm::register_wasm_udf;
+use crate::pipeline::tests::utils::get_select;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+use sqlparser::ast::SelectItem;
+use tempdir::TempDir;
+
+const MODULE: &str = r#"
+(module
+  (global $total (mut i64) (i64.const 0))
+  (func (export "add") (param i64 i64) (result i64)
+    local.get 0
+    local.get 1
+    i64.add)
+  (func (export "half") (param f64) (result f64)
+    local.get 0
+    f64.const 2
+    f64.div)
+  (func (export "fail") (param i64) (result i64)
+    unreachable)
+  (func (export "spin") (param i64) (result i64)
+    (loop $l
+      br $l)
+    local.get 0)
+  (func (export "accumulate") (param i64) (result i64)
+    global.get $total
+    local.get 0
+    i64.add
+    global.set $total
+    local.get 0
+    i64.const 0
+    i64.lt_s
+    if
+      unreachable
+    end
+    global.get $total)
+  (func (export "pair") (param i64) (result i64 i64)
+    local.get 0
+    local.get 0))
+"#;
+
+fn write_module(dir: &TempDir) -> String {
+    let path = dir.path().join("test.wasm");
+    std::fs::write(&path, wat::parse_str(MODULE).unwrap()).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+fn get_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("a"),
+                FieldType::Int,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("b"),
+                FieldType::Float,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn build(function: &str) -> Expression {
+    let select = get_select(&format!("SELECT {function} FROM users")).unwrap();
+    match &select.projection[0] {
+        SelectItem::UnnamedExpr(expr) => *ExpressionBuilder {}
+            .build(&BuilderExpressionType::FullExpression, expr, &get_schema())
+            .unwrap(),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_wasm_udf() {
+    let dir = TempDir::new("wasm").unwrap();
+    let path = write_module(&dir);
+    register_wasm_udf("wasm_add", &path, Some("add")).unwrap();
+    register_wasm_udf("half", &path, None).unwrap();
+
+    let f = run_scalar_fct(
+        "SELECT wasm_add(a, 3) FROM users",
+        get_schema(),
+        vec![Field::Int(4), Field::Float(OrderedFloat(5.0))],
+    );
+    assert_eq!(f, Field::Int(7));
+
+    let f = run_scalar_fct(
+        "SELECT HALF(b) FROM users",
+        get_schema(),
+        vec![Field::Int(4), Field::Float(OrderedFloat(5.0))],
+    );
+    assert_eq!(f, Field::Float(OrderedFloat(2.5)));
+
+    let f = run_scalar_fct(
+        "SELECT wasm_add(a, 3) FROM users",
+        get_schema(),
+        vec![Field::Null, Field::Float(OrderedFloat(5.0))],
+    );
+    assert_eq!(f, Field::Null);
+
+    let schema = get_schema();
+    assert!(matches!(
+        build("wasm_add(a)").get_type(&schema),
+        Err(PipelineError::NotEnoughArguments(_))
+    ));
+    assert!(matches!(
+        build("wasm_add(a, 'x')").get_type(&schema),
+        Err(PipelineError::InvalidFunctionArgumentType(
+            _,
+            FieldType::String,
+            _,
+            1
+        ))
+    ));
+}
+
+#[test]
+fn test_wasm_udf_errors() {
+    let dir = TempDir::new("wasm").unwrap();
+    let path = write_module(&dir);
+    register_wasm_udf("wasm_fail", &path, Some("fail")).unwrap();
+    register_wasm_udf("wasm_spin", &path, Some("spin")).unwrap();
+
+    // Traps and endless loops are reported as errors, and the module can still be called
+    let schema = get_schema();
+    let record = Record::new(
+        None,
+        vec![Field::Int(1), Field::Float(OrderedFloat(1.0))],
+        None,
+    );
+    for function in ["wasm_fail(a)", "wasm_spin(a)", "wasm_fail(a)"] {
+        assert!(
+            matches!(
+                build(function).evaluate(&record, &schema),
+                Err(PipelineError::WasmError(WasmError::Trap(_, _)))
+            ),
+            "{function}"
+        );
+    }
+
+    // The state of the module is reset after a trap
+    register_wasm_udf("wasm_accumulate", &path, Some("accumulate")).unwrap();
+    let evaluate = |a: i64| {
+        let record = Record::new(
+            None,
+            vec![Field::Int(a), Field::Float(OrderedFloat(1.0))],
+            None,
+        );
+        build("wasm_accumulate(a)").evaluate(&record, &schema)
+    };
+    assert_eq!(evaluate(1).unwrap(), Field::Int(1));
+    assert_eq!(evaluate(2).unwrap(), Field::Int(3));
+    assert!(matches!(
+        evaluate(-1),
+        Err(PipelineError::WasmError(WasmError::Trap(_, _)))
+    ));
+    assert_eq!(evaluate(1).unwrap(), Field::Int(1));
+
+    assert!(matches!(
+        register_wasm_udf("wasm_missing", &path, Some("missing")),
+        Err(PipelineError::WasmError(WasmError::FunctionNotFound(_, _)))
+    ));
+    assert!(matches!(
+        register_wasm_udf("wasm_pair", &path, Some("pair")),
+        Err(PipelineError::WasmError(WasmError::UnsupportedSignature(_)))
+    ));
+    assert!(matches!(
+        register_wasm_udf("wasm_none", "missing.wasm", None),
+        Err(PipelineError::WasmError(WasmError::InvalidModule(_, _)))
+    ));
+}
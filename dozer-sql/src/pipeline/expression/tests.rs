@@ -1,2 +1,4 @@
 #[cfg(test)]
+mod conditional;
+#[cfg(test)]
 mod execution;
@@ -0,0 +1,210 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::builder::{BuilderExpressionType, ExpressionBuilder};
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use crate::pipeline::tests::utils::get_select;
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+use sqlparser::ast::SelectItem;
+
+fn schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("a"),
+                FieldType::Int,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("b"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn try_build(sql_expr: &str) -> Result<Box<Expression>, PipelineError> {
+    let select = get_select(&format!("SELECT {sql_expr} FROM t")).unwrap();
+    match &select.projection[0] {
+        SelectItem::UnnamedExpr(expr) => {
+            ExpressionBuilder {}.build(&BuilderExpressionType::FullExpression, expr, &schema())
+        }
+        item => panic!("unexpected select item {item}"),
+    }
+}
+
+fn build(sql_expr: &str) -> Box<Expression> {
+    try_build(sql_expr).unwrap()
+}
+
+fn evaluate(sql_expr: &str, a: Field) -> Field {
+    build(sql_expr)
+        .evaluate(&Record::new(None, vec![a, Field::Int(10)], None), &schema())
+        .unwrap_or_else(|e| panic!("{}", e.to_string()))
+}
+
+#[test]
+fn test_is_null() {
+    assert_eq!(evaluate("a IS NULL", Field::Null), Field::Boolean(true));
+    assert_eq!(evaluate("a IS NULL", Field::Int(1)), Field::Boolean(false));
+    assert_eq!(
+        evaluate("a IS NOT NULL", Field::Null),
+        Field::Boolean(false)
+    );
+    assert_eq!(
+        evaluate("a IS NOT NULL", Field::Int(1)),
+        Field::Boolean(true)
+    );
+
+    let expression_type = build("a IS NULL").get_type(&schema()).unwrap();
+    assert_eq!(expression_type.return_type, FieldType::Boolean);
+    assert!(!expression_type.nullable);
+}
+
+#[test]
+fn test_in_list() {
+    assert_eq!(
+        evaluate("a IN (1, 2, 3)", Field::Int(2)),
+        Field::Boolean(true)
+    );
+    assert_eq!(
+        evaluate("a IN (1, 2, 3)", Field::Int(4)),
+        Field::Boolean(false)
+    );
+    assert_eq!(
+        evaluate("a NOT IN (1, 2, 3)", Field::Int(4)),
+        Field::Boolean(true)
+    );
+    assert_eq!(
+        evaluate("a IN (1.5, b)", Field::Int(10)),
+        Field::Boolean(true)
+    );
+
+    // Three-valued logic
+    assert_eq!(evaluate("a IN (1, 2)", Field::Null), Field::Null);
+    assert_eq!(
+        evaluate("a IN (1, NULL)", Field::Int(1)),
+        Field::Boolean(true)
+    );
+    assert_eq!(evaluate("a IN (1, NULL)", Field::Int(2)), Field::Null);
+    assert_eq!(evaluate("a NOT IN (1, NULL)", Field::Int(2)), Field::Null);
+
+    assert!(build("a IN (1, 2)").get_type(&schema()).unwrap().nullable);
+    assert!(!build("b IN (1, 2)").get_type(&schema()).unwrap().nullable);
+}
+
+#[test]
+fn test_between() {
+    assert_eq!(
+        evaluate("a BETWEEN 1 AND 10", Field::Int(1)),
+        Field::Boolean(true)
+    );
+    assert_eq!(
+        evaluate("a BETWEEN 1 AND 10", Field::Int(10)),
+        Field::Boolean(true)
+    );
+    assert_eq!(
+        evaluate("a BETWEEN 1 AND 10", Field::Int(11)),
+        Field::Boolean(false)
+    );
+    assert_eq!(
+        evaluate("a NOT BETWEEN 1 AND 10", Field::Int(11)),
+        Field::Boolean(true)
+    );
+    assert_eq!(
+        evaluate("a BETWEEN 0.5 AND b", Field::Int(1)),
+        Field::Boolean(true)
+    );
+
+    // Three-valued logic
+    assert_eq!(evaluate("a BETWEEN 1 AND 10", Field::Null), Field::Null);
+    assert_eq!(evaluate("b BETWEEN a AND 20", Field::Null), Field::Null);
+    assert_eq!(
+        evaluate("b BETWEEN a AND 5", Field::Null),
+        Field::Boolean(false)
+    );
+}
+
+#[test]
+fn test_case() {
+    let searched = "CASE WHEN a > 5 THEN 'big' WHEN a > 0 THEN 'small' ELSE 'none' END";
+    assert_eq!(
+        evaluate(searched, Field::Int(7)),
+        Field::String("big".to_string())
+    );
+    assert_eq!(
+        evaluate(searched, Field::Int(1)),
+        Field::String("small".to_string())
+    );
+    // A null condition does not match
+    assert_eq!(
+        evaluate(searched, Field::Null),
+        Field::String("none".to_string())
+    );
+
+    let simple = "CASE a WHEN 1 THEN 1.5 WHEN 2 THEN 2.5 END";
+    assert_eq!(
+        evaluate(simple, Field::Int(2)),
+        Field::Float(OrderedFloat(2.5))
+    );
+    assert_eq!(evaluate(simple, Field::Int(3)), Field::Null);
+    assert_eq!(evaluate(simple, Field::Null), Field::Null);
+
+    let expression_type = build(searched).get_type(&schema()).unwrap();
+    assert_eq!(expression_type.return_type, FieldType::String);
+    assert!(!expression_type.nullable);
+
+    let expression_type = build(simple).get_type(&schema()).unwrap();
+    assert_eq!(expression_type.return_type, FieldType::Float);
+    assert!(expression_type.nullable);
+
+    assert!(try_build("CASE WHEN a > 1 THEN 1 ELSE 'one' END").is_err());
+}
+
+#[test]
+fn test_case_numeric_promotion() {
+    // Results of different numeric types are converted to their common type
+    let mixed = "CASE WHEN a > 5 THEN a WHEN a > 0 THEN CAST(a AS DECIMAL) ELSE 0.5 END";
+    assert_eq!(
+        evaluate(mixed, Field::Int(7)),
+        Field::Float(OrderedFloat(7.0))
+    );
+    assert_eq!(
+        evaluate(mixed, Field::Int(1)),
+        Field::Float(OrderedFloat(1.0))
+    );
+    assert_eq!(
+        evaluate(mixed, Field::Int(0)),
+        Field::Float(OrderedFloat(0.5))
+    );
+    let expression_type = build(mixed).get_type(&schema()).unwrap();
+    assert_eq!(expression_type.return_type, FieldType::Float);
+
+    let expression_type = build("CASE WHEN a > 5 THEN a ELSE CAST(b AS DECIMAL) END")
+        .get_type(&schema())
+        .unwrap();
+    assert_eq!(expression_type.return_type, FieldType::Decimal);
+
+    // Decimals and floats are compared as floats
+    assert_eq!(
+        evaluate("CAST(a AS DECIMAL) IN (0.5, 1.0)", Field::Int(1)),
+        Field::Boolean(true)
+    );
+    assert_eq!(
+        evaluate("0.5 BETWEEN CAST(a AS DECIMAL) AND 1", Field::Int(0)),
+        Field::Boolean(true)
+    );
+    assert_eq!(
+        evaluate(
+            "CASE CAST(a AS DECIMAL) WHEN 2.0 THEN 'two' END",
+            Field::Int(2)
+        ),
+        Field::String("two".to_string())
+    );
+}
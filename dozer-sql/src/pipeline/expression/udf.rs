@@ -0,0 +1,108 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use dozer_types::parking_lot::{const_rwlock, RwLock};
+use dozer_types::types::{Field, FieldType, Record, Schema, SourceDefinition};
+
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor, ExpressionType};
+
+/// A scalar function provided by the embedding application, callable from SQL by its name.
+pub trait ScalarUdf: Send + Sync {
+    /// Name used to call the function in SQL, matched case-insensitively
+    fn name(&self) -> &str;
+
+    /// Checks the argument types when the pipeline is built
+    fn validate_args(&self, args: &[FieldType]) -> Result<(), PipelineError>;
+
+    /// Returns the type of the result for validated argument types
+    fn return_type(&self, args: &[FieldType]) -> FieldType;
+
+    /// Computes the result for one record. Null arguments are passed through as `Field::Null`.
+    fn evaluate(&self, args: &[Field]) -> Result<Field, PipelineError>;
+}
+
+/// Functions registered in the process. The registry is process-wide: every pipeline built
+/// by the process sees the same functions.
+static UDF_REGISTRY: RwLock<Vec<Arc<dyn ScalarUdf>>> = const_rwlock(Vec::new());
+
+/// Registers `udf` for every pipeline of the process. Functions must be registered before
+/// the pipelines using them are built, and a name can only be registered once.
+pub fn register_udf(udf: Arc<dyn ScalarUdf>) -> Result<(), PipelineError> {
+    let mut registry = UDF_REGISTRY.write();
+    if registry
+        .iter()
+        .any(|existing| existing.name().eq_ignore_ascii_case(udf.name()))
+    {
+        return Err(PipelineError::UdfAlreadyRegistered(udf.name().to_string()));
+    }
+    registry.push(udf);
+    Ok(())
+}
+
+/// Returns the function registered under `name`, if any
+pub fn get_udf(name: &str) -> Option<UdfFunction> {
+    UDF_REGISTRY
+        .read()
+        .iter()
+        .find(|udf| udf.name().eq_ignore_ascii_case(name))
+        .map(|udf| UdfFunction(udf.clone()))
+}
+
+/// Handle on a registered function, held by the expressions calling it
+#[derive(Clone)]
+pub struct UdfFunction(Arc<dyn ScalarUdf>);
+
+impl UdfFunction {
+    pub fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    pub(crate) fn get_type(
+        &self,
+        args: &[Expression],
+        schema: &Schema,
+    ) -> Result<ExpressionType, PipelineError> {
+        let mut arg_types = Vec::with_capacity(args.len());
+        let mut nullable = false;
+        for arg in args {
+            let arg_type = arg.get_type(schema)?;
+            nullable |= arg_type.nullable;
+            arg_types.push(arg_type.return_type);
+        }
+        self.0.validate_args(&arg_types)?;
+        Ok(ExpressionType::new(
+            self.0.return_type(&arg_types),
+            nullable,
+            SourceDefinition::Dynamic,
+        ))
+    }
+
+    pub(crate) fn evaluate(
+        &self,
+        schema: &Schema,
+        args: &[Expression],
+        record: &Record,
+    ) -> Result<Field, PipelineError> {
+        let values = args
+            .iter()
+            .map(|arg| arg.evaluate(record, schema))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.0.evaluate(&values)
+    }
+}
+
+impl Debug for UdfFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("UdfFunction").field(&self.name()).finish()
+    }
+}
+
+impl PartialEq for UdfFunction {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(
+            Arc::as_ptr(&self.0) as *const (),
+            Arc::as_ptr(&other.0) as *const (),
+        )
+    }
+}
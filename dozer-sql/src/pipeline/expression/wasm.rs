@@ -0,0 +1,232 @@
+use std::fs::File;
+use std::sync::Arc;
+
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::parking_lot::Mutex;
+use dozer_types::types::{Field, FieldType};
+use wasmi::core::{ValueType, F32, F64};
+use wasmi::{Config, Engine, Func, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Value};
+
+use crate::pipeline::errors::{FieldTypes, PipelineError, WasmError};
+use crate::pipeline::expression::udf::{register_udf, ScalarUdf};
+
+/// Fuel available to a single call, bounding the number of instructions it can execute
+const FUEL_PER_CALL: u64 = 10_000_000;
+/// Maximum size of the linear memory of a module
+const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+
+struct WasmInstance {
+    store: Store<StoreLimits>,
+    func: Func,
+    fuel_added: u64,
+}
+
+impl WasmInstance {
+    /// Instantiates `module` in a fresh store and looks up `function`, returning the reason
+    /// of the failure otherwise
+    fn new(engine: &Engine, module: &Module, function: &str) -> Result<Self, String> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MAX_MEMORY_BYTES)
+            .build();
+        let mut store = Store::new(engine, limits);
+        store.limiter(|limits| limits);
+        store.add_fuel(FUEL_PER_CALL).map_err(|e| e.to_string())?;
+        let instance = Linker::new(engine)
+            .instantiate(&mut store, module)
+            .and_then(|instance| instance.start(&mut store))
+            .map_err(|e| e.to_string())?;
+        let func = instance
+            .get_func(&store, function)
+            .ok_or_else(|| format!("function {function} not found"))?;
+        Ok(Self {
+            store,
+            func,
+            fuel_added: FUEL_PER_CALL,
+        })
+    }
+}
+
+/// Scalar function exported by a WebAssembly module.
+///
+/// The module runs in its own sandbox: it cannot import host functions, and each call is
+/// bounded in fuel and memory, so that failures surface as errors instead of stopping the
+/// pipeline. Integer parameters and results map to `Int`, and float ones to `Float`.
+///
+/// All the calls go through a single instance of the module, one at a time. Its memory and
+/// globals persist from one call to the next, so functions should not rely on them. An
+/// instance which trapped may be left in an inconsistent state, and is replaced by a fresh
+/// one before the next call.
+pub struct WasmUdf {
+    name: String,
+    function: String,
+    params: Vec<ValueType>,
+    result: ValueType,
+    engine: Engine,
+    module: Module,
+    instance: Mutex<WasmInstance>,
+}
+
+impl WasmUdf {
+    /// Loads `function` from the module at `path`, to be called as `name` in SQL
+    pub fn new(name: &str, path: &str, function: &str) -> Result<Self, WasmError> {
+        let invalid_module = |reason: String| WasmError::InvalidModule(path.to_string(), reason);
+
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let file = File::open(path).map_err(|e| invalid_module(e.to_string()))?;
+        let module = Module::new(&engine, file).map_err(|e| invalid_module(e.to_string()))?;
+
+        let func_type = module
+            .get_export(function)
+            .and_then(|export| export.func().cloned())
+            .ok_or_else(|| WasmError::FunctionNotFound(function.to_string(), path.to_string()))?;
+        let result = match func_type.results() {
+            [result] => *result,
+            _ => return Err(WasmError::UnsupportedSignature(function.to_string())),
+        };
+        if func_type
+            .params()
+            .iter()
+            .chain([&result])
+            .any(|typ| get_field_type(*typ).is_none())
+        {
+            return Err(WasmError::UnsupportedSignature(function.to_string()));
+        }
+
+        let instance = WasmInstance::new(&engine, &module, function).map_err(invalid_module)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            function: function.to_string(),
+            params: func_type.params().to_vec(),
+            result,
+            engine,
+            module,
+            instance: Mutex::new(instance),
+        })
+    }
+}
+
+/// Returns the field type of a WASM value type, if it can be marshalled
+fn get_field_type(typ: ValueType) -> Option<FieldType> {
+    match typ {
+        ValueType::I32 | ValueType::I64 => Some(FieldType::Int),
+        ValueType::F32 | ValueType::F64 => Some(FieldType::Float),
+        ValueType::FuncRef | ValueType::ExternRef => None,
+    }
+}
+
+/// Returns the field types accepted for a parameter of type `typ`
+fn get_param_types(typ: ValueType) -> Vec<FieldType> {
+    match typ {
+        ValueType::I32 => vec![FieldType::Boolean, FieldType::Int, FieldType::UInt],
+        ValueType::I64 => vec![FieldType::Int, FieldType::UInt],
+        _ => vec![
+            FieldType::Decimal,
+            FieldType::Float,
+            FieldType::Int,
+            FieldType::UInt,
+        ],
+    }
+}
+
+fn to_wasm_value(field: &Field, typ: ValueType) -> Option<Value> {
+    match (typ, field) {
+        (ValueType::I32, Field::Boolean(b)) => Some(Value::I32(*b as i32)),
+        (ValueType::I32, _) => field
+            .to_int()
+            .and_then(|i| i32::try_from(i).ok())
+            .map(Value::I32),
+        (ValueType::I64, _) => field.to_int().map(Value::I64),
+        (ValueType::F32, _) => field.to_float().map(|f| Value::F32(F32::from(f as f32))),
+        (ValueType::F64, _) => field.to_float().map(|f| Value::F64(F64::from(f))),
+        _ => None,
+    }
+}
+
+fn to_field(value: &Value) -> Option<Field> {
+    match value {
+        Value::I32(i) => Some(Field::Int(*i as i64)),
+        Value::I64(i) => Some(Field::Int(*i)),
+        Value::F32(f) => Some(Field::Float(OrderedFloat(f.to_float() as f64))),
+        Value::F64(f) => Some(Field::Float(OrderedFloat(f.to_float()))),
+        Value::FuncRef(_) | Value::ExternRef(_) => None,
+    }
+}
+
+impl ScalarUdf for WasmUdf {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn validate_args(&self, args: &[FieldType]) -> Result<(), PipelineError> {
+        if args.len() < self.params.len() {
+            return Err(PipelineError::NotEnoughArguments(self.name.clone()));
+        }
+        if args.len() > self.params.len() {
+            return Err(PipelineError::TooManyArguments(self.name.clone()));
+        }
+        for (idx, (arg, param)) in args.iter().zip(&self.params).enumerate() {
+            let expected = get_param_types(*param);
+            if !expected.contains(arg) {
+                return Err(PipelineError::InvalidFunctionArgumentType(
+                    self.name.clone(),
+                    *arg,
+                    FieldTypes::new(expected),
+                    idx,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn return_type(&self, _args: &[FieldType]) -> FieldType {
+        get_field_type(self.result).unwrap_or(FieldType::Int)
+    }
+
+    fn evaluate(&self, args: &[Field]) -> Result<Field, PipelineError> {
+        if args.contains(&Field::Null) {
+            return Ok(Field::Null);
+        }
+        let mut inputs = Vec::with_capacity(args.len());
+        for (idx, (arg, param)) in args.iter().zip(&self.params).enumerate() {
+            inputs.push(to_wasm_value(arg, *param).ok_or_else(|| {
+                PipelineError::InvalidFunctionArgument(self.name.clone(), arg.clone(), idx)
+            })?);
+        }
+        let mut outputs = [Value::default(self.result)];
+
+        let mut instance = self.instance.lock();
+        let WasmInstance {
+            store,
+            func,
+            fuel_added,
+        } = &mut *instance;
+        // Refill the fuel consumed by the previous calls
+        let remaining = *fuel_added - store.fuel_consumed().unwrap_or_default();
+        let refill = FUEL_PER_CALL.saturating_sub(remaining);
+        let trap = |reason: String| WasmError::Trap(self.function.clone(), reason);
+        store.add_fuel(refill).map_err(|e| trap(e.to_string()))?;
+        *fuel_added += refill;
+
+        if let Err(e) = func.call(&mut *store, &inputs, &mut outputs) {
+            // The trap may have left the memory and globals of the instance half-updated
+            *instance =
+                WasmInstance::new(&self.engine, &self.module, &self.function).map_err(trap)?;
+            return Err(trap(e.to_string()).into());
+        }
+        to_field(&outputs[0])
+            .ok_or_else(|| WasmError::UnsupportedSignature(self.function.clone()).into())
+    }
+}
+
+/// Loads `function` from the WebAssembly module at `path` and registers it as `name`
+pub fn register_wasm_udf(
+    name: &str,
+    path: &str,
+    function: Option<&str>,
+) -> Result<(), PipelineError> {
+    let udf = WasmUdf::new(name, path, function.unwrap_or(name))?;
+    register_udf(Arc::new(udf))
+}
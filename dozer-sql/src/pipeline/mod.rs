@@ -1,9 +1,20 @@
 mod aggregation;
 pub mod builder;
+mod distinct;
+mod error_location;
 pub mod errors;
 mod expression;
 mod product;
 mod projection;
 mod selection;
+mod semi_join;
+mod sort_key;
+mod storage;
 #[cfg(test)]
 mod tests;
+mod top_n;
+mod union;
+mod window;
+
+pub use expression::udf::{register_udf, ScalarUdf};
+pub use expression::wasm::register_wasm_udf;
@@ -59,14 +59,26 @@ impl ProcessorFactory<SchemaSQLContext> for ProductProcessorFactory {
         input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
     ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
         let mut output_schema = Schema::empty();
+        // The output records are identified by the keys of all the inputs, unless an outer
+        // join can output records without some of them
+        let mut primary_index = Some(vec![]);
         let input_names = get_input_names(&self.input_tables);
         for (port, table) in input_names.iter().enumerate() {
             if let Some((current_schema, _)) = input_schemas.get(&(port as PortHandle)) {
-                output_schema = append_schema(output_schema, table, current_schema);
+                let nullable = is_nullable_table(&self.input_tables, port);
+                let offset = output_schema.fields.len();
+                primary_index = primary_index
+                    .filter(|_| !nullable && !current_schema.primary_index.is_empty())
+                    .map(|mut index| {
+                        index.extend(current_schema.primary_index.iter().map(|idx| idx + offset));
+                        index
+                    });
+                output_schema = append_schema(output_schema, table, current_schema, nullable);
             } else {
                 return Err(ExecutionError::InvalidPortHandle(port as PortHandle));
             }
         }
+        output_schema.primary_index = primary_index.unwrap_or_default();
 
         Ok((output_schema, SchemaSQLContext::default()))
     }
@@ -126,27 +138,26 @@ pub fn build_join_chain(
         let input_schema = extend_schema_source_def(input_schema, relation_name);
         let mut right_join_table = JoinTable::from(&input_schema);
 
-        let join_op = match &join.join_operator {
-            sqlparser::ast::JoinOperator::Inner(constraint) => match constraint {
-                JoinConstraint::On(expression) => {
-                    let (left_keys, right_keys) =
-                        parse_join_constraint(expression, &left_join_table, &right_join_table)?;
-
-                    JoinOperator::new(
-                        JoinOperatorType::Inner,
-                        (index + 1) as PortHandle,
-                        left_keys,
-                        (index) as PortHandle,
-                        right_keys,
-                    )
-                }
-                _ => {
-                    return Err(PipelineError::JoinError(
-                        JoinError::UnsupportedJoinConstraint,
-                    ))
-                }
-            },
-            _ => return Err(PipelineError::JoinError(JoinError::UnsupportedJoinType)),
+        let (join_type, constraint) = get_join_type(&join.join_operator)?;
+
+        let join_op = match constraint {
+            JoinConstraint::On(expression) => {
+                let (left_keys, right_keys) =
+                    parse_join_constraint(expression, &left_join_table, &right_join_table)?;
+
+                JoinOperator::new(
+                    join_type,
+                    (index + 1) as PortHandle,
+                    left_keys,
+                    (index) as PortHandle,
+                    right_keys,
+                )
+            }
+            _ => {
+                return Err(PipelineError::JoinError(
+                    JoinError::UnsupportedJoinConstraint,
+                ))
+            }
         };
 
         input_tables.get_mut(&(index as PortHandle)).unwrap().right = Some(join_op.clone());
@@ -164,6 +175,43 @@ pub fn build_join_chain(
     Ok(input_tables)
 }
 
+fn get_join_type(
+    join_operator: &sqlparser::ast::JoinOperator,
+) -> Result<(JoinOperatorType, &JoinConstraint), PipelineError> {
+    match join_operator {
+        sqlparser::ast::JoinOperator::Inner(constraint) => {
+            Ok((JoinOperatorType::Inner, constraint))
+        }
+        sqlparser::ast::JoinOperator::LeftOuter(constraint) => {
+            Ok((JoinOperatorType::LeftOuter, constraint))
+        }
+        sqlparser::ast::JoinOperator::RightOuter(constraint) => {
+            Ok((JoinOperatorType::RightOuter, constraint))
+        }
+        sqlparser::ast::JoinOperator::FullOuter(constraint) => {
+            Ok((JoinOperatorType::FullOuter, constraint))
+        }
+        _ => Err(PipelineError::JoinError(JoinError::UnsupportedJoinType)),
+    }
+}
+
+/// Returns true if the fields of the table on `port` can be padded with nulls by an outer join
+fn is_nullable_table(join_tables: &IndexedTabelWithJoins, port: usize) -> bool {
+    join_tables
+        .joins
+        .iter()
+        .enumerate()
+        .any(
+            |(index, (_, join))| match get_join_type(&join.join_operator) {
+                // the left side of the join covers all the tables up to `index`
+                Ok((JoinOperatorType::RightOuter, _)) => port <= index,
+                Ok((JoinOperatorType::LeftOuter, _)) => port == index + 1,
+                Ok((JoinOperatorType::FullOuter, _)) => port <= index + 1,
+                _ => false,
+            },
+        )
+}
+
 fn parse_join_constraint(
     expression: &sqlparser::ast::Expr,
     left_join_table: &JoinTable,
@@ -286,8 +334,10 @@ fn append_schema(
     mut output_schema: Schema,
     table: &NameOrAlias,
     current_schema: &Schema,
+    nullable: bool,
 ) -> Schema {
     for mut field in current_schema.clone().fields.into_iter() {
+        field.nullable |= nullable;
         if let Some(alias) = &table.1 {
             field.source = SourceDefinition::Alias {
                 name: alias.to_string(),
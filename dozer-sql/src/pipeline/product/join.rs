@@ -31,9 +31,9 @@ impl JoinTable {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum JoinOperatorType {
     Inner,
-    // LeftOuter,
-    // RightOuter,
-    // FullOuter,
+    LeftOuter,
+    RightOuter,
+    FullOuter,
     // CrossJoin,
     // CrossApply,
     // OuterApply,
@@ -96,7 +96,7 @@ pub trait JoinExecutor: Send + Sync {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct JoinOperator {
     /// Type of the Join operation
-    _operator: JoinOperatorType,
+    operator: JoinOperatorType,
 
     /// relation on the right side of the JOIN
     pub right_table: PortHandle,
@@ -116,14 +116,14 @@ pub struct JoinOperator {
 
 impl JoinOperator {
     pub fn new(
-        _operator: JoinOperatorType,
+        operator: JoinOperatorType,
         right_table: PortHandle,
         left_join_key_indexes: Vec<usize>,
         left_table: PortHandle,
         right_join_key_indexes: Vec<usize>,
     ) -> Self {
         Self {
-            _operator,
+            operator,
             right_table,
             left_join_key_indexes,
             left_table,
@@ -132,6 +132,44 @@ impl JoinOperator {
         }
     }
 
+    /// Returns true if the records of the left table are kept, padded with nulls,
+    /// when they have no match on the right side of the join
+    pub fn preserves_left(&self) -> bool {
+        matches!(
+            self.operator,
+            JoinOperatorType::LeftOuter | JoinOperatorType::FullOuter
+        )
+    }
+
+    /// Returns true if the records of the right table are kept, padded with nulls,
+    /// when they have no match on the left side of the join
+    pub fn preserves_right(&self) -> bool {
+        matches!(
+            self.operator,
+            JoinOperatorType::RightOuter | JoinOperatorType::FullOuter
+        )
+    }
+
+    /// Returns the number of records of the left table indexed with `join_key`
+    pub fn get_left_join_count(
+        &self,
+        join_key: &[u8],
+        db: &Database,
+        transaction: &SharedTransaction,
+    ) -> Result<usize, ExecutionError> {
+        Ok(self.get_left_lookup_keys(join_key, db, transaction)?.len())
+    }
+
+    /// Returns the number of records of the right table indexed with `join_key`
+    pub fn get_right_join_count(
+        &self,
+        join_key: &[u8],
+        db: &Database,
+        transaction: &SharedTransaction,
+    ) -> Result<usize, ExecutionError> {
+        Ok(self.get_right_lookup_keys(join_key, db, transaction)?.len())
+    }
+
     pub fn get_left_record_join_key(&self, record: &Record) -> Result<Vec<u8>, TypeError> {
         get_composite_key(record, self.left_join_key_indexes.as_slice())
     }
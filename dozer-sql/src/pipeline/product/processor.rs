@@ -8,12 +8,18 @@ use dozer_core::dag::record_store::RecordReader;
 use dozer_core::storage::common::Database;
 use dozer_core::storage::lmdb_storage::{LmdbEnvironmentManager, SharedTransaction};
 use dozer_types::internal_err;
-use dozer_types::types::{Operation, Record};
+use dozer_types::types::{Field, Operation, Record};
 use std::collections::HashMap;
 
 use dozer_core::dag::errors::ExecutionError::InternalError;
 
-use super::join::{get_lookup_key, JoinExecutor, JoinTable};
+use super::join::{get_lookup_key, JoinExecutor, JoinOperator, JoinTable};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JoinAction {
+    Insert,
+    Delete,
+}
 
 /// Cartesian Product Processor
 #[derive(Debug)]
@@ -40,34 +46,69 @@ impl ProductProcessor {
         Ok(())
     }
 
-    fn delete(
-        &self,
-        from_port: PortHandle,
-        record: &Record,
-        transaction: &SharedTransaction,
-        reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
-    ) -> Result<Vec<Record>, ExecutionError> {
-        // Get the input Table based on the port of the incoming message
-        if let Some(input_table) = self.join_tables.get(&from_port) {
-            let mut records = vec![record.clone()];
+    fn get_join_table(&self, port: PortHandle) -> Result<&JoinTable, ExecutionError> {
+        self.join_tables
+            .get(&port)
+            .ok_or(ExecutionError::JoinError(JoinError::InsertPortError(port)))
+    }
+
+    /// Returns the number of fields of the tables between `from` and `to` (inclusive)
+    fn get_width(&self, from: PortHandle, to: PortHandle) -> Result<usize, ExecutionError> {
+        let mut width = 0;
+        for port in from..=to {
+            width += self.get_join_table(port)?.schema.fields.len();
+        }
+        Ok(width)
+    }
 
-            let database = &self.db.ok_or(ExecutionError::InvalidDatabase)?;
+    fn get_last_port(&self) -> PortHandle {
+        self.join_tables.keys().max().copied().unwrap_or_default()
+    }
 
-            let mut input_left_join = &input_table.left;
+    /// Returns true if a record of the left table of `join` with no match survives,
+    /// padded with nulls, through this join and all the following ones
+    fn is_left_preserved(&self, join: &JoinOperator) -> Result<bool, ExecutionError> {
+        let mut next_join = Some(join);
+        while let Some(join) = next_join {
+            if !join.preserves_left() {
+                return Ok(false);
+            }
+            next_join = self.get_join_table(join.right_table)?.right.as_ref();
+        }
+        Ok(true)
+    }
 
-            if let Some(left_join) = input_left_join {
-                // generate the key with the fields of the left table used in the join contstraint
-                let join_key: Vec<u8> = left_join.get_right_record_join_key(record)?;
-                // generate the key with theprimary key fields of the left table
-                let lookup_key: Vec<u8> = get_lookup_key(record, &input_table.schema)?;
-                // Update the Join index
-                left_join.delete_right_index(&join_key, &lookup_key, database, transaction)?;
+    /// Returns true if a record of the right table of `join` with no match survives,
+    /// padded with nulls, through this join and all the previous ones
+    fn is_right_preserved(&self, join: &JoinOperator) -> Result<bool, ExecutionError> {
+        let mut next_join = Some(join);
+        while let Some(join) = next_join {
+            if !join.preserves_right() {
+                return Ok(false);
             }
+            next_join = self.get_join_table(join.left_table)?.left.as_ref();
+        }
+        Ok(true)
+    }
 
-            while let Some(left_join) = input_left_join {
-                let join_key: Vec<u8> = left_join.get_right_record_join_key(record)?;
-                records = left_join.execute_left(
-                    records,
+    /// Joins the records with the tables on the left side of `left_join`.
+    /// The leading fields of each record belong to the right table of `left_join`.
+    fn join_left<'a>(
+        &'a self,
+        mut records: Vec<Record>,
+        mut left_join: Option<&'a JoinOperator>,
+        database: &Database,
+        transaction: &SharedTransaction,
+        reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
+    ) -> Result<Vec<Record>, ExecutionError> {
+        let mut padded_records = vec![];
+
+        while let Some(join) = left_join {
+            let mut joined_records = vec![];
+            for record in records.into_iter() {
+                let join_key: Vec<u8> = join.get_right_record_join_key(&record)?;
+                let joined = join.execute_left(
+                    vec![record.clone()],
                     &join_key,
                     database,
                     transaction,
@@ -75,25 +116,43 @@ impl ProductProcessor {
                     &self.join_tables,
                 )?;
 
-                let next_table = self
-                    .join_tables
-                    .get(&left_join.left_table)
-                    .ok_or(ExecutionError::InvalidPortHandle(left_join.left_table))?;
-                input_left_join = &next_table.left;
+                if !joined.is_empty() {
+                    joined_records.extend(joined);
+                } else if self.is_right_preserved(join)? {
+                    let width = self.get_width(0, join.left_table)?;
+                    padded_records.push(pad_left(record, width));
+                }
             }
 
-            let mut input_right_join = &input_table.right;
+            records = joined_records;
+            left_join = self.get_join_table(join.left_table)?.left.as_ref();
+        }
 
-            while let Some(right_join) = input_right_join {
-                // generate the key with the fields of the left table used in the join contstraint
-                let join_key: Vec<u8> = right_join.get_left_record_join_key(record)?;
-                // generate the key with theprimary key fields of the left table
-                let lookup_key: Vec<u8> = get_lookup_key(record, &input_table.schema)?;
-                // Update the Join index
-                right_join.delete_left_index(&join_key, &lookup_key, database, transaction)?;
+        records.extend(padded_records);
+        Ok(records)
+    }
 
-                records = right_join.execute_right(
-                    records,
+    /// Joins the records with the tables on the right side of `right_join`.
+    /// The trailing fields of each record belong to the left table of `right_join`.
+    fn join_right<'a>(
+        &'a self,
+        mut records: Vec<Record>,
+        mut right_join: Option<&'a JoinOperator>,
+        database: &Database,
+        transaction: &SharedTransaction,
+        reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
+    ) -> Result<Vec<Record>, ExecutionError> {
+        let mut padded_records = vec![];
+
+        while let Some(join) = right_join {
+            let left_width = self.get_width(join.left_table, join.left_table)?;
+            let mut joined_records = vec![];
+            for record in records.into_iter() {
+                let offset = record.values.len() - left_width;
+                let left_record = Record::new(None, record.values[offset..].to_vec(), None);
+                let join_key: Vec<u8> = join.get_left_record_join_key(&left_record)?;
+                let joined = join.execute_right(
+                    vec![record.clone()],
                     &join_key,
                     database,
                     transaction,
@@ -101,199 +160,212 @@ impl ProductProcessor {
                     &self.join_tables,
                 )?;
 
-                let next_table = self
-                    .join_tables
-                    .get(&right_join.right_table)
-                    .ok_or(ExecutionError::InvalidPortHandle(right_join.left_table))?;
-                input_right_join = &next_table.right;
+                if !joined.is_empty() {
+                    joined_records.extend(joined);
+                } else if self.is_left_preserved(join)? {
+                    let width = self.get_width(join.right_table, self.get_last_port())?;
+                    padded_records.push(pad_right(record, width));
+                }
             }
 
-            return Ok(records);
+            records = joined_records;
+            right_join = self.get_join_table(join.right_table)?.right.as_ref();
         }
 
-        Err(ExecutionError::InvalidPortHandle(from_port))
+        records.extend(padded_records);
+        Ok(records)
     }
 
-    fn insert(
+    fn update_index(
         &self,
-        from_port: PortHandle,
+        input_table: &JoinTable,
         record: &Record,
+        action: JoinAction,
+        database: &Database,
         transaction: &SharedTransaction,
-        reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
-    ) -> Result<Vec<Record>, ExecutionError> {
-        // Get the input Table based on the port of the incoming message
-
-        if let Some(input_table) = self.join_tables.get(&from_port) {
-            let mut records = vec![record.clone()];
-
-            let database = &self.db.ok_or(ExecutionError::InvalidDatabase)?;
-
-            let mut input_left_join = &input_table.left;
+    ) -> Result<(), ExecutionError> {
+        // generate the key with the primary key fields of the input table
+        let lookup_key: Vec<u8> = get_lookup_key(record, &input_table.schema)?;
+
+        if let Some(left_join) = &input_table.left {
+            // generate the key with the fields of the input table used in the join constraint
+            let join_key: Vec<u8> = left_join.get_right_record_join_key(record)?;
+            match action {
+                JoinAction::Insert => {
+                    left_join.insert_right_index(&join_key, &lookup_key, database, transaction)?
+                }
+                JoinAction::Delete => {
+                    left_join.delete_right_index(&join_key, &lookup_key, database, transaction)?
+                }
+            }
+        }
 
-            // Update the Join index
-            if let Some(left_join) = input_left_join {
-                // generate the key with the fields of the left table used in the join contstraint
-                let join_key: Vec<u8> = left_join.get_right_record_join_key(record)?;
-                // generate the key with theprimary key fields of the left table
-                let lookup_key: Vec<u8> = get_lookup_key(record, &input_table.schema)?;
-                // Update the Join index
-                left_join.insert_right_index(&join_key, &lookup_key, database, transaction)?;
+        if let Some(right_join) = &input_table.right {
+            // generate the key with the fields of the input table used in the join constraint
+            let join_key: Vec<u8> = right_join.get_left_record_join_key(record)?;
+            match action {
+                JoinAction::Insert => {
+                    right_join.insert_left_index(&join_key, &lookup_key, database, transaction)?
+                }
+                JoinAction::Delete => {
+                    right_join.delete_left_index(&join_key, &lookup_key, database, transaction)?
+                }
             }
+        }
+
+        Ok(())
+    }
 
-            while let Some(left_join) = input_left_join {
-                let join_key: Vec<u8> = left_join.get_right_record_join_key(record)?;
-                records = left_join.execute_left(
-                    records,
+    /// Returns the null-padded results of outer joins that change because `record`
+    /// is the first match of the records on the other side (insert) or was the last one (delete)
+    fn get_dangling_records(
+        &self,
+        input_table: &JoinTable,
+        record: &Record,
+        action: JoinAction,
+        database: &Database,
+        transaction: &SharedTransaction,
+        reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
+    ) -> Result<Vec<Record>, ExecutionError> {
+        let mut dangling_records = vec![];
+        let is_changed = |count: usize| match action {
+            JoinAction::Insert => count == 1,
+            JoinAction::Delete => count == 0,
+        };
+
+        if let Some(left_join) = &input_table.left {
+            let join_key: Vec<u8> = left_join.get_right_record_join_key(record)?;
+            if self.is_left_preserved(left_join)?
+                && is_changed(left_join.get_right_join_count(&join_key, database, transaction)?)
+            {
+                let left_records = left_join.execute_left(
+                    vec![Record::new(None, vec![], None)],
                     &join_key,
                     database,
                     transaction,
                     reader,
                     &self.join_tables,
                 )?;
-
-                let next_table = self.join_tables.get(&left_join.left_table).ok_or(
-                    ExecutionError::JoinError(JoinError::InsertPortError(left_join.left_table)),
+                let left_records = self.join_left(
+                    left_records,
+                    self.get_join_table(left_join.left_table)?.left.as_ref(),
+                    database,
+                    transaction,
+                    reader,
                 )?;
-                input_left_join = &next_table.left;
-            }
-
-            let mut input_right_join = &input_table.right;
 
-            while let Some(right_join) = input_right_join {
-                // generate the key with the fields of the left table used in the join contstraint
-                let join_key: Vec<u8> = right_join.get_left_record_join_key(record)?;
-                // generate the key with theprimary key fields of the left table
-                let lookup_key: Vec<u8> = get_lookup_key(record, &input_table.schema)?;
-                // Update the Join index
-                right_join.insert_left_index(&join_key, &lookup_key, database, transaction)?;
+                let width = self.get_width(left_join.right_table, self.get_last_port())?;
+                dangling_records.extend(
+                    left_records
+                        .into_iter()
+                        .map(|left_record| pad_right(left_record, width)),
+                );
+            }
+        }
 
-                records = right_join.execute_right(
-                    records,
+        if let Some(right_join) = &input_table.right {
+            let join_key: Vec<u8> = right_join.get_left_record_join_key(record)?;
+            if self.is_right_preserved(right_join)?
+                && is_changed(right_join.get_left_join_count(&join_key, database, transaction)?)
+            {
+                let right_records = right_join.execute_right(
+                    vec![Record::new(None, vec![], None)],
                     &join_key,
                     database,
                     transaction,
                     reader,
                     &self.join_tables,
                 )?;
-
-                let next_table = self.join_tables.get(&right_join.right_table).ok_or(
-                    ExecutionError::JoinError(JoinError::InsertPortError(right_join.left_table)),
+                let right_records = self.join_right(
+                    right_records,
+                    self.get_join_table(right_join.right_table)?.right.as_ref(),
+                    database,
+                    transaction,
+                    reader,
                 )?;
-                input_right_join = &next_table.right;
-            }
 
-            return Ok(records);
+                let width = self.get_width(0, right_join.left_table)?;
+                dangling_records.extend(
+                    right_records
+                        .into_iter()
+                        .map(|right_record| pad_left(right_record, width)),
+                );
+            }
         }
 
-        Err(ExecutionError::JoinError(JoinError::PortNotConnected(
-            from_port,
-        )))
+        Ok(dangling_records)
     }
 
-    fn update(
+    fn execute(
         &self,
         from_port: PortHandle,
-        old: &Record,
-        new: &Record,
+        record: &Record,
+        action: JoinAction,
         transaction: &SharedTransaction,
         reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
-    ) -> Result<(Vec<Record>, Vec<Record>), ExecutionError> {
+    ) -> Result<Vec<Operation>, ExecutionError> {
+        // Get the input Table based on the port of the incoming message
         let input_table = self
             .join_tables
             .get(&from_port)
-            .ok_or(ExecutionError::InvalidPortHandle(from_port))?;
+            .ok_or(ExecutionError::JoinError(JoinError::PortNotConnected(
+                from_port,
+            )))?;
 
         let database = &self.db.ok_or(ExecutionError::InvalidDatabase)?;
 
-        let mut old_records = vec![old.clone()];
-        let mut new_records = vec![new.clone()];
-
-        let mut input_left_join = &input_table.left;
-
-        if let Some(left_join) = input_left_join {
-            let old_join_key: Vec<u8> = left_join.get_right_record_join_key(old)?;
-            let old_lookup_key: Vec<u8> = get_lookup_key(old, &input_table.schema)?;
-            let new_join_key: Vec<u8> = left_join.get_right_record_join_key(new)?;
-            let new_lookup_key: Vec<u8> = get_lookup_key(new, &input_table.schema)?;
-
-            // Update the Join index
-            left_join.delete_right_index(&old_join_key, &old_lookup_key, database, transaction)?;
-            left_join.insert_right_index(&new_join_key, &new_lookup_key, database, transaction)?;
-        }
-
-        while let Some(left_join) = input_left_join {
-            let old_join_key: Vec<u8> = left_join.get_right_record_join_key(old)?;
-            old_records = left_join.execute_left(
-                old_records,
-                &old_join_key,
-                database,
-                transaction,
-                reader,
-                &self.join_tables,
-            )?;
-
-            let new_join_key: Vec<u8> = left_join.get_right_record_join_key(new)?;
-            new_records = left_join.execute_left(
-                new_records,
-                &new_join_key,
-                database,
-                transaction,
-                reader,
-                &self.join_tables,
-            )?;
-
-            let next_table = self
-                .join_tables
-                .get(&left_join.left_table)
-                .ok_or(ExecutionError::InvalidPortHandle(left_join.left_table))?;
-            input_left_join = &next_table.left;
-        }
-
-        let mut input_right_join = &input_table.right;
-
-        if let Some(right_join) = input_right_join {
-            let old_join_key: Vec<u8> = right_join.get_left_record_join_key(old)?;
-            let old_lookup_key: Vec<u8> = get_lookup_key(old, &input_table.schema)?;
-            let new_join_key: Vec<u8> = right_join.get_left_record_join_key(new)?;
-            let new_lookup_key: Vec<u8> = get_lookup_key(new, &input_table.schema)?;
-
-            // Update the Join index
-            right_join.delete_left_index(&old_join_key, &old_lookup_key, database, transaction)?;
-            right_join.insert_left_index(&new_join_key, &new_lookup_key, database, transaction)?;
-        }
+        // Update the Join indexes
+        self.update_index(input_table, record, action, database, transaction)?;
+
+        let records = self.join_left(
+            vec![record.clone()],
+            input_table.left.as_ref(),
+            database,
+            transaction,
+            reader,
+        )?;
+        let records = self.join_right(
+            records,
+            input_table.right.as_ref(),
+            database,
+            transaction,
+            reader,
+        )?;
+
+        let dangling_records =
+            self.get_dangling_records(input_table, record, action, database, transaction, reader)?;
+
+        let operations = match action {
+            JoinAction::Insert => dangling_records
+                .into_iter()
+                .map(|old| Operation::Delete { old })
+                .chain(records.into_iter().map(|new| Operation::Insert { new }))
+                .collect(),
+            JoinAction::Delete => records
+                .into_iter()
+                .map(|old| Operation::Delete { old })
+                .chain(
+                    dangling_records
+                        .into_iter()
+                        .map(|new| Operation::Insert { new }),
+                )
+                .collect(),
+        };
+
+        Ok(operations)
+    }
+}
 
-        while let Some(right_join) = input_right_join {
-            let old_join_key: Vec<u8> = right_join.get_left_record_join_key(old)?;
-
-            old_records = right_join.execute_right(
-                old_records,
-                &old_join_key,
-                database,
-                transaction,
-                reader,
-                &self.join_tables,
-            )?;
-
-            let new_join_key: Vec<u8> = right_join.get_left_record_join_key(new)?;
-
-            new_records = right_join.execute_right(
-                new_records,
-                &new_join_key,
-                database,
-                transaction,
-                reader,
-                &self.join_tables,
-            )?;
-
-            let next_table = self
-                .join_tables
-                .get(&right_join.right_table)
-                .ok_or(ExecutionError::InvalidPortHandle(right_join.left_table))?;
-            input_right_join = &next_table.right;
-        }
+fn pad_left(record: Record, width: usize) -> Record {
+    let mut values = vec![Field::Null; width];
+    values.extend(record.values);
+    Record::new(None, values, None)
+}
 
-        Ok((old_records, new_records))
-    }
+fn pad_right(record: Record, width: usize) -> Record {
+    let mut values = record.values;
+    values.extend(vec![Field::Null; width]);
+    Record::new(None, values, None)
 }
 
 impl Processor for ProductProcessor {
@@ -313,33 +385,29 @@ impl Processor for ProductProcessor {
         transaction: &SharedTransaction,
         reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
     ) -> Result<(), ExecutionError> {
-        match op {
+        let operations = match op {
             Operation::Delete { ref old } => {
-                let records = self.delete(from_port, old, transaction, reader)?;
-
-                for record in records.into_iter() {
-                    let _ = fw.send(Operation::Delete { old: record }, DEFAULT_PORT_HANDLE);
-                }
+                self.execute(from_port, old, JoinAction::Delete, transaction, reader)?
             }
             Operation::Insert { ref new } => {
-                let records = self.insert(from_port, new, transaction, reader)?;
-
-                for record in records.into_iter() {
-                    let _ = fw.send(Operation::Insert { new: record }, DEFAULT_PORT_HANDLE);
-                }
+                self.execute(from_port, new, JoinAction::Insert, transaction, reader)?
             }
             Operation::Update { ref old, ref new } => {
-                let (old_join_records, new_join_records) =
-                    self.update(from_port, old, new, transaction, reader)?;
-
-                for old in old_join_records.into_iter() {
-                    let _ = fw.send(Operation::Delete { old }, DEFAULT_PORT_HANDLE);
-                }
-
-                for new in new_join_records.into_iter() {
-                    let _ = fw.send(Operation::Insert { new }, DEFAULT_PORT_HANDLE);
-                }
+                let mut operations =
+                    self.execute(from_port, old, JoinAction::Delete, transaction, reader)?;
+                operations.extend(self.execute(
+                    from_port,
+                    new,
+                    JoinAction::Insert,
+                    transaction,
+                    reader,
+                )?);
+                operations
             }
+        };
+
+        for operation in operations.into_iter() {
+            let _ = fw.send(operation, DEFAULT_PORT_HANDLE);
         }
         Ok(())
     }
@@ -2,3 +2,5 @@
 mod factory_tests;
 #[cfg(test)]
 mod pipeline_test;
+#[cfg(test)]
+mod processor_tests;
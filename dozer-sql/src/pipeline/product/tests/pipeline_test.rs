@@ -2,6 +2,7 @@ use dozer_core::dag::app::App;
 use dozer_core::dag::appsource::{AppSource, AppSourceManager};
 use dozer_core::dag::channels::SourceChannelForwarder;
 use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
+use dozer_core::dag::dag_schemas::DagSchemaManager;
 use dozer_core::dag::epoch::Epoch;
 use dozer_core::dag::errors::ExecutionError;
 use dozer_core::dag::executor::{DagExecutor, ExecutorOptions};
@@ -502,3 +503,68 @@ fn test_pipeline_builder() {
     let elapsed = now.elapsed();
     debug!("Elapsed: {:.2?}", elapsed);
 }
+
+#[test]
+fn test_pipeline_primary_key() {
+    let queries = [
+        (
+            "SELECT u.id, d.id, u.name \
+            FROM user u JOIN department d ON u.department_id = d.id",
+            vec![0, 1],
+        ),
+        (
+            "SELECT u.name, d.id FROM user u JOIN department d \
+            ON u.department_id = d.id",
+            vec![],
+        ),
+        (
+            "SELECT u.id, d.id FROM user u LEFT JOIN department d \
+            ON u.department_id = d.id",
+            vec![],
+        ),
+        (
+            "SELECT SUM(u.salary), d.name \
+            FROM user u JOIN department d ON u.department_id = d.id \
+            GROUP BY d.name",
+            vec![1],
+        ),
+        (
+            "SELECT department_id, COUNT(id) FROM user \
+            GROUP BY department_id, name",
+            vec![],
+        ),
+        ("SELECT name, id FROM user WHERE salary > 1", vec![1]),
+    ];
+
+    for (sql, primary_index) in queries {
+        let (mut pipeline, (node, port)) = statement_to_pipeline(sql).unwrap();
+
+        let latch = Arc::new(AtomicBool::new(true));
+        let mut asm = AppSourceManager::new();
+        asm.add(AppSource::new(
+            "conn1".to_string(),
+            Arc::new(TestSourceFactory::new(latch.clone())),
+            vec![
+                ("user".to_string(), USER_PORT),
+                ("department".to_string(), DEPARTMENT_PORT),
+            ]
+            .into_iter()
+            .collect(),
+        ))
+        .unwrap();
+
+        pipeline.add_sink(Arc::new(TestSinkFactory::new(0, latch)), "sink");
+        pipeline
+            .connect_nodes(&node, Some(port), "sink", Some(DEFAULT_PORT_HANDLE))
+            .unwrap();
+
+        let mut app = App::new(asm);
+        app.add_pipeline(pipeline);
+        let dag = app.get_dag().unwrap();
+
+        let schemas = DagSchemaManager::new(&dag).unwrap();
+        let (sink, _) = &dag.get_sinks()[0];
+        let (schema, _) = &schemas.get_node_input_schemas(sink).unwrap()[&DEFAULT_PORT_HANDLE];
+        assert_eq!(schema.primary_index, primary_index, "{sql}");
+    }
+}
@@ -0,0 +1,434 @@
+use dozer_core::dag::channels::ProcessorChannelForwarder;
+use dozer_core::dag::errors::ExecutionError;
+use dozer_core::dag::node::{PortHandle, Processor, ProcessorFactory};
+use dozer_core::dag::record_store::RecordReader;
+use dozer_core::storage::lmdb_storage::{LmdbEnvironmentManager, SharedTransaction};
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+use std::collections::HashMap;
+use tempdir::TempDir;
+
+use crate::pipeline::builder::{get_input_tables, QueryContext};
+use crate::pipeline::product::factory::ProductProcessorFactory;
+use crate::pipeline::tests::utils::get_select;
+use dozer_core::dag::app::AppPipeline;
+
+const USER_PORT: PortHandle = 0;
+const DEPARTMENT_PORT: PortHandle = 1;
+
+struct TestChannelForwarder {
+    operations: Vec<Operation>,
+}
+
+impl ProcessorChannelForwarder for TestChannelForwarder {
+    fn send(&mut self, op: Operation, _port: PortHandle) -> Result<(), ExecutionError> {
+        self.operations.push(op);
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct TestRecordReader {
+    records: HashMap<Vec<u8>, Record>,
+}
+
+impl RecordReader for TestRecordReader {
+    fn get(&self, key: &[u8], _version: u32) -> Result<Option<Record>, ExecutionError> {
+        Ok(self.records.get(key).cloned())
+    }
+}
+
+struct TestJoin {
+    processor: Box<dyn Processor>,
+    tx: SharedTransaction,
+    readers: HashMap<PortHandle, TestRecordReader>,
+    _tmp_dir: TempDir,
+}
+
+impl TestJoin {
+    fn new(sql: &str) -> Self {
+        let select = get_select(sql).unwrap();
+        let input_tables = get_input_tables(
+            &select.from[0],
+            &mut AppPipeline::new(),
+            &mut QueryContext::default(),
+        )
+        .unwrap();
+
+        let processor_factory = ProductProcessorFactory::new(input_tables);
+        let mut processor = processor_factory
+            .build(
+                HashMap::from([
+                    (USER_PORT, user_schema()),
+                    (DEPARTMENT_PORT, department_schema()),
+                ]),
+                HashMap::new(),
+            )
+            .unwrap();
+
+        let tmp_dir = TempDir::new("product").unwrap();
+        let mut storage = LmdbEnvironmentManager::create(tmp_dir.path(), "product_test")
+            .unwrap_or_else(|e| panic!("{}", e.to_string()));
+        processor
+            .init(&mut storage)
+            .unwrap_or_else(|e| panic!("{}", e.to_string()));
+        let tx = storage.create_txn().unwrap();
+
+        Self {
+            processor,
+            tx,
+            readers: HashMap::from([
+                (
+                    USER_PORT,
+                    TestRecordReader {
+                        records: HashMap::new(),
+                    },
+                ),
+                (
+                    DEPARTMENT_PORT,
+                    TestRecordReader {
+                        records: HashMap::new(),
+                    },
+                ),
+            ]),
+            _tmp_dir: tmp_dir,
+        }
+    }
+
+    fn process(&mut self, port: PortHandle, op: Operation) -> Vec<Operation> {
+        let records = &mut self.readers.get_mut(&port).unwrap().records;
+        match &op {
+            Operation::Insert { new } => {
+                records.insert(new.get_key(&vec![0]), new.clone());
+            }
+            Operation::Delete { old } => {
+                records.remove(&old.get_key(&vec![0]));
+            }
+            Operation::Update { old, new } => {
+                records.remove(&old.get_key(&vec![0]));
+                records.insert(new.get_key(&vec![0]), new.clone());
+            }
+        }
+
+        let readers: HashMap<PortHandle, Box<dyn RecordReader>> = self
+            .readers
+            .iter()
+            .map(|(port, reader)| (*port, Box::new(reader.clone()) as Box<dyn RecordReader>))
+            .collect();
+
+        let mut fw = TestChannelForwarder { operations: vec![] };
+        self.processor
+            .process(port, op, &mut fw, &self.tx, &readers)
+            .unwrap();
+        fw.operations
+    }
+}
+
+fn user_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("id"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Table {
+                    connection: String::from("test"),
+                    name: String::from("user"),
+                },
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("department_id"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Table {
+                    connection: String::from("test"),
+                    name: String::from("user"),
+                },
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn department_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("id"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Table {
+                    connection: String::from("test"),
+                    name: String::from("department"),
+                },
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("name"),
+                FieldType::String,
+                false,
+                SourceDefinition::Table {
+                    connection: String::from("test"),
+                    name: String::from("department"),
+                },
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn user(id: i64, department_id: i64) -> Record {
+    Record::new(None, vec![Field::Int(id), Field::Int(department_id)], None)
+}
+
+fn department(id: i64, name: &str) -> Record {
+    Record::new(
+        None,
+        vec![Field::Int(id), Field::String(name.to_string())],
+        None,
+    )
+}
+
+fn joined(user: Option<(i64, i64)>, department: Option<(i64, &str)>) -> Record {
+    let mut values = match user {
+        Some((id, department_id)) => vec![Field::Int(id), Field::Int(department_id)],
+        None => vec![Field::Null, Field::Null],
+    };
+    match department {
+        Some((id, name)) => values.extend([Field::Int(id), Field::String(name.to_string())]),
+        None => values.extend([Field::Null, Field::Null]),
+    }
+    Record::new(None, values, None)
+}
+
+#[test]
+fn test_inner_join() {
+    let mut join =
+        TestJoin::new("SELECT * FROM user JOIN department ON user.department_id = department.id");
+
+    let out = join.process(USER_PORT, Operation::Insert { new: user(1, 10) });
+    assert_eq!(out, vec![]);
+
+    let out = join.process(
+        DEPARTMENT_PORT,
+        Operation::Insert {
+            new: department(10, "IT"),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: joined(Some((1, 10)), Some((10, "IT")))
+        }]
+    );
+
+    let out = join.process(USER_PORT, Operation::Delete { old: user(1, 10) });
+    assert_eq!(
+        out,
+        vec![Operation::Delete {
+            old: joined(Some((1, 10)), Some((10, "IT")))
+        }]
+    );
+}
+
+#[test]
+fn test_left_outer_join() {
+    let mut join = TestJoin::new(
+        "SELECT * FROM user LEFT JOIN department ON user.department_id = department.id",
+    );
+
+    // a user without department is padded with nulls
+    let out = join.process(USER_PORT, Operation::Insert { new: user(1, 10) });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: joined(Some((1, 10)), None)
+        }]
+    );
+
+    // a department without users is not part of the result
+    let out = join.process(
+        DEPARTMENT_PORT,
+        Operation::Insert {
+            new: department(20, "HR"),
+        },
+    );
+    assert_eq!(out, vec![]);
+
+    // the first matching department retracts the null-padded record
+    let out = join.process(
+        DEPARTMENT_PORT,
+        Operation::Insert {
+            new: department(10, "IT"),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![
+            Operation::Delete {
+                old: joined(Some((1, 10)), None)
+            },
+            Operation::Insert {
+                new: joined(Some((1, 10)), Some((10, "IT")))
+            },
+        ]
+    );
+
+    // moving the user to another department
+    let out = join.process(
+        USER_PORT,
+        Operation::Update {
+            old: user(1, 10),
+            new: user(1, 20),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![
+            Operation::Delete {
+                old: joined(Some((1, 10)), Some((10, "IT")))
+            },
+            Operation::Insert {
+                new: joined(Some((1, 20)), Some((20, "HR")))
+            },
+        ]
+    );
+
+    // the last matching department leaving brings back the null-padded record
+    let out = join.process(
+        DEPARTMENT_PORT,
+        Operation::Delete {
+            old: department(20, "HR"),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![
+            Operation::Delete {
+                old: joined(Some((1, 20)), Some((20, "HR")))
+            },
+            Operation::Insert {
+                new: joined(Some((1, 20)), None)
+            },
+        ]
+    );
+
+    let out = join.process(USER_PORT, Operation::Delete { old: user(1, 20) });
+    assert_eq!(
+        out,
+        vec![Operation::Delete {
+            old: joined(Some((1, 20)), None)
+        }]
+    );
+}
+
+#[test]
+fn test_right_outer_join() {
+    let mut join = TestJoin::new(
+        "SELECT * FROM user RIGHT JOIN department ON user.department_id = department.id",
+    );
+
+    let out = join.process(USER_PORT, Operation::Insert { new: user(1, 10) });
+    assert_eq!(out, vec![]);
+
+    let out = join.process(
+        DEPARTMENT_PORT,
+        Operation::Insert {
+            new: department(20, "HR"),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: joined(None, Some((20, "HR")))
+        }]
+    );
+
+    let out = join.process(USER_PORT, Operation::Insert { new: user(2, 20) });
+    assert_eq!(
+        out,
+        vec![
+            Operation::Delete {
+                old: joined(None, Some((20, "HR")))
+            },
+            Operation::Insert {
+                new: joined(Some((2, 20)), Some((20, "HR")))
+            },
+        ]
+    );
+
+    let out = join.process(USER_PORT, Operation::Delete { old: user(2, 20) });
+    assert_eq!(
+        out,
+        vec![
+            Operation::Delete {
+                old: joined(Some((2, 20)), Some((20, "HR")))
+            },
+            Operation::Insert {
+                new: joined(None, Some((20, "HR")))
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_full_outer_join() {
+    let mut join = TestJoin::new(
+        "SELECT * FROM user FULL OUTER JOIN department ON user.department_id = department.id",
+    );
+
+    let out = join.process(USER_PORT, Operation::Insert { new: user(1, 10) });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: joined(Some((1, 10)), None)
+        }]
+    );
+
+    let out = join.process(USER_PORT, Operation::Insert { new: user(2, 10) });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: joined(Some((2, 10)), None)
+        }]
+    );
+
+    let out = join.process(
+        DEPARTMENT_PORT,
+        Operation::Insert {
+            new: department(10, "IT"),
+        },
+    );
+    assert_eq!(out.len(), 4);
+    assert!(out.contains(&Operation::Delete {
+        old: joined(Some((1, 10)), None)
+    }));
+    assert!(out.contains(&Operation::Delete {
+        old: joined(Some((2, 10)), None)
+    }));
+    assert!(out.contains(&Operation::Insert {
+        new: joined(Some((1, 10)), Some((10, "IT")))
+    }));
+    assert!(out.contains(&Operation::Insert {
+        new: joined(Some((2, 10)), Some((10, "IT")))
+    }));
+
+    let out = join.process(
+        DEPARTMENT_PORT,
+        Operation::Insert {
+            new: department(20, "HR"),
+        },
+    );
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: joined(None, Some((20, "HR")))
+        }]
+    );
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::builder::{BuilderExpressionType, ExpressionBuilder};
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use dozer_core::dag::{
+    dag::DEFAULT_PORT_HANDLE,
+    errors::ExecutionError,
+    node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
+};
+use dozer_types::types::Schema;
+use sqlparser::ast::Expr as SqlExpr;
+
+use super::processor::{SemiJoinProcessor, SemiJoinType};
+
+/// Port receiving the records to filter
+pub const LEFT_PORT: PortHandle = 0;
+/// Port receiving the records of the subquery
+pub const RIGHT_PORT: PortHandle = 1;
+
+#[derive(Debug)]
+pub struct SemiJoinProcessorFactory {
+    left_keys: Vec<SqlExpr>,
+    join_type: SemiJoinType,
+}
+
+impl SemiJoinProcessorFactory {
+    /// Creates a new [`SemiJoinProcessorFactory`]. The `left_keys` of a record are matched
+    /// against the first fields of the subquery records.
+    pub fn new(left_keys: Vec<SqlExpr>, join_type: SemiJoinType) -> Self {
+        Self {
+            left_keys,
+            join_type,
+        }
+    }
+
+    /// Builds the key expressions of the left records, checking them against the subquery
+    fn build_left_keys(
+        &self,
+        left_schema: &Schema,
+        right_schema: &Schema,
+    ) -> Result<Vec<Expression>, PipelineError> {
+        if right_schema.fields.len() < self.left_keys.len() {
+            return Err(PipelineError::InvalidQuery(format!(
+                "subquery has too few columns, {} expected",
+                self.left_keys.len()
+            )));
+        }
+
+        let builder = ExpressionBuilder {};
+        let mut left_keys = vec![];
+        for (expr, right_field) in self.left_keys.iter().zip(&right_schema.fields) {
+            let expression =
+                builder.build(&BuilderExpressionType::FullExpression, expr, left_schema)?;
+            let left_type = expression.get_type(left_schema)?.return_type;
+            if left_type != right_field.typ {
+                return Err(PipelineError::InvalidQuery(format!(
+                    "{expr} of type {left_type} cannot be matched with subquery column {:?} of type {}",
+                    right_field.name, right_field.typ
+                )));
+            }
+            left_keys.push(*expression);
+        }
+        Ok(left_keys)
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for SemiJoinProcessorFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![LEFT_PORT, RIGHT_PORT]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
+        let (left_schema, ctx) = input_schemas
+            .get(&LEFT_PORT)
+            .ok_or(ExecutionError::InvalidPortHandle(LEFT_PORT))?;
+        let (right_schema, _) = input_schemas
+            .get(&RIGHT_PORT)
+            .ok_or(ExecutionError::InvalidPortHandle(RIGHT_PORT))?;
+
+        self.build_left_keys(left_schema, right_schema)
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+        Ok((left_schema.clone(), ctx.clone()))
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        let left_schema = input_schemas
+            .get(&LEFT_PORT)
+            .ok_or(ExecutionError::InvalidPortHandle(LEFT_PORT))?;
+        let right_schema = input_schemas
+            .get(&RIGHT_PORT)
+            .ok_or(ExecutionError::InvalidPortHandle(RIGHT_PORT))?;
+
+        let left_keys = self
+            .build_left_keys(left_schema, right_schema)
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+        Ok(Box::new(SemiJoinProcessor::new(
+            left_schema.clone(),
+            left_keys,
+            self.join_type,
+        )))
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+        _output_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use crate::pipeline::storage::{decode_count, get_record_id};
+use dozer_core::dag::channels::ProcessorChannelForwarder;
+use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
+use dozer_core::dag::epoch::Epoch;
+use dozer_core::dag::errors::ExecutionError;
+use dozer_core::dag::errors::ExecutionError::InternalError;
+use dozer_core::dag::node::{PortHandle, Processor};
+use dozer_core::dag::record_store::RecordReader;
+use dozer_core::storage::common::{Database, Seek};
+use dozer_core::storage::errors::StorageError::InvalidRecord;
+use dozer_core::storage::lmdb_storage::{
+    LmdbEnvironmentManager, LmdbExclusiveTransaction, SharedTransaction,
+};
+use dozer_types::bincode;
+use dozer_types::errors::types::{DeserializationError, SerializationError, TypeError};
+use dozer_types::internal_err;
+use dozer_types::types::{Field, Operation, Record, Schema};
+use std::collections::HashMap;
+
+use super::factory::LEFT_PORT;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemiJoinType {
+    /// Forwards the records matching the subquery, as `IN` and `EXISTS`
+    Semi,
+    /// Forwards the records not matching the subquery, as `NOT EXISTS`
+    Anti,
+}
+
+/// Semi-Join Processor, forwards the records of the left port whose key matches (or, for
+/// an anti-join, does not match) the first fields of a record of the right port.
+/// Records with a NULL key never match.
+#[derive(Debug)]
+pub struct SemiJoinProcessor {
+    /// Expressions computing the key of a left record
+    left_keys: Vec<Expression>,
+    join_type: SemiJoinType,
+    left_schema: Schema,
+    /// Database to store the left records, by key
+    pub left_db: Option<Database>,
+    /// Database to store the occurrences of each key of the right records
+    pub right_db: Option<Database>,
+}
+
+impl SemiJoinProcessor {
+    /// Creates a new [`SemiJoinProcessor`].
+    pub fn new(left_schema: Schema, left_keys: Vec<Expression>, join_type: SemiJoinType) -> Self {
+        Self {
+            left_keys,
+            join_type,
+            left_schema,
+            left_db: None,
+            right_db: None,
+        }
+    }
+
+    fn init_store(&mut self, env: &mut LmdbEnvironmentManager) -> Result<(), PipelineError> {
+        self.left_db = Some(env.open_database("semi_join_left", false)?);
+        self.right_db = Some(env.open_database("semi_join_right", false)?);
+
+        Ok(())
+    }
+
+    /// Returns the key of a left record, or `None` if part of it is NULL
+    fn get_left_key(&self, record: &Record) -> Result<Option<Vec<u8>>, PipelineError> {
+        let mut values = vec![];
+        for expression in &self.left_keys {
+            values.push(expression.evaluate(record, &self.left_schema)?);
+        }
+        Ok(encode_key(&values))
+    }
+
+    /// Returns the key of a right record, or `None` if part of it is NULL
+    fn get_right_key(&self, record: &Record) -> Option<Vec<u8>> {
+        encode_key(&record.values[..self.left_keys.len()])
+    }
+
+    /// Returns whether a record with the given match state is forwarded
+    fn is_forwarded(&self, matched: bool) -> bool {
+        matched != (self.join_type == SemiJoinType::Anti)
+    }
+
+    /// Adds (or removes) an occurrence of the left `record`, returning whether it is forwarded
+    fn update_left(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        left_db: Database,
+        right_db: Database,
+        record: &Record,
+        insert: bool,
+    ) -> Result<bool, PipelineError> {
+        let join_key = match self.get_left_key(record)? {
+            Some(join_key) => join_key,
+            None => return Ok(self.is_forwarded(false)),
+        };
+
+        let mut key = join_key.clone();
+        key.extend(get_record_id(
+            &record.values,
+            &self.left_schema.primary_index,
+        ));
+
+        let prev_count = match txn.get(left_db, &key)? {
+            Some(value) => decode_count(value)?,
+            None => 0,
+        };
+        let new_count = if insert {
+            prev_count + 1
+        } else {
+            prev_count.saturating_sub(1)
+        };
+        if new_count == 0 {
+            txn.del(left_db, &key, None)?;
+        } else {
+            let mut value = new_count.to_be_bytes().to_vec();
+            value.extend(
+                bincode::serialize(&record.values)
+                    .map_err(|e| TypeError::SerializationError(SerializationError::Bincode(e)))?,
+            );
+            txn.put(left_db, &key, &value)?;
+        }
+
+        let matched = txn.get(right_db, &join_key)?.is_some();
+        Ok(self.is_forwarded(matched))
+    }
+
+    /// Returns the left records with the key `join_key`, repeated by their occurrences
+    fn read_left(
+        &self,
+        txn: &LmdbExclusiveTransaction,
+        left_db: Database,
+        join_key: &[u8],
+    ) -> Result<Vec<Record>, PipelineError> {
+        let cursor = txn.open_ro_cursor(left_db)?;
+        let mut records = vec![];
+
+        let mut exists = cursor.seek_gte(join_key)?;
+        while exists {
+            let (key, value) = cursor
+                .read()?
+                .ok_or(PipelineError::InternalStorageError(InvalidRecord))?;
+            if !key.starts_with(join_key) {
+                break;
+            }
+
+            let count = decode_count(value)?;
+            let values: Vec<Field> = bincode::deserialize(&value[8..])
+                .map_err(|e| TypeError::DeserializationError(DeserializationError::Bincode(e)))?;
+            for _ in 0..count {
+                records.push(Record::new(None, values.clone(), None));
+            }
+            exists = cursor.next()?;
+        }
+        Ok(records)
+    }
+
+    /// Adds (or removes) an occurrence of the right `record`, returning the operations on the
+    /// left records whose match state changed
+    fn update_right(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        left_db: Database,
+        right_db: Database,
+        record: &Record,
+        insert: bool,
+    ) -> Result<Vec<Operation>, PipelineError> {
+        let join_key = match self.get_right_key(record) {
+            Some(join_key) => join_key,
+            None => return Ok(vec![]),
+        };
+
+        let prev_count = match txn.get(right_db, &join_key)? {
+            Some(value) => decode_count(value)?,
+            None => 0,
+        };
+        let new_count = if insert {
+            prev_count + 1
+        } else {
+            prev_count.saturating_sub(1)
+        };
+        if new_count == 0 {
+            txn.del(right_db, &join_key, None)?;
+        } else {
+            txn.put(right_db, &join_key, &new_count.to_be_bytes())?;
+        }
+
+        // Only the first and the last occurrences of a key change the matches
+        if (prev_count == 0) == (new_count == 0) {
+            return Ok(vec![]);
+        }
+        let forwarded = self.is_forwarded(new_count > 0);
+        Ok(self
+            .read_left(txn, left_db, &join_key)?
+            .into_iter()
+            .map(|record| {
+                if forwarded {
+                    Operation::Insert { new: record }
+                } else {
+                    Operation::Delete { old: record }
+                }
+            })
+            .collect())
+    }
+
+    pub(crate) fn semi_join(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        left_db: Database,
+        right_db: Database,
+        from_port: PortHandle,
+        op: Operation,
+    ) -> Result<Vec<Operation>, PipelineError> {
+        if from_port != LEFT_PORT {
+            return match op {
+                Operation::Insert { new } => self.update_right(txn, left_db, right_db, &new, true),
+                Operation::Delete { old } => self.update_right(txn, left_db, right_db, &old, false),
+                Operation::Update { old, new } => {
+                    if self.get_right_key(&old) == self.get_right_key(&new) {
+                        return Ok(vec![]);
+                    }
+                    let mut ops = self.update_right(txn, left_db, right_db, &old, false)?;
+                    ops.extend(self.update_right(txn, left_db, right_db, &new, true)?);
+                    Ok(ops)
+                }
+            };
+        }
+
+        match op {
+            Operation::Insert { new } => {
+                if self.update_left(txn, left_db, right_db, &new, true)? {
+                    Ok(vec![Operation::Insert { new }])
+                } else {
+                    Ok(vec![])
+                }
+            }
+            Operation::Delete { old } => {
+                if self.update_left(txn, left_db, right_db, &old, false)? {
+                    Ok(vec![Operation::Delete { old }])
+                } else {
+                    Ok(vec![])
+                }
+            }
+            Operation::Update { old, new } => {
+                let old_forwarded = self.update_left(txn, left_db, right_db, &old, false)?;
+                let new_forwarded = self.update_left(txn, left_db, right_db, &new, true)?;
+                match (old_forwarded, new_forwarded) {
+                    (true, true) => Ok(vec![Operation::Update { old, new }]),
+                    (true, false) => Ok(vec![Operation::Delete { old }]),
+                    (false, true) => Ok(vec![Operation::Insert { new }]),
+                    (false, false) => Ok(vec![]),
+                }
+            }
+        }
+    }
+}
+
+/// Encodes the values of a key, or returns `None` if one of them is NULL.
+/// The key starts with the number of values, so that it is never empty.
+fn encode_key(values: &[Field]) -> Option<Vec<u8>> {
+    let mut key = (values.len() as u32).to_be_bytes().to_vec();
+    for value in values {
+        if value == &Field::Null {
+            return None;
+        }
+        let bytes = value.encode();
+        key.extend((bytes.len() as u32).to_be_bytes());
+        key.extend(bytes);
+    }
+    Some(key)
+}
+
+impl Processor for SemiJoinProcessor {
+    fn init(&mut self, state: &mut LmdbEnvironmentManager) -> Result<(), ExecutionError> {
+        internal_err!(self.init_store(state))
+    }
+
+    fn commit(&self, _epoch: &Epoch, _tx: &SharedTransaction) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+        txn: &SharedTransaction,
+        _reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
+    ) -> Result<(), ExecutionError> {
+        match (self.left_db, self.right_db) {
+            (Some(left_db), Some(right_db)) => {
+                let ops = internal_err!(self.semi_join(
+                    &mut txn.write(),
+                    left_db,
+                    right_db,
+                    from_port,
+                    op
+                ))?;
+                for fop in ops {
+                    fw.send(fop, DEFAULT_PORT_HANDLE)?;
+                }
+                Ok(())
+            }
+            _ => Err(ExecutionError::InvalidDatabase),
+        }
+    }
+}
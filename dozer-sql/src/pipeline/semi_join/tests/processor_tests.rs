@@ -0,0 +1,236 @@
+use dozer_core::dag::node::Processor;
+use dozer_core::storage::lmdb_storage::LmdbEnvironmentManager;
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+use tempdir::TempDir;
+
+use crate::pipeline::expression::execution::Expression;
+use crate::pipeline::semi_join::factory::{LEFT_PORT, RIGHT_PORT};
+use crate::pipeline::semi_join::processor::{SemiJoinProcessor, SemiJoinType};
+
+fn get_user_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("name"),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("department_id"),
+                FieldType::Int,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn user(name: &str, department_id: Option<i64>) -> Record {
+    Record::new(
+        None,
+        vec![
+            Field::String(name.to_string()),
+            department_id.map_or(Field::Null, Field::Int),
+        ],
+        None,
+    )
+}
+
+fn department(id: i64) -> Record {
+    Record::new(None, vec![Field::Int(id)], None)
+}
+
+fn run_semi_join(join_type: SemiJoinType, ops: Vec<(u16, Operation)>) -> Vec<Vec<Operation>> {
+    let tmp_dir = TempDir::new("semi_join").unwrap();
+    let mut storage = LmdbEnvironmentManager::create(tmp_dir.path(), "semi_join_test")
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let mut processor = SemiJoinProcessor::new(
+        get_user_schema(),
+        vec![Expression::Column { index: 1 }],
+        join_type,
+    );
+    processor
+        .init(&mut storage)
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let tx = storage.create_txn().unwrap();
+
+    ops.into_iter()
+        .map(|(port, op)| {
+            processor
+                .semi_join(
+                    &mut tx.write(),
+                    processor.left_db.unwrap(),
+                    processor.right_db.unwrap(),
+                    port,
+                    op,
+                )
+                .unwrap_or_else(|e| panic!("{}", e.to_string()))
+        })
+        .collect()
+}
+
+#[test]
+fn test_semi_join() {
+    let out = run_semi_join(
+        SemiJoinType::Semi,
+        vec![
+            // Records without a match are not forwarded
+            (
+                LEFT_PORT,
+                Operation::Insert {
+                    new: user("Alice", Some(1)),
+                },
+            ),
+            (
+                LEFT_PORT,
+                Operation::Insert {
+                    new: user("Alice", Some(1)),
+                },
+            ),
+            (
+                LEFT_PORT,
+                Operation::Insert {
+                    new: user("Bob", None),
+                },
+            ),
+            // The first match forwards all the records with the key
+            (RIGHT_PORT, Operation::Insert { new: department(1) }),
+            // Duplicates in the subquery do not produce duplicates
+            (RIGHT_PORT, Operation::Insert { new: department(1) }),
+            (
+                LEFT_PORT,
+                Operation::Insert {
+                    new: user("Carol", Some(1)),
+                },
+            ),
+            (
+                LEFT_PORT,
+                Operation::Update {
+                    old: user("Carol", Some(1)),
+                    new: user("Carol", Some(2)),
+                },
+            ),
+            (RIGHT_PORT, Operation::Delete { old: department(1) }),
+            // Removing the last match retracts the records
+            (
+                RIGHT_PORT,
+                Operation::Update {
+                    old: department(1),
+                    new: department(2),
+                },
+            ),
+        ],
+    );
+
+    assert_eq!(
+        out,
+        vec![
+            vec![],
+            vec![],
+            vec![],
+            vec![
+                Operation::Insert {
+                    new: user("Alice", Some(1))
+                },
+                Operation::Insert {
+                    new: user("Alice", Some(1))
+                },
+            ],
+            vec![],
+            vec![Operation::Insert {
+                new: user("Carol", Some(1))
+            }],
+            vec![Operation::Delete {
+                old: user("Carol", Some(1))
+            }],
+            vec![],
+            vec![
+                Operation::Delete {
+                    old: user("Alice", Some(1))
+                },
+                Operation::Delete {
+                    old: user("Alice", Some(1))
+                },
+                Operation::Insert {
+                    new: user("Carol", Some(2))
+                },
+            ],
+        ]
+    );
+}
+
+#[test]
+fn test_anti_join() {
+    let out = run_semi_join(
+        SemiJoinType::Anti,
+        vec![
+            (RIGHT_PORT, Operation::Insert { new: department(1) }),
+            (
+                LEFT_PORT,
+                Operation::Insert {
+                    new: user("Alice", Some(1)),
+                },
+            ),
+            // Records with a NULL key never match
+            (
+                LEFT_PORT,
+                Operation::Insert {
+                    new: user("Bob", None),
+                },
+            ),
+            (
+                LEFT_PORT,
+                Operation::Insert {
+                    new: user("Carol", Some(2)),
+                },
+            ),
+            (
+                RIGHT_PORT,
+                Operation::Insert {
+                    new: Record::new(None, vec![Field::Null], None),
+                },
+            ),
+            (RIGHT_PORT, Operation::Delete { old: department(1) }),
+            (
+                LEFT_PORT,
+                Operation::Update {
+                    old: user("Alice", Some(1)),
+                    new: user("Alice", Some(3)),
+                },
+            ),
+            (RIGHT_PORT, Operation::Insert { new: department(2) }),
+        ],
+    );
+
+    assert_eq!(
+        out,
+        vec![
+            vec![],
+            vec![],
+            vec![Operation::Insert {
+                new: user("Bob", None)
+            }],
+            vec![Operation::Insert {
+                new: user("Carol", Some(2))
+            }],
+            vec![],
+            vec![Operation::Insert {
+                new: user("Alice", Some(1))
+            }],
+            vec![Operation::Update {
+                old: user("Alice", Some(1)),
+                new: user("Alice", Some(3))
+            }],
+            vec![Operation::Delete {
+                old: user("Carol", Some(2))
+            }],
+        ]
+    );
+}
@@ -0,0 +1,94 @@
+use dozer_types::chrono::Datelike;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::Field;
+
+const NULL_FIRST: u8 = 0x00;
+const NOT_NULL: u8 = 0x01;
+const NULL_LAST: u8 = 0x02;
+
+/// Encodes `field` so that the byte order of the encoded values matches the order of the
+/// values, as required by ORDER BY and by the aggregations keeping their values sorted.
+/// Descending values are encoded with inverted bytes.
+pub(crate) fn encode_sort_key(field: &Field, descending: bool, nulls_first: bool) -> Vec<u8> {
+    let mut body = match field {
+        Field::Null => {
+            return vec![if nulls_first { NULL_FIRST } else { NULL_LAST }];
+        }
+        Field::UInt(v) => v.to_be_bytes().to_vec(),
+        Field::Int(v) => encode_i64(*v),
+        Field::Float(v) => encode_f64(v.0),
+        Field::Decimal(v) => encode_decimal(v),
+        Field::Boolean(v) => vec![*v as u8],
+        Field::String(v) | Field::Text(v) => encode_bytes(v.as_bytes()),
+        Field::Binary(v) | Field::Bson(v) => encode_bytes(v),
+        Field::Timestamp(v) => {
+            let mut bytes = encode_i64(v.timestamp());
+            bytes.extend(v.timestamp_subsec_nanos().to_be_bytes());
+            bytes
+        }
+        Field::Date(v) => encode_i64(v.num_days_from_ce() as i64),
+    };
+
+    if descending {
+        body.iter_mut().for_each(|b| *b = !*b);
+    }
+    let mut key = Vec::with_capacity(body.len() + 1);
+    key.push(NOT_NULL);
+    key.extend(body);
+    key
+}
+
+fn encode_i64(v: i64) -> Vec<u8> {
+    ((v as u64) ^ (1 << 63)).to_be_bytes().to_vec()
+}
+
+fn encode_f64(v: f64) -> Vec<u8> {
+    let bits = v.to_bits();
+    let bits = if bits >> 63 == 1 {
+        !bits
+    } else {
+        bits ^ (1 << 63)
+    };
+    bits.to_be_bytes().to_vec()
+}
+
+/// Encodes the sign, then the exponent of the most significant digit, then the significant
+/// digits, so that the decimals with the same value have the same key regardless of their
+/// scale. The bytes of negative values are inverted, larger magnitudes sorting first.
+fn encode_decimal(v: &Decimal) -> Vec<u8> {
+    if v.is_zero() {
+        return vec![0x01];
+    }
+    let v = v.normalize();
+    let digits = v.mantissa().unsigned_abs().to_string();
+    let exponent = digits.len() as i32 - 1 - v.scale() as i32;
+
+    let mut magnitude = Vec::with_capacity(digits.len() + 2);
+    magnitude.push((exponent + 128) as u8);
+    // Digits are shifted by one, so that the terminator sorts before them
+    magnitude.extend(digits.bytes().map(|d| d - b'0' + 1));
+    magnitude.push(0x00);
+
+    if v.is_sign_negative() {
+        let mut bytes = vec![0x00];
+        bytes.extend(magnitude.iter().map(|b| !*b));
+        bytes
+    } else {
+        let mut bytes = vec![0x02];
+        bytes.extend(magnitude);
+        bytes
+    }
+}
+
+/// Escapes the zero bytes, so that the terminator sorts before any other byte
+fn encode_bytes(v: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(v.len() + 2);
+    for b in v {
+        bytes.push(*b);
+        if *b == 0x00 {
+            bytes.push(0xFF);
+        }
+    }
+    bytes.extend([0x00, 0x00]);
+    bytes
+}
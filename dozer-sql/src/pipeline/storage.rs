@@ -0,0 +1,35 @@
+use crate::pipeline::errors::PipelineError;
+use dozer_core::storage::errors::StorageError::InvalidRecord;
+use dozer_types::types::Field;
+
+/// Appends `value` to `key`, prefixed with its length so that the keys made of several
+/// values never collide
+pub(crate) fn append_field(key: &mut Vec<u8>, value: &Field) {
+    let bytes = value.encode();
+    key.extend((bytes.len() as u32).to_be_bytes());
+    key.extend(bytes);
+}
+
+/// Returns the suffix identifying a record in the keys of the processors storing records:
+/// its primary key, or all its values if its schema has none. Records with a large
+/// primary key, or without one and with large values, may exceed the LMDB key size limit.
+pub(crate) fn get_record_id(values: &[Field], primary_index: &[usize]) -> Vec<u8> {
+    let mut id = vec![];
+    if primary_index.is_empty() {
+        values.iter().for_each(|value| append_field(&mut id, value));
+    } else {
+        primary_index
+            .iter()
+            .for_each(|idx| append_field(&mut id, &values[*idx]));
+    }
+    id
+}
+
+/// Decodes the number of occurrences stored at the start of the value of a record
+pub(crate) fn decode_count(value: &[u8]) -> Result<u64, PipelineError> {
+    let bytes: [u8; 8] = value
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(PipelineError::InternalStorageError(InvalidRecord))?;
+    Ok(u64::from_be_bytes(bytes))
+}
@@ -1,4 +1,4 @@
-use dozer_core::dag::app::App;
+use dozer_core::dag::app::{App, AppPipeline};
 use dozer_core::dag::appsource::{AppSource, AppSourceManager};
 use dozer_core::dag::channels::SourceChannelForwarder;
 use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
@@ -10,6 +10,7 @@ use dozer_core::dag::node::{
 use dozer_core::dag::record_store::RecordReader;
 use dozer_core::storage::lmdb_storage::{LmdbEnvironmentManager, SharedTransaction};
 use dozer_types::log::debug;
+use dozer_types::models::sql_dialect::SqlDialect;
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::types::{
     Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
@@ -24,7 +25,11 @@ use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use tempdir::TempDir;
 
-use crate::pipeline::builder::{statement_to_pipeline, SchemaSQLContext};
+use crate::pipeline::builder::{
+    locate_schema_error, shared_statement_to_pipeline, statement_to_pipeline,
+    views_to_query_context, SchemaSQLContext,
+};
+use crate::pipeline::errors::{PipelineError, SqlLocation};
 
 /// Test Source
 #[derive(Debug)]
@@ -252,3 +257,362 @@ fn test_pipeline_builder() {
     let elapsed = now.elapsed();
     debug!("Elapsed: {:.2?}", elapsed);
 }
+
+#[test]
+fn test_union_pipeline_builder() {
+    let (mut pipeline, (node, node_port)) = statement_to_pipeline(
+        "SELECT Country, Spending FROM users WHERE Spending >= 1 \
+        UNION ALL \
+        SELECT Country, Spending FROM users WHERE Spending < 1 \
+        UNION \
+        SELECT Country, Spending FROM users",
+    )
+    .unwrap();
+
+    let mut asm = AppSourceManager::new();
+    asm.add(AppSource::new(
+        "mem".to_string(),
+        Arc::new(TestSourceFactory::new(vec![DEFAULT_PORT_HANDLE])),
+        vec![("users".to_string(), DEFAULT_PORT_HANDLE)]
+            .into_iter()
+            .collect(),
+    ))
+    .unwrap();
+
+    pipeline.add_sink(
+        Arc::new(TestSinkFactory::new(vec![DEFAULT_PORT_HANDLE])),
+        "sink",
+    );
+    pipeline
+        .connect_nodes(&node, Some(node_port), "sink", Some(DEFAULT_PORT_HANDLE))
+        .unwrap();
+
+    let mut app = App::new(asm);
+    app.add_pipeline(pipeline);
+
+    let dag = app.get_dag().unwrap();
+
+    let tmp_dir = TempDir::new("test").unwrap();
+    let mut executor = DagExecutor::new(
+        &dag,
+        tmp_dir.path(),
+        ExecutorOptions::default(),
+        Arc::new(AtomicBool::new(true)),
+    )
+    .unwrap();
+
+    executor
+        .start()
+        .unwrap_or_else(|e| panic!("Unable to start the Executor: {e}"));
+    assert!(executor.join().is_ok());
+}
+
+#[test]
+fn test_top_n_pipeline_builder() {
+    let queries = [
+        "SELECT Country, Spending FROM users ORDER BY Spending DESC LIMIT 10 OFFSET 5",
+        "SELECT Country, Spending, rn FROM \
+        (SELECT Country, Spending, ROW_NUMBER() OVER (PARTITION BY Country ORDER BY Spending DESC) AS rn FROM users) t \
+        WHERE rn <= 3",
+    ];
+
+    for sql in queries {
+        let (mut pipeline, (node, node_port)) = statement_to_pipeline(sql).unwrap();
+
+        let mut asm = AppSourceManager::new();
+        asm.add(AppSource::new(
+            "mem".to_string(),
+            Arc::new(TestSourceFactory::new(vec![DEFAULT_PORT_HANDLE])),
+            vec![("users".to_string(), DEFAULT_PORT_HANDLE)]
+                .into_iter()
+                .collect(),
+        ))
+        .unwrap();
+
+        pipeline.add_sink(
+            Arc::new(TestSinkFactory::new(vec![DEFAULT_PORT_HANDLE])),
+            "sink",
+        );
+        pipeline
+            .connect_nodes(&node, Some(node_port), "sink", Some(DEFAULT_PORT_HANDLE))
+            .unwrap();
+
+        let mut app = App::new(asm);
+        app.add_pipeline(pipeline);
+
+        let dag = app.get_dag().unwrap();
+
+        let tmp_dir = TempDir::new("test").unwrap();
+        let mut executor = DagExecutor::new(
+            &dag,
+            tmp_dir.path(),
+            ExecutorOptions::default(),
+            Arc::new(AtomicBool::new(true)),
+        )
+        .unwrap();
+
+        executor
+            .start()
+            .unwrap_or_else(|e| panic!("Unable to start the Executor: {e}"));
+        assert!(executor.join().is_ok());
+    }
+
+    // ORDER BY is only supported together with LIMIT
+    assert!(statement_to_pipeline("SELECT Country FROM users ORDER BY Spending").is_err());
+    // Window frames are not supported
+    assert!(statement_to_pipeline(
+        "SELECT Country, SUM(Spending) OVER (PARTITION BY Country ORDER BY Spending \
+        ROWS BETWEEN 1 PRECEDING AND CURRENT ROW) AS total FROM users"
+    )
+    .is_err());
+}
+
+#[test]
+fn test_subquery_pipeline_builder() {
+    let queries = [
+        "SELECT Country, Spending FROM users \
+        WHERE CustomerID IN (SELECT CustomerID FROM users WHERE Spending > 1)",
+        "SELECT u.Country FROM users u \
+        WHERE EXISTS (SELECT 1 FROM users o WHERE o.CustomerID = u.CustomerID AND o.Spending > 1)",
+        "SELECT Country FROM users \
+        WHERE NOT EXISTS (SELECT Country FROM users WHERE Spending > 100) AND Spending > 1",
+    ];
+
+    for sql in queries {
+        let (mut pipeline, (node, node_port)) = statement_to_pipeline(sql).unwrap();
+
+        let mut asm = AppSourceManager::new();
+        asm.add(AppSource::new(
+            "mem".to_string(),
+            Arc::new(TestSourceFactory::new(vec![DEFAULT_PORT_HANDLE])),
+            vec![("users".to_string(), DEFAULT_PORT_HANDLE)]
+                .into_iter()
+                .collect(),
+        ))
+        .unwrap();
+
+        pipeline.add_sink(
+            Arc::new(TestSinkFactory::new(vec![DEFAULT_PORT_HANDLE])),
+            "sink",
+        );
+        pipeline
+            .connect_nodes(&node, Some(node_port), "sink", Some(DEFAULT_PORT_HANDLE))
+            .unwrap();
+
+        let mut app = App::new(asm);
+        app.add_pipeline(pipeline);
+
+        let dag = app.get_dag().unwrap();
+
+        let tmp_dir = TempDir::new("test").unwrap();
+        let mut executor = DagExecutor::new(
+            &dag,
+            tmp_dir.path(),
+            ExecutorOptions::default(),
+            Arc::new(AtomicBool::new(true)),
+        )
+        .unwrap();
+
+        executor
+            .start()
+            .unwrap_or_else(|e| panic!("Unable to start the Executor: {e}"));
+        assert!(executor.join().is_ok());
+    }
+
+    // Subqueries must be IN or EXISTS conditions combined with AND
+    assert!(statement_to_pipeline(
+        "SELECT Country FROM users WHERE CustomerID NOT IN (SELECT CustomerID FROM users)"
+    )
+    .is_err());
+    assert!(statement_to_pipeline(
+        "SELECT Country FROM users WHERE Spending > 1 OR EXISTS (SELECT 1 FROM users)"
+    )
+    .is_err());
+}
+
+#[test]
+fn test_shared_views_pipeline_builder() {
+    let mut pipeline = AppPipeline::new();
+    let mut views = views_to_query_context(
+        "CREATE VIEW spending AS SELECT Country, Spending FROM users WHERE Spending > 1; \
+        CREATE VIEW unused AS SELECT Country FROM users",
+        SqlDialect::Ansi,
+    )
+    .unwrap();
+
+    let queries = [
+        ("SELECT Country::text FROM spending", SqlDialect::Postgres),
+        (
+            "SELECT COUNT(Spending), Country FROM spending GROUP BY Country",
+            SqlDialect::Ansi,
+        ),
+    ];
+    for (index, (sql, dialect)) in queries.into_iter().enumerate() {
+        let (node, node_port) =
+            shared_statement_to_pipeline(sql, dialect, &mut pipeline, &mut views).unwrap();
+        let sink_name = format!("sink_{index}");
+        pipeline.add_sink(
+            Arc::new(TestSinkFactory::new(vec![DEFAULT_PORT_HANDLE])),
+            &sink_name,
+        );
+        pipeline
+            .connect_nodes(
+                &node,
+                Some(node_port),
+                &sink_name,
+                Some(DEFAULT_PORT_HANDLE),
+            )
+            .unwrap();
+    }
+
+    let mut asm = AppSourceManager::new();
+    asm.add(AppSource::new(
+        "mem".to_string(),
+        Arc::new(TestSourceFactory::new(vec![DEFAULT_PORT_HANDLE])),
+        vec![("users".to_string(), DEFAULT_PORT_HANDLE)]
+            .into_iter()
+            .collect(),
+    ))
+    .unwrap();
+
+    let mut app = App::new(asm);
+    app.add_pipeline(pipeline);
+
+    let dag = app.get_dag().unwrap();
+    // The views are built once, when first selected from: 3 nodes for "spending" and 2 per query
+    assert_eq!(dag.get_processors().len(), 7);
+
+    let tmp_dir = TempDir::new("test").unwrap();
+    let mut executor = DagExecutor::new(
+        &dag,
+        tmp_dir.path(),
+        ExecutorOptions::default(),
+        Arc::new(AtomicBool::new(true)),
+    )
+    .unwrap();
+
+    executor
+        .start()
+        .unwrap_or_else(|e| panic!("Unable to start the Executor: {e}"));
+    assert!(executor.join().is_ok());
+
+    // Views can be defined in the same block as the query, but only once
+    assert!(statement_to_pipeline(
+        "CREATE VIEW v AS SELECT Country FROM users; SELECT Country FROM v"
+    )
+    .is_ok());
+    assert!(statement_to_pipeline(
+        "CREATE VIEW v AS SELECT Country FROM users; \
+        CREATE VIEW v AS SELECT Spending FROM users; \
+        SELECT Country FROM v"
+    )
+    .is_err());
+    assert!(
+        statement_to_pipeline("SELECT Country FROM users; SELECT Spending FROM users").is_err()
+    );
+}
+
+fn sql_error_location(error: PipelineError) -> SqlLocation {
+    match error {
+        PipelineError::SqlError { location, .. } => location,
+        e => panic!("Expected an SQL error, got: {e}"),
+    }
+}
+
+#[test]
+fn test_sql_error_location() {
+    // Parse errors point at the unexpected token
+    let error = statement_to_pipeline("SELECT Country\nFROM users\nWHERE Spending >> 1")
+        .err()
+        .unwrap();
+    assert_eq!(
+        sql_error_location(error),
+        SqlLocation {
+            line: 3,
+            column: 16,
+            snippet: "WHERE Spending >> 1".to_string(),
+            length: 2,
+        }
+    );
+
+    let error = statement_to_pipeline("SELEC Country FROM users")
+        .err()
+        .unwrap();
+    assert_eq!(
+        error.to_string(),
+        "Invalid query: Expected an SQL statement, found: SELEC\n \
+        --> line 1, column 1\n  \
+        |\n\
+        1 | SELEC Country FROM users\n  \
+        | ^^^^^"
+    );
+
+    // A missing token is reported at the end of the SQL
+    let error = statement_to_pipeline("SELECT Country FROM users WHERE\n")
+        .err()
+        .unwrap();
+    let location = sql_error_location(error);
+    assert_eq!((location.line, location.column), (1, 32));
+
+    let error = statement_to_pipeline("SELECT 'abc FROM users")
+        .err()
+        .unwrap();
+    let location = sql_error_location(error);
+    assert_eq!((location.line, location.column), (1, 8));
+
+    // Semantic errors point at the name they are about, in the statement that raised them
+    let error = statement_to_pipeline(
+        "CREATE VIEW v AS SELECT Country FROM users ORDER BY Spending LIMIT 1;\n\
+        SELECT Country FROM v ORDER BY Country",
+    )
+    .err()
+    .unwrap();
+    let location = sql_error_location(error);
+    assert_eq!(
+        (location.line, location.column, location.length),
+        (2, 23, 5)
+    );
+
+    // Errors raised when computing the schemas are located with `locate_schema_error`
+    let sql = "SELECT Country,\n  Unknown FROM users";
+    let (mut pipeline, (node, node_port)) = statement_to_pipeline(sql).unwrap();
+    let mut asm = AppSourceManager::new();
+    asm.add(AppSource::new(
+        "mem".to_string(),
+        Arc::new(TestSourceFactory::new(vec![DEFAULT_PORT_HANDLE])),
+        vec![("users".to_string(), DEFAULT_PORT_HANDLE)]
+            .into_iter()
+            .collect(),
+    ))
+    .unwrap();
+    pipeline.add_sink(
+        Arc::new(TestSinkFactory::new(vec![DEFAULT_PORT_HANDLE])),
+        "sink",
+    );
+    pipeline
+        .connect_nodes(&node, Some(node_port), "sink", Some(DEFAULT_PORT_HANDLE))
+        .unwrap();
+    let mut app = App::new(asm);
+    app.add_pipeline(pipeline);
+
+    let dag = app.get_dag().unwrap();
+    let tmp_dir = TempDir::new("test").unwrap();
+    let error = DagExecutor::new(
+        &dag,
+        tmp_dir.path(),
+        ExecutorOptions::default(),
+        Arc::new(AtomicBool::new(true)),
+    )
+    .err()
+    .unwrap();
+    let error = locate_schema_error(sql, SqlDialect::Ansi, error).unwrap();
+    assert_eq!(
+        sql_error_location(error),
+        SqlLocation {
+            line: 2,
+            column: 3,
+            snippet: "  Unknown FROM users".to_string(),
+            length: 7,
+        }
+    );
+}
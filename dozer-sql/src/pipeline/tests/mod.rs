@@ -1,5 +1,7 @@
 #[cfg(test)]
 mod builder_test;
+#[cfg(test)]
+mod sort_key_tests;
 
 #[cfg(test)]
 pub mod utils;
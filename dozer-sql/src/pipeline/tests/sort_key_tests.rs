@@ -0,0 +1,123 @@
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::rust_decimal::Decimal;
+use dozer_types::types::Field;
+
+use crate::pipeline::sort_key::encode_sort_key;
+
+fn assert_sorted(fields: &[Field], descending: bool, nulls_first: bool) {
+    let keys: Vec<Vec<u8>> = fields
+        .iter()
+        .map(|f| encode_sort_key(f, descending, nulls_first))
+        .collect();
+    for (i, pair) in keys.windows(2).enumerate() {
+        assert!(
+            pair[0] < pair[1],
+            "{:?} should sort before {:?}",
+            fields[i],
+            fields[i + 1]
+        );
+    }
+}
+
+#[test]
+fn test_int_sort_key() {
+    assert_sorted(
+        &[
+            Field::Int(i64::MIN),
+            Field::Int(-10),
+            Field::Int(0),
+            Field::Int(7),
+            Field::Int(i64::MAX),
+        ],
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_float_sort_key() {
+    assert_sorted(
+        &[
+            Field::Float(OrderedFloat(f64::NEG_INFINITY)),
+            Field::Float(OrderedFloat(-2.5)),
+            Field::Float(OrderedFloat(-0.1)),
+            Field::Float(OrderedFloat(0.0)),
+            Field::Float(OrderedFloat(1.5)),
+            Field::Float(OrderedFloat(f64::INFINITY)),
+        ],
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_decimal_sort_key() {
+    let decimal = |v: &str| Field::Decimal(v.parse::<Decimal>().unwrap());
+    let decimals = [
+        Field::Decimal(Decimal::MIN),
+        decimal("-12.5"),
+        decimal("-1.25"),
+        decimal("-1.2"),
+        decimal("-0.001"),
+        decimal("0"),
+        decimal("0.001"),
+        decimal("1.2"),
+        decimal("1.2000000000000000000000001"),
+        decimal("1.25"),
+        decimal("9.99"),
+        decimal("12"),
+        decimal("79228162514264337593543950334"),
+        Field::Decimal(Decimal::MAX),
+    ];
+    assert_sorted(&decimals, false, false);
+    let mut descending = decimals.to_vec();
+    descending.reverse();
+    assert_sorted(&descending, true, false);
+
+    // The scale does not change the key
+    assert_eq!(
+        encode_sort_key(&decimal("1.2"), false, false),
+        encode_sort_key(&decimal("1.200"), false, false)
+    );
+    assert_eq!(
+        encode_sort_key(&decimal("0"), false, false),
+        encode_sort_key(&decimal("-0.00"), false, false)
+    );
+}
+
+#[test]
+fn test_string_sort_key() {
+    assert_sorted(
+        &[
+            Field::String("".to_string()),
+            Field::String("a".to_string()),
+            Field::String("a\0".to_string()),
+            Field::String("ab".to_string()),
+            Field::String("b".to_string()),
+        ],
+        false,
+        false,
+    );
+}
+
+#[test]
+fn test_descending_sort_key() {
+    assert_sorted(
+        &[
+            Field::String("b".to_string()),
+            Field::String("ab".to_string()),
+            Field::String("a".to_string()),
+            Field::String("".to_string()),
+        ],
+        true,
+        false,
+    );
+    assert_sorted(&[Field::Int(3), Field::Int(0), Field::Int(-3)], true, false);
+}
+
+#[test]
+fn test_null_sort_key() {
+    assert_sorted(&[Field::Null, Field::Int(i64::MIN)], false, true);
+    assert_sorted(&[Field::Int(i64::MAX), Field::Null], false, false);
+    assert_sorted(&[Field::Null, Field::Int(i64::MAX)], true, true);
+}
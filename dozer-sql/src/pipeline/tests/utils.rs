@@ -1,14 +1,19 @@
 use sqlparser::{
     ast::{Query, Select, SetExpr, Statement},
-    dialect::AnsiDialect,
+    dialect::{AnsiDialect, Dialect},
     parser::Parser,
 };
 
 use crate::pipeline::errors::PipelineError;
 pub fn get_select(sql: &str) -> Result<Box<Select>, PipelineError> {
-    let dialect = AnsiDialect {};
+    get_select_with_dialect(sql, &AnsiDialect {})
+}
 
-    let ast = Parser::parse_sql(&dialect, sql).unwrap();
+pub fn get_select_with_dialect(
+    sql: &str,
+    dialect: &dyn Dialect,
+) -> Result<Box<Select>, PipelineError> {
+    let ast = Parser::parse_sql(dialect, sql).unwrap();
 
     let statement = ast.get(0).expect("First statement is missing").to_owned();
     if let Statement::Query(query) = statement {
@@ -0,0 +1,4 @@
+pub mod factory;
+pub mod processor;
+mod tests;
+pub mod window_function;
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::errors::{PipelineError, UnsupportedSqlError};
+use crate::pipeline::expression::builder::{BuilderExpressionType, ExpressionBuilder};
+use crate::pipeline::expression::execution::Expression;
+use dozer_core::dag::{
+    dag::DEFAULT_PORT_HANDLE,
+    errors::ExecutionError,
+    node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
+};
+use dozer_types::types::{Field, FieldDefinition, Schema, SourceDefinition};
+use sqlparser::ast::{
+    Expr as SqlExpr, Function, FunctionArg, FunctionArgExpr, OrderByExpr, Value, WindowSpec,
+};
+
+use super::processor::{SortExpression, TopNProcessor};
+use super::window_function::WindowFunction;
+
+#[derive(Debug)]
+pub struct TopNProcessorFactory {
+    partition_by: Vec<SqlExpr>,
+    order_by: Vec<OrderByExpr>,
+    offset: usize,
+    limit: Option<usize>,
+    functions: Vec<(String, Function)>,
+    stateful: bool,
+}
+
+impl TopNProcessorFactory {
+    /// Creates a new [`TopNProcessorFactory`]. The window `functions` are computed over the
+    /// sorted records of each partition and appended to them as fields with the given names.
+    pub fn new(
+        partition_by: Vec<SqlExpr>,
+        order_by: Vec<OrderByExpr>,
+        offset: usize,
+        limit: Option<usize>,
+        functions: Vec<(String, Function)>,
+        stateful: bool,
+    ) -> Self {
+        Self {
+            partition_by,
+            order_by,
+            offset,
+            limit,
+            functions,
+            stateful,
+        }
+    }
+}
+
+/// Builds the window function computed by `function`. The partitioning and ordering of its
+/// `OVER` clause are applied by the processor, and window frames are not supported.
+pub(crate) fn build_window_function(
+    function: &Function,
+    schema: &Schema,
+) -> Result<WindowFunction, PipelineError> {
+    if let Some(WindowSpec {
+        window_frame: Some(_),
+        ..
+    }) = &function.over
+    {
+        return Err(PipelineError::UnsupportedSqlError(
+            UnsupportedSqlError::WindowFrameError,
+        ));
+    }
+    let name = function.name.to_string().to_lowercase();
+    let mut args = vec![];
+    for arg in &function.args {
+        match arg {
+            FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => args.push(expr),
+            _ => return Err(PipelineError::InvalidArgument(arg.to_string())),
+        }
+    }
+
+    let (min_args, max_args) = match name.as_str() {
+        "row_number" | "rank" => (0, 0),
+        "lag" | "lead" => (1, 3),
+        "sum" => (1, 1),
+        _ => return Err(PipelineError::InvalidFunction(name)),
+    };
+    if args.len() < min_args {
+        return Err(PipelineError::NotEnoughArguments(name));
+    }
+    if args.len() > max_args {
+        return Err(PipelineError::TooManyArguments(name));
+    }
+
+    let builder = ExpressionBuilder {};
+    let mut expressions = vec![];
+    for arg in &args {
+        expressions.push(builder.build(&BuilderExpressionType::FullExpression, arg, schema)?);
+    }
+    let offset = match args.get(1) {
+        Some(SqlExpr::Value(Value::Number(offset, _))) => offset
+            .parse()
+            .map_err(|_| PipelineError::InvalidArgument(offset.clone()))?,
+        Some(arg) => return Err(PipelineError::InvalidArgument(arg.to_string())),
+        None => 1,
+    };
+    let mut expressions = expressions.into_iter();
+
+    match name.as_str() {
+        "row_number" => Ok(WindowFunction::RowNumber),
+        "rank" => Ok(WindowFunction::Rank),
+        "lag" | "lead" => {
+            let expression = expressions.next().unwrap();
+            let default = expressions
+                .nth(1)
+                .unwrap_or_else(|| Box::new(Expression::Literal(Field::Null)));
+            if name == "lag" {
+                Ok(WindowFunction::Lag {
+                    expression,
+                    offset,
+                    default,
+                })
+            } else {
+                Ok(WindowFunction::Lead {
+                    expression,
+                    offset,
+                    default,
+                })
+            }
+        }
+        _ => Ok(WindowFunction::Sum {
+            expression: expressions.next().unwrap(),
+        }),
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for TopNProcessorFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        if self.stateful {
+            vec![OutputPortDef::new(
+                DEFAULT_PORT_HANDLE,
+                OutputPortType::StatefulWithPrimaryKeyLookup {
+                    retr_old_records_for_deletes: true,
+                    retr_old_records_for_updates: true,
+                },
+            )]
+        } else {
+            vec![OutputPortDef::new(
+                DEFAULT_PORT_HANDLE,
+                OutputPortType::Stateless,
+            )]
+        }
+    }
+
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
+        let (schema, ctx) = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(ExecutionError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+
+        let mut output_schema = schema.clone();
+        for (name, function) in &self.functions {
+            let (return_type, nullable) = build_window_function(function, schema)
+                .and_then(|function| function.get_type(schema))
+                .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+            output_schema.fields.push(FieldDefinition::new(
+                name.clone(),
+                return_type,
+                nullable,
+                SourceDefinition::Dynamic,
+            ));
+        }
+        Ok((output_schema, ctx.clone()))
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        let schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(ExecutionError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+
+        let builder = ExpressionBuilder {};
+        let mut partition_by = vec![];
+        for expr in &self.partition_by {
+            partition_by.push(
+                *builder
+                    .build(&BuilderExpressionType::FullExpression, expr, schema)
+                    .map_err(|e| ExecutionError::InternalError(Box::new(e)))?,
+            );
+        }
+
+        let mut order_by = vec![];
+        for order in &self.order_by {
+            let expression = builder
+                .build(&BuilderExpressionType::FullExpression, &order.expr, schema)
+                .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+            // NULL values are larger than any other value, as in PostgreSQL
+            let descending = order.asc == Some(false);
+            order_by.push(SortExpression {
+                expression,
+                descending,
+                nulls_first: order.nulls_first.unwrap_or(descending),
+            });
+        }
+
+        let mut functions = vec![];
+        for (_, function) in &self.functions {
+            functions.push(
+                build_window_function(function, schema)
+                    .map_err(|e| ExecutionError::InternalError(Box::new(e)))?,
+            );
+        }
+
+        Ok(Box::new(TopNProcessor::new(
+            schema.clone(),
+            partition_by,
+            order_by,
+            self.offset,
+            self.limit,
+            functions,
+        )))
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+        _output_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
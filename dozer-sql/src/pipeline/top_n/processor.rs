@@ -0,0 +1,355 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use crate::pipeline::sort_key::encode_sort_key;
+use crate::pipeline::storage::{append_field, decode_count, get_record_id};
+use dozer_core::dag::channels::ProcessorChannelForwarder;
+use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
+use dozer_core::dag::epoch::Epoch;
+use dozer_core::dag::errors::ExecutionError;
+use dozer_core::dag::errors::ExecutionError::InternalError;
+use dozer_core::dag::node::{PortHandle, Processor};
+use dozer_core::dag::record_store::RecordReader;
+use dozer_core::storage::common::{Database, Seek};
+use dozer_core::storage::errors::StorageError::InvalidRecord;
+use dozer_core::storage::lmdb_storage::{
+    LmdbEnvironmentManager, LmdbExclusiveTransaction, SharedTransaction,
+};
+use dozer_types::bincode;
+use dozer_types::errors::types::{DeserializationError, SerializationError, TypeError};
+use dozer_types::internal_err;
+use dozer_types::types::{Field, Operation, Record, Schema};
+use std::collections::HashMap;
+
+use super::window_function::{PartitionRecord, WindowFunction};
+
+/// Sort criterion of the Top-N processor
+#[derive(Debug)]
+pub struct SortExpression {
+    pub expression: Box<Expression>,
+    pub descending: bool,
+    pub nulls_first: bool,
+}
+
+/// Top-N Processor, keeps the records of each partition sorted and forwards the ones
+/// ranked between `offset` and `offset + limit`, appending the window functions computed
+/// over the partition
+#[derive(Debug)]
+pub struct TopNProcessor {
+    /// Expressions identifying the partition of a record
+    partition_by: Vec<Expression>,
+    /// Sort criteria within a partition
+    order_by: Vec<SortExpression>,
+    /// Number of top records of each partition to skip
+    offset: usize,
+    /// Number of records of each partition to forward, all of them if `None`
+    limit: Option<usize>,
+    /// Functions appended to the records
+    functions: Vec<WindowFunction>,
+    input_schema: Schema,
+    /// Database to store the sorted records
+    pub db: Option<Database>,
+}
+
+impl TopNProcessor {
+    /// Creates a new [`TopNProcessor`].
+    pub fn new(
+        input_schema: Schema,
+        partition_by: Vec<Expression>,
+        order_by: Vec<SortExpression>,
+        offset: usize,
+        limit: Option<usize>,
+        functions: Vec<WindowFunction>,
+    ) -> Self {
+        Self {
+            partition_by,
+            order_by,
+            offset,
+            limit,
+            functions,
+            input_schema,
+            db: None,
+        }
+    }
+
+    fn init_store(&mut self, env: &mut LmdbEnvironmentManager) -> Result<(), PipelineError> {
+        self.db = Some(env.open_database("top_n", false)?);
+
+        Ok(())
+    }
+
+    /// Returns the prefix shared by the keys of all the records of the partition of `record`
+    fn get_partition_key(&self, record: &Record) -> Result<Vec<u8>, PipelineError> {
+        let mut key = vec![];
+        for expression in &self.partition_by {
+            let value = expression.evaluate(record, &self.input_schema)?;
+            append_field(&mut key, &value);
+        }
+        Ok(key)
+    }
+
+    /// Returns the key of `record`, made of its partition, its sort key and its identifier
+    fn get_record_key(
+        &self,
+        partition_key: &[u8],
+        record: &Record,
+    ) -> Result<Vec<u8>, PipelineError> {
+        let mut key = partition_key.to_vec();
+        for sort in &self.order_by {
+            let value = sort.expression.evaluate(record, &self.input_schema)?;
+            key.extend(encode_sort_key(&value, sort.descending, sort.nulls_first));
+        }
+        key.extend(self.get_record_id(&record.values));
+        Ok(key)
+    }
+
+    fn get_record_id(&self, values: &[Field]) -> Vec<u8> {
+        get_record_id(values, &self.input_schema.primary_index)
+    }
+
+    /// Adds (or removes) an occurrence of `record` to its partition
+    fn update_record(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        db: Database,
+        partition_key: &[u8],
+        record: &Record,
+        insert: bool,
+    ) -> Result<(), PipelineError> {
+        let key = self.get_record_key(partition_key, record)?;
+        let prev_count = match txn.get(db, &key)? {
+            Some(value) => decode_count(value)?,
+            None => 0,
+        };
+        let new_count = if insert {
+            prev_count + 1
+        } else {
+            prev_count.saturating_sub(1)
+        };
+
+        if new_count == 0 {
+            txn.del(db, &key, None)?;
+        } else {
+            let mut value = new_count.to_be_bytes().to_vec();
+            value.extend(
+                bincode::serialize(&record.values)
+                    .map_err(|e| TypeError::SerializationError(SerializationError::Bincode(e)))?,
+            );
+            txn.put(db, &key, &value)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the sorted records of the partition, up to `bound` records
+    fn read_partition(
+        &self,
+        txn: &LmdbExclusiveTransaction,
+        db: Database,
+        partition_key: &[u8],
+        bound: Option<usize>,
+    ) -> Result<Vec<PartitionRecord>, PipelineError> {
+        let cursor = txn.open_ro_cursor(db)?;
+        let mut records = vec![];
+
+        let mut exists = if partition_key.is_empty() {
+            cursor.first()?
+        } else {
+            cursor.seek_gte(partition_key)?
+        };
+        while exists {
+            let (key, value) = cursor
+                .read()?
+                .ok_or(PipelineError::InternalStorageError(InvalidRecord))?;
+            if !key.starts_with(partition_key) {
+                break;
+            }
+
+            let count = decode_count(value)?;
+            let values: Vec<Field> = bincode::deserialize(&value[8..])
+                .map_err(|e| TypeError::DeserializationError(DeserializationError::Bincode(e)))?;
+            // The key ends with the identifier of the record
+            let id_len = self.get_record_id(&values).len();
+            let sort_key = key[partition_key.len()..key.len() - id_len].to_vec();
+            for _ in 0..count {
+                if matches!(bound, Some(bound) if records.len() >= bound) {
+                    return Ok(records);
+                }
+                records.push(PartitionRecord {
+                    sort_key: sort_key.clone(),
+                    record: Record::new(None, values.clone(), None),
+                });
+            }
+            exists = cursor.next()?;
+        }
+        Ok(records)
+    }
+
+    /// Returns the number of records to read to compute the window functions of the first
+    /// `bound` records, all of them if `None`
+    fn get_read_bound(&self, bound: Option<usize>) -> Option<usize> {
+        let mut read_bound = bound?;
+        for function in &self.functions {
+            match function {
+                WindowFunction::RowNumber | WindowFunction::Rank | WindowFunction::Lag { .. } => {}
+                WindowFunction::Lead { offset, .. } => read_bound = read_bound.max(bound? + offset),
+                // The last records may share their sort key with the following ones
+                WindowFunction::Sum { .. } => return None,
+            }
+        }
+        Some(read_bound)
+    }
+
+    /// Returns the records of the partition ranked between `offset` and `offset + limit`
+    fn get_top_records(
+        &self,
+        txn: &LmdbExclusiveTransaction,
+        db: Database,
+        partition_key: &[u8],
+    ) -> Result<Vec<Record>, PipelineError> {
+        let bound = self.limit.map(|limit| self.offset + limit);
+        let records = self.read_partition(txn, db, partition_key, self.get_read_bound(bound))?;
+
+        let mut function_values = vec![];
+        for function in &self.functions {
+            function_values.push(function.compute(&records, &self.input_schema)?);
+        }
+
+        let end = bound.map_or(records.len(), |bound| bound.min(records.len()));
+        let mut top_records = vec![];
+        for position in self.offset.min(end)..end {
+            let mut record = records[position].record.clone();
+            for values in &function_values {
+                record.values.push(values[position].clone());
+            }
+            top_records.push(record);
+        }
+        Ok(top_records)
+    }
+
+    pub(crate) fn top_n(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        db: Database,
+        op: Operation,
+    ) -> Result<Vec<Operation>, PipelineError> {
+        let changes = match &op {
+            Operation::Insert { new } => vec![(new, true)],
+            Operation::Delete { old } => vec![(old, false)],
+            Operation::Update { old, new } => vec![(old, false), (new, true)],
+        };
+
+        let mut partitions: Vec<(Vec<u8>, Vec<Record>)> = vec![];
+        for (record, _) in &changes {
+            let partition_key = self.get_partition_key(record)?;
+            if !partitions.iter().any(|(key, _)| key == &partition_key) {
+                let records = self.get_top_records(txn, db, &partition_key)?;
+                partitions.push((partition_key, records));
+            }
+        }
+
+        for (record, insert) in &changes {
+            let partition_key = self.get_partition_key(record)?;
+            self.update_record(txn, db, &partition_key, record, *insert)?;
+        }
+
+        let mut ops = vec![];
+        for (partition_key, before) in partitions {
+            let after = self.get_top_records(txn, db, &partition_key)?;
+            ops.extend(self.diff(before, after, &op));
+        }
+        Ok(ops)
+    }
+
+    /// Returns the operations turning the top records `before` into the top records `after`.
+    /// Records are matched by their identifier, and the ones whose values or window functions
+    /// changed are forwarded as updates.
+    fn diff(&self, before: Vec<Record>, after: Vec<Record>, op: &Operation) -> Vec<Operation> {
+        let mut before_ids: HashMap<Vec<u8>, Vec<usize>> = HashMap::new();
+        for (index, record) in before.iter().enumerate() {
+            before_ids
+                .entry(self.get_top_record_id(record, None))
+                .or_default()
+                .push(index);
+        }
+        let mut before: Vec<Option<Record>> = before.into_iter().map(Some).collect();
+
+        // Unchanged records are matched first, so that they are not forwarded
+        let mut changed = vec![];
+        for record in after {
+            let id = self.get_top_record_id(&record, Some(op));
+            let indexes = before_ids.get_mut(&id);
+            let unchanged = indexes.and_then(|indexes| {
+                let position = indexes
+                    .iter()
+                    .position(|index| before[*index].as_ref() == Some(&record))?;
+                Some(indexes.remove(position))
+            });
+            match unchanged {
+                Some(index) => before[index] = None,
+                None => changed.push((id, record)),
+            }
+        }
+
+        let mut updates = vec![];
+        let mut inserts = vec![];
+        for (id, new) in changed {
+            match before_ids.get_mut(&id).and_then(|indexes| indexes.pop()) {
+                Some(index) => updates.push(Operation::Update {
+                    old: before[index].take().unwrap(),
+                    new,
+                }),
+                None => inserts.push(Operation::Insert { new }),
+            }
+        }
+
+        let mut ops: Vec<Operation> = before
+            .into_iter()
+            .flatten()
+            .map(|old| Operation::Delete { old })
+            .collect();
+        ops.extend(updates);
+        ops.extend(inserts);
+        ops
+    }
+
+    /// Returns the identifier of a top record, without its window functions. The new record of
+    /// an updated `op` is identified as its old record, as both are the same record.
+    fn get_top_record_id(&self, record: &Record, op: Option<&Operation>) -> Vec<u8> {
+        let values = &record.values[..record.values.len() - self.functions.len()];
+        match op {
+            Some(Operation::Update { old, new }) if new.values == values => {
+                self.get_record_id(&old.values)
+            }
+            _ => self.get_record_id(values),
+        }
+    }
+}
+
+impl Processor for TopNProcessor {
+    fn init(&mut self, state: &mut LmdbEnvironmentManager) -> Result<(), ExecutionError> {
+        internal_err!(self.init_store(state))
+    }
+
+    fn commit(&self, _epoch: &Epoch, _tx: &SharedTransaction) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+        txn: &SharedTransaction,
+        _reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
+    ) -> Result<(), ExecutionError> {
+        match self.db {
+            Some(db) => {
+                let ops = internal_err!(self.top_n(&mut txn.write(), db, op))?;
+                for fop in ops {
+                    fw.send(fop, DEFAULT_PORT_HANDLE)?;
+                }
+                Ok(())
+            }
+            _ => Err(ExecutionError::InvalidDatabase),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+#[cfg(test)]
+mod processor_tests;
+#[cfg(test)]
+mod window_function_tests;
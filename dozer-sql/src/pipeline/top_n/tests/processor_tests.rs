@@ -0,0 +1,242 @@
+use dozer_core::storage::lmdb_storage::LmdbEnvironmentManager;
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+use tempdir::TempDir;
+
+use crate::pipeline::expression::execution::Expression;
+use crate::pipeline::top_n::processor::{SortExpression, TopNProcessor};
+use crate::pipeline::top_n::window_function::WindowFunction;
+use dozer_core::dag::node::Processor;
+
+fn player_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("name"),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("country"),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("score"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn player(name: &str, country: &str, score: i64) -> Record {
+    Record::new(
+        None,
+        vec![
+            Field::String(name.to_string()),
+            Field::String(country.to_string()),
+            Field::Int(score),
+        ],
+        None,
+    )
+}
+
+fn ranked(name: &str, country: &str, score: i64, rn: i64) -> Record {
+    let mut record = player(name, country, score);
+    record.values.push(Field::Int(rn));
+    record
+}
+
+fn by_score_desc() -> Vec<SortExpression> {
+    vec![SortExpression {
+        expression: Box::new(Expression::Column { index: 2 }),
+        descending: true,
+        nulls_first: true,
+    }]
+}
+
+#[test]
+fn test_top_n() {
+    let tmp_dir = TempDir::new("top_n").unwrap();
+    let mut storage = LmdbEnvironmentManager::create(tmp_dir.path(), "top_n_test")
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let mut processor =
+        TopNProcessor::new(player_schema(), vec![], by_score_desc(), 0, Some(2), vec![]);
+    processor
+        .init(&mut storage)
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let tx = storage.create_txn().unwrap();
+
+    let top_n = |op: Operation| -> Vec<Operation> {
+        processor
+            .top_n(&mut tx.write(), processor.db.unwrap(), op)
+            .unwrap_or_else(|e| panic!("{}", e.to_string()))
+    };
+
+    top_n(Operation::Insert {
+        new: player("John", "UK", 10),
+    });
+    let out = top_n(Operation::Insert {
+        new: player("Mario", "Italy", 20),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: player("Mario", "Italy", 20)
+        }]
+    );
+
+    // A record outside of the top 2 is not forwarded
+    let out = top_n(Operation::Insert {
+        new: player("Wei", "Singapore", 5),
+    });
+    assert_eq!(out, vec![]);
+
+    // A new leader pushes the last record out of the top 2
+    let out = top_n(Operation::Insert {
+        new: player("Anna", "Italy", 30),
+    });
+    assert_eq!(
+        out,
+        vec![
+            Operation::Delete {
+                old: player("John", "UK", 10)
+            },
+            Operation::Insert {
+                new: player("Anna", "Italy", 30)
+            },
+        ]
+    );
+
+    // Deleting a record of the top 2 brings the next one in
+    let out = top_n(Operation::Delete {
+        old: player("Mario", "Italy", 20),
+    });
+    assert_eq!(
+        out,
+        vec![
+            Operation::Delete {
+                old: player("Mario", "Italy", 20)
+            },
+            Operation::Insert {
+                new: player("John", "UK", 10)
+            },
+        ]
+    );
+
+    // Updating a record within the top 2 is forwarded as an update
+    let out = top_n(Operation::Update {
+        old: player("John", "UK", 10),
+        new: player("John", "UK", 15),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Update {
+            old: player("John", "UK", 10),
+            new: player("John", "UK", 15),
+        }]
+    );
+}
+
+#[test]
+fn test_row_number() {
+    let tmp_dir = TempDir::new("top_n").unwrap();
+    let mut storage = LmdbEnvironmentManager::create(tmp_dir.path(), "top_n_test")
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let mut processor = TopNProcessor::new(
+        player_schema(),
+        vec![Expression::Column { index: 1 }],
+        by_score_desc(),
+        0,
+        Some(2),
+        vec![WindowFunction::RowNumber],
+    );
+    processor
+        .init(&mut storage)
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let tx = storage.create_txn().unwrap();
+
+    let top_n = |op: Operation| -> Vec<Operation> {
+        processor
+            .top_n(&mut tx.write(), processor.db.unwrap(), op)
+            .unwrap_or_else(|e| panic!("{}", e.to_string()))
+    };
+
+    let out = top_n(Operation::Insert {
+        new: player("Mario", "Italy", 20),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: ranked("Mario", "Italy", 20, 1)
+        }]
+    );
+
+    // Partitions are ranked independently
+    let out = top_n(Operation::Insert {
+        new: player("John", "UK", 10),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: ranked("John", "UK", 10, 1)
+        }]
+    );
+
+    // Records whose rank changes are updated
+    let out = top_n(Operation::Insert {
+        new: player("Anna", "Italy", 30),
+    });
+    assert_eq!(
+        out,
+        vec![
+            Operation::Update {
+                old: ranked("Mario", "Italy", 20, 1),
+                new: ranked("Mario", "Italy", 20, 2),
+            },
+            Operation::Insert {
+                new: ranked("Anna", "Italy", 30, 1)
+            },
+        ]
+    );
+
+    let out = top_n(Operation::Insert {
+        new: player("Luca", "Italy", 25),
+    });
+    assert_eq!(
+        out,
+        vec![
+            Operation::Delete {
+                old: ranked("Mario", "Italy", 20, 2)
+            },
+            Operation::Insert {
+                new: ranked("Luca", "Italy", 25, 2)
+            },
+        ]
+    );
+
+    // Only the records whose values or rank changed are forwarded
+    let out = top_n(Operation::Update {
+        old: player("Luca", "Italy", 25),
+        new: player("Luca", "Italy", 22),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Update {
+            old: ranked("Luca", "Italy", 25, 2),
+            new: ranked("Luca", "Italy", 22, 2),
+        }]
+    );
+}
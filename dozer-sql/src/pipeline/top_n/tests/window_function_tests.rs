@@ -0,0 +1,215 @@
+use dozer_core::storage::lmdb_storage::LmdbEnvironmentManager;
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+use tempdir::TempDir;
+
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::expression::execution::Expression;
+use crate::pipeline::top_n::processor::{SortExpression, TopNProcessor};
+use crate::pipeline::top_n::window_function::{PartitionRecord, WindowFunction};
+use dozer_core::dag::node::Processor;
+
+fn transaction_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("account"),
+                FieldType::String,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("day"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("amount"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn transaction(account: &str, day: i64, amount: i64) -> Record {
+    Record::new(
+        None,
+        vec![
+            Field::String(account.to_string()),
+            Field::Int(day),
+            Field::Int(amount),
+        ],
+        None,
+    )
+}
+
+fn with_functions(mut record: Record, balance: i64, previous: Option<i64>, rank: i64) -> Record {
+    record.values.push(Field::Int(balance));
+    record.values.push(previous.map_or(Field::Null, Field::Int));
+    record.values.push(Field::Int(rank));
+    record
+}
+
+#[test]
+fn test_window_functions() {
+    let tmp_dir = TempDir::new("top_n").unwrap();
+    let mut storage = LmdbEnvironmentManager::create(tmp_dir.path(), "top_n_test")
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let mut processor = TopNProcessor::new(
+        transaction_schema(),
+        vec![Expression::Column { index: 0 }],
+        vec![SortExpression {
+            expression: Box::new(Expression::Column { index: 1 }),
+            descending: false,
+            nulls_first: false,
+        }],
+        0,
+        None,
+        vec![
+            WindowFunction::Sum {
+                expression: Box::new(Expression::Column { index: 2 }),
+            },
+            WindowFunction::Lag {
+                expression: Box::new(Expression::Column { index: 2 }),
+                offset: 1,
+                default: Box::new(Expression::Literal(Field::Null)),
+            },
+            WindowFunction::Rank,
+        ],
+    );
+    processor
+        .init(&mut storage)
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let tx = storage.create_txn().unwrap();
+
+    let top_n = |op: Operation| -> Vec<Operation> {
+        processor
+            .top_n(&mut tx.write(), processor.db.unwrap(), op)
+            .unwrap_or_else(|e| panic!("{}", e.to_string()))
+    };
+
+    let out = top_n(Operation::Insert {
+        new: transaction("A", 1, 10),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: with_functions(transaction("A", 1, 10), 10, None, 1)
+        }]
+    );
+
+    let out = top_n(Operation::Insert {
+        new: transaction("A", 3, 5),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: with_functions(transaction("A", 3, 5), 15, Some(10), 2)
+        }]
+    );
+
+    // Only the records following the new one are updated
+    let out = top_n(Operation::Insert {
+        new: transaction("A", 2, 7),
+    });
+    assert_eq!(
+        out,
+        vec![
+            Operation::Update {
+                old: with_functions(transaction("A", 3, 5), 15, Some(10), 2),
+                new: with_functions(transaction("A", 3, 5), 22, Some(7), 3),
+            },
+            Operation::Insert {
+                new: with_functions(transaction("A", 2, 7), 17, Some(10), 2)
+            },
+        ]
+    );
+
+    // Other partitions are not affected
+    let out = top_n(Operation::Insert {
+        new: transaction("B", 1, 1),
+    });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: with_functions(transaction("B", 1, 1), 1, None, 1)
+        }]
+    );
+}
+
+#[test]
+fn test_peers() {
+    let records: Vec<PartitionRecord> = [(1, 10), (2, 5), (2, 7), (3, 1)]
+        .into_iter()
+        .map(|(day, amount)| PartitionRecord {
+            sort_key: vec![day],
+            record: transaction("A", day as i64, amount),
+        })
+        .collect();
+
+    // Records sharing the sort key get the same running sum and rank
+    let sum = WindowFunction::Sum {
+        expression: Box::new(Expression::Column { index: 2 }),
+    };
+    assert_eq!(
+        sum.compute(&records, &transaction_schema()).unwrap(),
+        vec![
+            Field::Int(10),
+            Field::Int(22),
+            Field::Int(22),
+            Field::Int(23)
+        ]
+    );
+    assert_eq!(
+        WindowFunction::Rank
+            .compute(&records, &transaction_schema())
+            .unwrap(),
+        vec![Field::Int(1), Field::Int(2), Field::Int(2), Field::Int(4)]
+    );
+    assert_eq!(
+        WindowFunction::RowNumber
+            .compute(&records, &transaction_schema())
+            .unwrap(),
+        vec![Field::Int(1), Field::Int(2), Field::Int(3), Field::Int(4)]
+    );
+
+    let lead = WindowFunction::Lead {
+        expression: Box::new(Expression::Column { index: 2 }),
+        offset: 2,
+        default: Box::new(Expression::Literal(Field::Int(0))),
+    };
+    assert_eq!(
+        lead.compute(&records, &transaction_schema()).unwrap(),
+        vec![Field::Int(7), Field::Int(1), Field::Int(0), Field::Int(0)]
+    );
+}
+
+#[test]
+fn test_sum_overflow() {
+    let records: Vec<PartitionRecord> = [(1, i64::MAX), (2, 1)]
+        .into_iter()
+        .map(|(day, amount)| PartitionRecord {
+            sort_key: vec![day],
+            record: transaction("A", day as i64, amount),
+        })
+        .collect();
+
+    let sum = WindowFunction::Sum {
+        expression: Box::new(Expression::Column { index: 2 }),
+    };
+    assert!(matches!(
+        sum.compute(&records, &transaction_schema()),
+        Err(PipelineError::ArithmeticOverflow(_))
+    ));
+}
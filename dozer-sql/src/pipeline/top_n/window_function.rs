@@ -0,0 +1,140 @@
+use crate::pipeline::errors::PipelineError;
+use crate::pipeline::errors::PipelineError::{ArithmeticOverflow, InvalidOperandType};
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use dozer_types::types::{Field, FieldType, Record, Schema};
+
+/// Record of a partition, along with its encoded sort key
+#[derive(Debug, Clone)]
+pub struct PartitionRecord {
+    pub sort_key: Vec<u8>,
+    pub record: Record,
+}
+
+/// Function computed over the sorted records of a partition
+#[derive(Debug)]
+pub enum WindowFunction {
+    /// Position of the record within its partition
+    RowNumber,
+    /// Position of the first record sharing the sort key of the record
+    Rank,
+    /// Value of `expression` on the record `offset` positions before, `default` if there is none
+    Lag {
+        expression: Box<Expression>,
+        offset: usize,
+        default: Box<Expression>,
+    },
+    /// Value of `expression` on the record `offset` positions after, `default` if there is none
+    Lead {
+        expression: Box<Expression>,
+        offset: usize,
+        default: Box<Expression>,
+    },
+    /// Sum of `expression` up to the last record sharing the sort key of the record
+    Sum { expression: Box<Expression> },
+}
+
+impl WindowFunction {
+    pub fn get_type(&self, schema: &Schema) -> Result<(FieldType, bool), PipelineError> {
+        match self {
+            WindowFunction::RowNumber | WindowFunction::Rank => Ok((FieldType::Int, false)),
+            WindowFunction::Lag { expression, .. } | WindowFunction::Lead { expression, .. } => {
+                Ok((expression.get_type(schema)?.return_type, true))
+            }
+            WindowFunction::Sum { expression } => {
+                let expression_type = expression.get_type(schema)?;
+                match expression_type.return_type {
+                    FieldType::Decimal | FieldType::Float | FieldType::Int | FieldType::UInt => {
+                        Ok((expression_type.return_type, expression_type.nullable))
+                    }
+                    _ => Err(InvalidOperandType("SUM".to_string())),
+                }
+            }
+        }
+    }
+
+    /// Computes the function for each record of a sorted partition
+    pub fn compute(
+        &self,
+        records: &[PartitionRecord],
+        schema: &Schema,
+    ) -> Result<Vec<Field>, PipelineError> {
+        let mut values = Vec::with_capacity(records.len());
+        match self {
+            WindowFunction::RowNumber => {
+                values.extend((1..=records.len() as i64).map(Field::Int));
+            }
+            WindowFunction::Rank => {
+                let mut rank = 0;
+                for (position, record) in records.iter().enumerate() {
+                    if position == 0 || record.sort_key != records[position - 1].sort_key {
+                        rank = position as i64 + 1;
+                    }
+                    values.push(Field::Int(rank));
+                }
+            }
+            WindowFunction::Lag {
+                expression,
+                offset,
+                default,
+            } => {
+                for (position, record) in records.iter().enumerate() {
+                    values.push(match position.checked_sub(*offset) {
+                        Some(other) => expression.evaluate(&records[other].record, schema)?,
+                        None => default.evaluate(&record.record, schema)?,
+                    });
+                }
+            }
+            WindowFunction::Lead {
+                expression,
+                offset,
+                default,
+            } => {
+                for (position, record) in records.iter().enumerate() {
+                    values.push(match records.get(position + offset) {
+                        Some(other) => expression.evaluate(&other.record, schema)?,
+                        None => default.evaluate(&record.record, schema)?,
+                    });
+                }
+            }
+            WindowFunction::Sum { expression } => {
+                let mut total = Field::Null;
+                let mut peers_start = 0;
+                for (position, record) in records.iter().enumerate() {
+                    total = add(total, expression.evaluate(&record.record, schema)?)?;
+                    // Records sharing the same sort key get the same sum
+                    let is_last_peer = !matches!(
+                        records.get(position + 1),
+                        Some(next) if next.sort_key == record.sort_key
+                    );
+                    if is_last_peer {
+                        values.extend((peers_start..=position).map(|_| total.clone()));
+                        peers_start = position + 1;
+                    }
+                }
+            }
+        }
+        Ok(values)
+    }
+}
+
+fn add(total: Field, value: Field) -> Result<Field, PipelineError> {
+    let overflow = || ArithmeticOverflow("SUM".to_string());
+    match (total, value) {
+        (total, Field::Null) => Ok(total),
+        (Field::Null, value) => Ok(value),
+        (Field::Int(total), Field::Int(value)) => total
+            .checked_add(value)
+            .map(Field::Int)
+            .ok_or_else(overflow),
+        (Field::UInt(total), Field::UInt(value)) => total
+            .checked_add(value)
+            .map(Field::UInt)
+            .ok_or_else(overflow),
+        (Field::Float(total), Field::Float(value)) => Ok(Field::Float(total + value)),
+        (Field::Decimal(total), Field::Decimal(value)) => total
+            .checked_add(value)
+            .map(Field::Decimal)
+            .ok_or_else(overflow),
+        _ => Err(InvalidOperandType("SUM".to_string())),
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::errors::PipelineError;
+use dozer_core::dag::{
+    dag::DEFAULT_PORT_HANDLE,
+    errors::ExecutionError,
+    node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
+};
+use dozer_types::types::{FieldDefinition, Schema, SourceDefinition};
+
+use super::processor::UnionProcessor;
+
+#[derive(Debug)]
+pub struct UnionProcessorFactory {
+    input_ports: Vec<PortHandle>,
+}
+
+impl UnionProcessorFactory {
+    /// Creates a new [`UnionProcessorFactory`] merging `input_count` inputs.
+    pub fn new(input_count: usize) -> Self {
+        Self {
+            input_ports: (0..input_count as PortHandle).collect(),
+        }
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for UnionProcessorFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        self.input_ports.clone()
+    }
+
+    /// The output has no primary key to look the records up by
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
+        let mut schemas = vec![];
+        for port in &self.input_ports {
+            let (schema, _) = input_schemas
+                .get(port)
+                .ok_or(ExecutionError::InvalidPortHandle(*port))?;
+            schemas.push(schema);
+        }
+
+        let output_schema =
+            build_union_schema(&schemas).map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+        Ok((output_schema, SchemaSQLContext::default()))
+    }
+
+    fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        Ok(Box::new(UnionProcessor::new()))
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+        _output_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+/// Builds the schema of the union of `schemas`. Fields are named after the first input
+/// and must have the same type in all the inputs.
+/// The schema has no primary key, as records of different inputs may share the same key.
+/// The duplicates removed by UNION are identified by all of their fields, as in DISTINCT.
+pub(crate) fn build_union_schema(schemas: &[&Schema]) -> Result<Schema, PipelineError> {
+    let (first, others) = schemas
+        .split_first()
+        .ok_or_else(|| PipelineError::InvalidQuery("UNION without inputs".to_string()))?;
+
+    if others
+        .iter()
+        .any(|schema| schema.fields.len() != first.fields.len())
+    {
+        return Err(PipelineError::InvalidQuery(
+            "each UNION query must have the same number of columns".to_string(),
+        ));
+    }
+
+    let mut output_schema = Schema::empty();
+    for (index, field) in first.fields.iter().enumerate() {
+        let mut nullable = field.nullable;
+        for schema in others {
+            let other = &schema.fields[index];
+            if other.typ != field.typ {
+                return Err(PipelineError::InvalidQuery(format!(
+                    "UNION types {} and {} of column {:?} cannot be matched",
+                    field.typ, other.typ, field.name
+                )));
+            }
+            nullable |= other.nullable;
+        }
+        output_schema.fields.push(FieldDefinition::new(
+            field.name.clone(),
+            field.typ,
+            nullable,
+            SourceDefinition::Dynamic,
+        ));
+    }
+    Ok(output_schema)
+}
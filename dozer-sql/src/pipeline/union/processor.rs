@@ -0,0 +1,42 @@
+use dozer_core::dag::channels::ProcessorChannelForwarder;
+use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
+use dozer_core::dag::epoch::Epoch;
+use dozer_core::dag::errors::ExecutionError;
+use dozer_core::dag::node::{PortHandle, Processor};
+use dozer_core::dag::record_store::RecordReader;
+use dozer_core::storage::lmdb_storage::{LmdbEnvironmentManager, SharedTransaction};
+use dozer_types::log::debug;
+use dozer_types::types::Operation;
+use std::collections::HashMap;
+
+/// Union Processor, merges the records of all its inputs into a single output
+#[derive(Debug, Default)]
+pub struct UnionProcessor {}
+
+impl UnionProcessor {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Processor for UnionProcessor {
+    fn init(&mut self, _env: &mut LmdbEnvironmentManager) -> Result<(), ExecutionError> {
+        debug!("{:?}", "Initialising Union Processor");
+        Ok(())
+    }
+
+    fn commit(&self, _epoch: &Epoch, _tx: &SharedTransaction) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+        _tx: &SharedTransaction,
+        _reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
+    ) -> Result<(), ExecutionError> {
+        fw.send(op, DEFAULT_PORT_HANDLE)
+    }
+}
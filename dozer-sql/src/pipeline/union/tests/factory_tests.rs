@@ -0,0 +1,75 @@
+use dozer_types::types::{FieldDefinition, FieldType, Schema, SourceDefinition};
+
+use crate::pipeline::union::factory::build_union_schema;
+
+fn orders_schema(region: &str, amount_type: FieldType, nullable: bool) -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("id"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Table {
+                    connection: region.to_string(),
+                    name: String::from("orders"),
+                },
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("amount"),
+                amount_type,
+                nullable,
+                SourceDefinition::Table {
+                    connection: region.to_string(),
+                    name: String::from("orders"),
+                },
+            ),
+            false,
+        )
+        .clone()
+}
+
+#[test]
+fn test_union_schema() {
+    let eu = orders_schema("eu", FieldType::Float, false);
+    let us = orders_schema("us", FieldType::Float, true);
+
+    // Both inputs may have an order with the same id, so the id is not a primary key
+    let schema = build_union_schema(&[&eu, &us]).unwrap();
+    assert_eq!(
+        schema,
+        Schema::empty()
+            .field(
+                FieldDefinition::new(
+                    String::from("id"),
+                    FieldType::Int,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .field(
+                FieldDefinition::new(
+                    String::from("amount"),
+                    FieldType::Float,
+                    true,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .clone()
+    );
+}
+
+#[test]
+fn test_union_schema_mismatch() {
+    let eu = orders_schema("eu", FieldType::Float, false);
+    let us = orders_schema("us", FieldType::Decimal, false);
+    assert!(build_union_schema(&[&eu, &us]).is_err());
+
+    let mut apac = orders_schema("apac", FieldType::Float, false);
+    apac.fields.pop();
+    assert!(build_union_schema(&[&eu, &apac]).is_err());
+}
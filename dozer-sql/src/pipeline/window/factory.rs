@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use crate::pipeline::builder::SchemaSQLContext;
+use crate::pipeline::errors::{PipelineError, WindowError};
+use crate::pipeline::expression::builder::{BuilderExpressionType, ExpressionBuilder};
+use crate::pipeline::expression::execution::ExpressionExecutor;
+use crate::pipeline::expression::interval::{parse_interval, Interval};
+use dozer_core::dag::{
+    dag::DEFAULT_PORT_HANDLE,
+    errors::ExecutionError,
+    node::{OutputPortDef, OutputPortType, PortHandle, Processor, ProcessorFactory},
+};
+use dozer_types::chrono::Duration;
+use dozer_types::types::{FieldDefinition, FieldType, Schema, SourceDefinition};
+use sqlparser::ast::{Expr as SqlExpr, Function, FunctionArg, FunctionArgExpr};
+
+use super::processor::{WindowProcessor, WindowType};
+
+pub const WINDOW_START: &str = "window_start";
+pub const WINDOW_END: &str = "window_end";
+
+#[derive(Debug)]
+pub struct WindowProcessorFactory {
+    window: WindowType,
+    timestamp: SqlExpr,
+    retention: Option<Duration>,
+}
+
+impl WindowProcessorFactory {
+    /// Creates a new [`WindowProcessorFactory`].
+    pub fn new(window: WindowType, timestamp: SqlExpr, retention: Option<Duration>) -> Self {
+        Self {
+            window,
+            timestamp,
+            retention,
+        }
+    }
+
+    /// Parses `TUMBLE(ts, size [, retention])` and `HOP(ts, hop, size [, retention])`,
+    /// returning `None` if `function` is not a window function
+    pub(crate) fn from_function(function: &Function) -> Result<Option<Self>, PipelineError> {
+        let name = function.name.to_string().to_lowercase();
+        let window_size = match name.as_str() {
+            "tumble" => 1,
+            "hop" => 2,
+            _ => return Ok(None),
+        };
+
+        let mut args = vec![];
+        for arg in &function.args {
+            match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => args.push(expr),
+                _ => return Err(WindowError::InvalidArguments(name).into()),
+            }
+        }
+        if args.len() != window_size + 1 && args.len() != window_size + 2 {
+            return Err(WindowError::InvalidArguments(name).into());
+        }
+
+        let window = if window_size == 1 {
+            WindowType::Tumble {
+                size: parse_window_interval(args[1])?,
+            }
+        } else {
+            WindowType::Hop {
+                hop: parse_window_interval(args[1])?,
+                size: parse_window_interval(args[2])?,
+            }
+        };
+        let retention = match args.get(window_size + 1) {
+            Some(arg) => Some(parse_window_interval(arg)?),
+            None => None,
+        };
+
+        Ok(Some(Self::new(window, args[0].clone(), retention)))
+    }
+}
+
+/// Parses the interval of a window, a positive fixed duration such as `INTERVAL '5' MINUTE`
+pub(crate) fn parse_window_interval(expr: &SqlExpr) -> Result<Duration, WindowError> {
+    match parse_interval(expr) {
+        Ok(Interval::Duration(duration)) if duration > Duration::zero() => Ok(duration),
+        _ => Err(WindowError::InvalidInterval(expr.to_string())),
+    }
+}
+
+impl ProcessorFactory<SchemaSQLContext> for WindowProcessorFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<OutputPortDef> {
+        vec![OutputPortDef::new(
+            DEFAULT_PORT_HANDLE,
+            OutputPortType::Stateless,
+        )]
+    }
+
+    fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(Schema, SchemaSQLContext), ExecutionError> {
+        let (schema, ctx) = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(ExecutionError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+
+        let timestamp = ExpressionBuilder {}
+            .build(
+                &BuilderExpressionType::FullExpression,
+                &self.timestamp,
+                schema,
+            )
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+        let timestamp_type = timestamp
+            .get_type(schema)
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+        if timestamp_type.return_type != FieldType::Timestamp {
+            return Err(ExecutionError::InternalError(Box::new(
+                WindowError::InvalidTimestampField(self.timestamp.to_string()),
+            )));
+        }
+
+        // A record belongs to several hopping windows, which are told apart by their start
+        let mut output_schema = schema.clone();
+        if !output_schema.primary_index.is_empty() {
+            output_schema.primary_index.push(output_schema.fields.len());
+        }
+        for name in [WINDOW_START, WINDOW_END] {
+            output_schema.fields.push(FieldDefinition::new(
+                name.to_string(),
+                FieldType::Timestamp,
+                false,
+                SourceDefinition::Dynamic,
+            ));
+        }
+        Ok((output_schema, ctx.clone()))
+    }
+
+    fn build(
+        &self,
+        input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Processor>, ExecutionError> {
+        let schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(ExecutionError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+
+        let timestamp = ExpressionBuilder {}
+            .build(
+                &BuilderExpressionType::FullExpression,
+                &self.timestamp,
+                schema,
+            )
+            .map_err(|e| ExecutionError::InternalError(Box::new(e)))?;
+
+        Ok(Box::new(WindowProcessor::new(
+            schema.clone(),
+            self.window,
+            timestamp,
+            self.retention,
+        )))
+    }
+
+    fn prepare(
+        &self,
+        _input_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+        _output_schemas: HashMap<PortHandle, (Schema, SchemaSQLContext)>,
+    ) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,331 @@
+use crate::pipeline::errors::{PipelineError, WindowError};
+use crate::pipeline::expression::execution::{Expression, ExpressionExecutor};
+use crate::pipeline::storage::{decode_count, get_record_id};
+use dozer_core::dag::channels::ProcessorChannelForwarder;
+use dozer_core::dag::dag::DEFAULT_PORT_HANDLE;
+use dozer_core::dag::epoch::Epoch;
+use dozer_core::dag::errors::ExecutionError;
+use dozer_core::dag::errors::ExecutionError::InternalError;
+use dozer_core::dag::node::{PortHandle, Processor};
+use dozer_core::dag::record_store::RecordReader;
+use dozer_core::storage::common::{Database, Seek};
+use dozer_core::storage::errors::StorageError::InvalidRecord;
+use dozer_core::storage::lmdb_storage::{
+    LmdbEnvironmentManager, LmdbExclusiveTransaction, SharedTransaction,
+};
+use dozer_types::bincode;
+use dozer_types::chrono::{DateTime, Duration, TimeZone, Utc};
+use dozer_types::errors::types::{DeserializationError, SerializationError, TypeError};
+use dozer_types::internal_err;
+use dozer_types::types::{Field, Operation, Record, Schema};
+use std::collections::HashMap;
+
+/// Prefix of the key storing the highest timestamp seen so far
+const WATERMARK_KEY: &[u8] = &[0x00];
+/// Prefix of the keys storing the records of the open windows
+const RECORD_PREFIX: u8 = 0x01;
+
+/// Time window assigned to the records by `TUMBLE()` and `HOP()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowType {
+    /// Non-overlapping windows of `size`
+    Tumble { size: Duration },
+    /// Windows of `size` starting every `hop`
+    Hop { hop: Duration, size: Duration },
+}
+
+impl WindowType {
+    /// Returns the start and the end, in milliseconds, of the windows containing `ts`
+    pub fn get_windows(&self, ts: i64) -> Vec<(i64, i64)> {
+        match self {
+            WindowType::Tumble { size } => {
+                let size = size.num_milliseconds();
+                let start = ts.div_euclid(size) * size;
+                vec![(start, start + size)]
+            }
+            WindowType::Hop { hop, size } => {
+                let (hop, size) = (hop.num_milliseconds(), size.num_milliseconds());
+                let mut windows = vec![];
+                let mut start = ts.div_euclid(hop) * hop;
+                while start > ts - size {
+                    windows.push((start, start + size));
+                    start -= hop;
+                }
+                windows.reverse();
+                windows
+            }
+        }
+    }
+}
+
+/// Window Processor, appends the start and the end of its time windows to each record,
+/// duplicating the records that belong to several windows.
+/// When a retention is set, the records of the windows closed for longer than the retention
+/// are retracted, so that the downstream aggregation drops their state.
+#[derive(Debug)]
+pub struct WindowProcessor {
+    window: WindowType,
+    /// Expression returning the timestamp of a record
+    timestamp: Box<Expression>,
+    /// How long the windows are kept after they are closed, forever if `None`
+    retention: Option<Duration>,
+    input_schema: Schema,
+    /// Database to store the records of the open windows
+    pub db: Option<Database>,
+}
+
+impl WindowProcessor {
+    /// Creates a new [`WindowProcessor`].
+    pub fn new(
+        input_schema: Schema,
+        window: WindowType,
+        timestamp: Box<Expression>,
+        retention: Option<Duration>,
+    ) -> Self {
+        Self {
+            window,
+            timestamp,
+            retention,
+            input_schema,
+            db: None,
+        }
+    }
+
+    fn init_store(&mut self, env: &mut LmdbEnvironmentManager) -> Result<(), PipelineError> {
+        self.db = Some(env.open_database("window", false)?);
+
+        Ok(())
+    }
+
+    /// Returns the timestamp of `record` in milliseconds, `None` if it is null
+    fn get_timestamp(&self, record: &Record) -> Result<Option<i64>, PipelineError> {
+        match self.timestamp.evaluate(record, &self.input_schema)? {
+            Field::Timestamp(ts) => Ok(Some(ts.timestamp_millis())),
+            Field::Null => Ok(None),
+            value => Err(PipelineError::InvalidValue(format!(
+                "{value} is not a timestamp"
+            ))),
+        }
+    }
+
+    /// Returns a copy of `record` for each of its windows
+    fn get_window_records(&self, record: &Record) -> Result<Vec<(i64, Record)>, PipelineError> {
+        let ts = match self.get_timestamp(record)? {
+            Some(ts) => ts,
+            None => return Ok(vec![]),
+        };
+
+        let mut records = vec![];
+        for (start, end) in self.window.get_windows(ts) {
+            let mut values = record.values.clone();
+            values.push(to_timestamp(start)?);
+            values.push(to_timestamp(end)?);
+            records.push((end, Record::new(None, values, None)));
+        }
+        Ok(records)
+    }
+
+    pub(crate) fn window(
+        &self,
+        txn: &mut LmdbExclusiveTransaction,
+        db: Database,
+        op: Operation,
+    ) -> Result<Vec<Operation>, PipelineError> {
+        let (old, new) = match &op {
+            Operation::Insert { new } => (vec![], self.get_window_records(new)?),
+            Operation::Delete { old } => (self.get_window_records(old)?, vec![]),
+            Operation::Update { old, new } => {
+                (self.get_window_records(old)?, self.get_window_records(new)?)
+            }
+        };
+
+        let retention = match self.retention {
+            Some(retention) => retention.num_milliseconds(),
+            None => {
+                let mut ops: Vec<Operation> = old
+                    .into_iter()
+                    .map(|(_, old)| Operation::Delete { old })
+                    .collect();
+                ops.extend(new.into_iter().map(|(_, new)| Operation::Insert { new }));
+                return Ok(ops);
+            }
+        };
+
+        let primary_index = &self.input_schema.primary_index;
+        let mut watermark = get_watermark(txn, db)?;
+        let mut ops = vec![];
+        for (end, record) in old {
+            // Records of expired windows have already been retracted
+            if update_record(txn, db, end, &record, primary_index, false)? {
+                ops.push(Operation::Delete { old: record });
+            }
+        }
+        for (end, record) in new {
+            // Late records of expired windows are dropped
+            let expiry = end
+                .checked_add(retention)
+                .ok_or(WindowError::RetentionOverflow(end))?;
+            if !matches!(watermark, Some(watermark) if expiry <= watermark) {
+                update_record(txn, db, end, &record, primary_index, true)?;
+                ops.push(Operation::Insert { new: record });
+            }
+        }
+
+        if let Operation::Insert { new } | Operation::Update { new, .. } = &op {
+            if let Some(ts) = self.get_timestamp(new)? {
+                if !matches!(watermark, Some(watermark) if ts <= watermark) {
+                    watermark = Some(ts);
+                    txn.put(db, WATERMARK_KEY, &ts.to_be_bytes())?;
+                }
+            }
+        }
+        if let Some(watermark) = watermark {
+            let expired_end = watermark
+                .checked_sub(retention)
+                .ok_or(WindowError::RetentionOverflow(watermark))?;
+            ops.extend(expire_windows(txn, db, expired_end)?);
+        }
+        Ok(ops)
+    }
+}
+
+fn to_timestamp(millis: i64) -> Result<Field, PipelineError> {
+    let ts = Utc
+        .timestamp_millis_opt(millis)
+        .single()
+        .ok_or_else(|| PipelineError::InvalidValue(format!("{millis} is not a valid timestamp")))?;
+    Ok(Field::Timestamp(DateTime::from(ts)))
+}
+
+fn get_watermark(
+    txn: &LmdbExclusiveTransaction,
+    db: Database,
+) -> Result<Option<i64>, PipelineError> {
+    match txn.get(db, WATERMARK_KEY)? {
+        Some(value) => {
+            Ok(Some(i64::from_be_bytes(value.try_into().map_err(
+                |_| PipelineError::InternalStorageError(InvalidRecord),
+            )?)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Returns the key of `record`, made of the end of its window, so that the records are
+/// sorted by expiry, and its identifier
+fn get_record_key(end: i64, record: &Record, primary_index: &[usize]) -> Vec<u8> {
+    let mut key = vec![RECORD_PREFIX];
+    key.extend(encode_window_end(end));
+    key.extend(get_record_id(&record.values, primary_index));
+    key
+}
+
+/// Encodes the end of a window, preserving the order of negative timestamps
+fn encode_window_end(end: i64) -> [u8; 8] {
+    ((end as u64) ^ (1 << 63)).to_be_bytes()
+}
+
+/// Adds (or removes) an occurrence of `record` to the window ending at `end`.
+/// Returns false if the record to remove is not stored.
+fn update_record(
+    txn: &mut LmdbExclusiveTransaction,
+    db: Database,
+    end: i64,
+    record: &Record,
+    primary_index: &[usize],
+    insert: bool,
+) -> Result<bool, PipelineError> {
+    let key = get_record_key(end, record, primary_index);
+    let prev_count = match txn.get(db, &key)? {
+        Some(value) => decode_count(value)?,
+        None => 0,
+    };
+    if !insert && prev_count == 0 {
+        return Ok(false);
+    }
+
+    let new_count = if insert {
+        prev_count + 1
+    } else {
+        prev_count - 1
+    };
+    if new_count == 0 {
+        txn.del(db, &key, None)?;
+    } else {
+        let mut value = new_count.to_be_bytes().to_vec();
+        value.extend(
+            bincode::serialize(&record.values)
+                .map_err(|e| TypeError::SerializationError(SerializationError::Bincode(e)))?,
+        );
+        txn.put(db, &key, &value)?;
+    }
+    Ok(true)
+}
+
+/// Retracts the records of the windows ending before `bound`
+fn expire_windows(
+    txn: &mut LmdbExclusiveTransaction,
+    db: Database,
+    bound: i64,
+) -> Result<Vec<Operation>, PipelineError> {
+    let bound = encode_window_end(bound);
+    let mut expired = vec![];
+    {
+        let cursor = txn.open_ro_cursor(db)?;
+        let mut exists = cursor.seek_gte(&[RECORD_PREFIX])?;
+        while exists {
+            let (key, value) = cursor
+                .read()?
+                .ok_or(PipelineError::InternalStorageError(InvalidRecord))?;
+            if key[1..9] > bound[..] {
+                break;
+            }
+            expired.push((key.to_vec(), value.to_vec()));
+            exists = cursor.next()?;
+        }
+    }
+
+    let mut ops = vec![];
+    for (key, value) in expired {
+        let count = decode_count(&value)?;
+        let values: Vec<Field> = bincode::deserialize(&value[8..])
+            .map_err(|e| TypeError::DeserializationError(DeserializationError::Bincode(e)))?;
+        for _ in 0..count {
+            ops.push(Operation::Delete {
+                old: Record::new(None, values.clone(), None),
+            });
+        }
+        txn.del(db, &key, None)?;
+    }
+    Ok(ops)
+}
+
+impl Processor for WindowProcessor {
+    fn init(&mut self, state: &mut LmdbEnvironmentManager) -> Result<(), ExecutionError> {
+        internal_err!(self.init_store(state))
+    }
+
+    fn commit(&self, _epoch: &Epoch, _tx: &SharedTransaction) -> Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _from_port: PortHandle,
+        op: Operation,
+        fw: &mut dyn ProcessorChannelForwarder,
+        txn: &SharedTransaction,
+        _reader: &HashMap<PortHandle, Box<dyn RecordReader>>,
+    ) -> Result<(), ExecutionError> {
+        match self.db {
+            Some(db) => {
+                let ops = internal_err!(self.window(&mut txn.write(), db, op))?;
+                for fop in ops {
+                    fw.send(fop, DEFAULT_PORT_HANDLE)?;
+                }
+                Ok(())
+            }
+            _ => Err(ExecutionError::InvalidDatabase),
+        }
+    }
+}
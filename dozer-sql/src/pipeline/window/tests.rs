@@ -0,0 +1,4 @@
+#[cfg(test)]
+mod factory_tests;
+#[cfg(test)]
+mod processor_tests;
@@ -0,0 +1,51 @@
+use dozer_types::chrono::Duration;
+use sqlparser::ast::Expr as SqlExpr;
+
+use crate::pipeline::tests::utils::get_select;
+use crate::pipeline::window::factory::{parse_window_interval, WindowProcessorFactory};
+
+fn get_group_by(sql: &str) -> SqlExpr {
+    get_select(sql).unwrap().group_by[0].clone()
+}
+
+fn interval(sql: &str) -> Result<Duration, String> {
+    let expr = get_group_by(&format!("SELECT 1 FROM t GROUP BY {sql}"));
+    parse_window_interval(&expr).map_err(|e| e.to_string())
+}
+
+#[test]
+fn test_parse_interval() {
+    assert_eq!(interval("INTERVAL '5' MINUTE"), Ok(Duration::minutes(5)));
+    assert_eq!(interval("INTERVAL '90' SECOND"), Ok(Duration::seconds(90)));
+    assert_eq!(interval("INTERVAL '2 hours'"), Ok(Duration::hours(2)));
+    assert_eq!(interval("INTERVAL '1 day'"), Ok(Duration::days(1)));
+    // Windows have a fixed size
+    assert!(interval("INTERVAL '1' MONTH").is_err());
+    assert!(interval("INTERVAL '1' YEAR").is_err());
+    assert!(interval("INTERVAL '0' MINUTE").is_err());
+    assert!(interval("INTERVAL 'five' MINUTE").is_err());
+    assert!(interval("INTERVAL '200000000000000' DAY").is_err());
+}
+
+#[test]
+fn test_window_functions() {
+    let parse = |sql: &str| match get_group_by(sql) {
+        SqlExpr::Function(function) => WindowProcessorFactory::from_function(&function),
+        expr => panic!("{expr} is not a function"),
+    };
+
+    assert!(
+        parse("SELECT COUNT(id) FROM t GROUP BY TUMBLE(ts, INTERVAL '5' MINUTE)")
+            .unwrap()
+            .is_some()
+    );
+    assert!(parse(
+        "SELECT COUNT(id) FROM t GROUP BY HOP(ts, INTERVAL '1' MINUTE, INTERVAL '5' MINUTE, INTERVAL '1' HOUR)"
+    )
+    .unwrap()
+    .is_some());
+    assert!(parse("SELECT COUNT(id) FROM t GROUP BY UPPER(name)")
+        .unwrap()
+        .is_none());
+    assert!(parse("SELECT COUNT(id) FROM t GROUP BY HOP(ts, INTERVAL '1' MINUTE)").is_err());
+}
@@ -0,0 +1,198 @@
+use dozer_core::storage::lmdb_storage::LmdbEnvironmentManager;
+use dozer_types::chrono::{DateTime, Duration, TimeZone, Utc};
+use dozer_types::types::{
+    Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+};
+use tempdir::TempDir;
+
+use crate::pipeline::errors::{PipelineError, WindowError};
+use crate::pipeline::expression::execution::Expression;
+use crate::pipeline::window::processor::{WindowProcessor, WindowType};
+use dozer_core::dag::node::Processor;
+
+fn event_schema() -> Schema {
+    Schema::empty()
+        .field(
+            FieldDefinition::new(
+                String::from("id"),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("ts"),
+                FieldType::Timestamp,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn timestamp(minutes: i64) -> Field {
+    Field::Timestamp(DateTime::from(
+        Utc.timestamp_millis_opt(Duration::minutes(minutes).num_milliseconds())
+            .unwrap(),
+    ))
+}
+
+fn event(id: i64, minutes: i64) -> Record {
+    Record::new(None, vec![Field::Int(id), timestamp(minutes)], None)
+}
+
+fn windowed(id: i64, minutes: i64, start: i64, end: i64) -> Record {
+    Record::new(
+        None,
+        vec![
+            Field::Int(id),
+            timestamp(minutes),
+            timestamp(start),
+            timestamp(end),
+        ],
+        None,
+    )
+}
+
+#[test]
+fn test_get_windows() {
+    let minute = Duration::minutes(1).num_milliseconds();
+
+    let tumble = WindowType::Tumble {
+        size: Duration::minutes(5),
+    };
+    assert_eq!(
+        tumble.get_windows(7 * minute),
+        vec![(5 * minute, 10 * minute)]
+    );
+    assert_eq!(tumble.get_windows(-minute), vec![(-5 * minute, 0)]);
+
+    let hop = WindowType::Hop {
+        hop: Duration::minutes(2),
+        size: Duration::minutes(5),
+    };
+    assert_eq!(
+        hop.get_windows(7 * minute),
+        vec![(4 * minute, 9 * minute), (6 * minute, 11 * minute)]
+    );
+    assert_eq!(
+        hop.get_windows(6 * minute),
+        vec![
+            (2 * minute, 7 * minute),
+            (4 * minute, 9 * minute),
+            (6 * minute, 11 * minute)
+        ]
+    );
+}
+
+#[test]
+fn test_tumble_retention() {
+    let tmp_dir = TempDir::new("window").unwrap();
+    let mut storage = LmdbEnvironmentManager::create(tmp_dir.path(), "window_test")
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let mut processor = WindowProcessor::new(
+        event_schema(),
+        WindowType::Tumble {
+            size: Duration::minutes(5),
+        },
+        Box::new(Expression::Column { index: 1 }),
+        Some(Duration::minutes(10)),
+    );
+    processor
+        .init(&mut storage)
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let tx = storage.create_txn().unwrap();
+
+    let window = |op: Operation| -> Vec<Operation> {
+        processor
+            .window(&mut tx.write(), processor.db.unwrap(), op)
+            .unwrap_or_else(|e| panic!("{}", e.to_string()))
+    };
+
+    let out = window(Operation::Insert { new: event(1, 3) });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: windowed(1, 3, 0, 5)
+        }]
+    );
+
+    // Moving a record to another window
+    let out = window(Operation::Update {
+        old: event(1, 3),
+        new: event(1, 6),
+    });
+    assert_eq!(
+        out,
+        vec![
+            Operation::Delete {
+                old: windowed(1, 3, 0, 5)
+            },
+            Operation::Insert {
+                new: windowed(1, 6, 5, 10)
+            },
+        ]
+    );
+
+    let out = window(Operation::Insert { new: event(2, 12) });
+    assert_eq!(
+        out,
+        vec![Operation::Insert {
+            new: windowed(2, 12, 10, 15)
+        }]
+    );
+
+    // The window [5, 10) expires 10 minutes after its end
+    let out = window(Operation::Insert { new: event(3, 20) });
+    assert_eq!(
+        out,
+        vec![
+            Operation::Insert {
+                new: windowed(3, 20, 20, 25)
+            },
+            Operation::Delete {
+                old: windowed(1, 6, 5, 10)
+            },
+        ]
+    );
+
+    // Late records and deletes of expired windows are dropped
+    let out = window(Operation::Insert { new: event(4, 8) });
+    assert_eq!(out, vec![]);
+    let out = window(Operation::Delete { old: event(1, 6) });
+    assert_eq!(out, vec![]);
+}
+
+#[test]
+fn test_retention_overflow() {
+    let tmp_dir = TempDir::new("window").unwrap();
+    let mut storage = LmdbEnvironmentManager::create(tmp_dir.path(), "window_test")
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let mut processor = WindowProcessor::new(
+        event_schema(),
+        WindowType::Tumble {
+            size: Duration::minutes(5),
+        },
+        Box::new(Expression::Column { index: 1 }),
+        Some(Duration::milliseconds(i64::MAX)),
+    );
+    processor
+        .init(&mut storage)
+        .unwrap_or_else(|e| panic!("{}", e.to_string()));
+    let tx = storage.create_txn().unwrap();
+
+    let result = processor.window(
+        &mut tx.write(),
+        processor.db.unwrap(),
+        Operation::Insert { new: event(1, 3) },
+    );
+    assert!(matches!(
+        result,
+        Err(PipelineError::WindowError(WindowError::RetentionOverflow(
+            _
+        )))
+    ));
+}
@@ -1,3 +1,6 @@
+use super::sql_dialect::{
+    deserialize_sql_dialect_str_as_i32, serialize_sql_dialect_i32_as_string, SqlDialect,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
@@ -23,4 +26,20 @@ pub struct ApiEndpoint {
     pub sql: Option<String>,
     #[prost(message, tag = "6")]
     pub index: Option<ApiIndex>,
+    #[prost(enumeration = "SqlDialect", optional, tag = "7")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_sql_dialect_i32_as_string")]
+    #[serde(deserialize_with = "deserialize_sql_dialect_str_as_i32")]
+    /// SQL dialect of `sql`, overriding the one of the app
+    pub sql_dialect: Option<i32>,
+}
+
+impl ApiEndpoint {
+    /// Returns the dialect of `sql`, which defaults to the dialect of the app
+    pub fn get_sql_dialect(&self, app_dialect: SqlDialect) -> SqlDialect {
+        match self.sql_dialect {
+            Some(_) => self.sql_dialect(),
+            None => app_dialect,
+        }
+    }
 }
@@ -1,12 +1,18 @@
 use super::{
-    api_config::ApiConfig, api_endpoint::ApiEndpoint, connection::Connection, flags::Flags,
+    api_config::ApiConfig,
+    api_endpoint::ApiEndpoint,
+    connection::Connection,
+    flags::Flags,
     source::Source,
+    sql_dialect::{serialize_sql_dialect_i32_as_string, SqlDialect},
+    udf_config::UdfConfig,
 };
 use crate::{constants::DEFAULT_HOME_DIR, models::api_config::default_api_config};
 use serde::{
     de::{self, IgnoredAny, Visitor},
     Deserialize, Deserializer, Serialize,
 };
+use std::str::FromStr;
 #[derive(Serialize, PartialEq, Eq, Clone, prost::Message)]
 /// The configuration for the app
 pub struct Config {
@@ -37,6 +43,19 @@ pub struct Config {
     #[prost(message, tag = "8")]
     /// flags to enable/disable features
     pub flags: Option<Flags>,
+    #[prost(message, repeated, tag = "9")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// user defined functions callable from endpoint SQL
+    pub udfs: Vec<UdfConfig>,
+    #[prost(string, optional, tag = "10")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// views shared by the endpoints, defined by CREATE VIEW statements
+    pub sql: Option<String>,
+    #[prost(enumeration = "SqlDialect", optional, tag = "11")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_sql_dialect_i32_as_string")]
+    /// SQL dialect of the views and the endpoints: `ansi` or `postgres`; Default: ansi
+    pub sql_dialect: Option<i32>,
 }
 
 pub fn default_home_dir() -> String {
@@ -65,6 +84,9 @@ impl<'de> Deserialize<'de> for Config {
                 let mut connections: Vec<Connection> = vec![];
                 let mut sources_value: Vec<serde_yaml::Value> = vec![];
                 let mut endpoints: Vec<ApiEndpoint> = vec![];
+                let mut udfs: Vec<UdfConfig> = vec![];
+                let mut sql: Option<String> = None;
+                let mut sql_dialect: Option<i32> = None;
                 let mut app_name = "".to_owned();
                 let mut id: Option<String> = None;
                 let mut home_dir: String = default_home_dir();
@@ -91,6 +113,18 @@ impl<'de> Deserialize<'de> for Config {
                         "endpoints" => {
                             endpoints = access.next_value::<Vec<ApiEndpoint>>()?;
                         }
+                        "udfs" => {
+                            udfs = access.next_value::<Vec<UdfConfig>>()?;
+                        }
+                        "sql" => {
+                            sql = access.next_value::<Option<String>>()?;
+                        }
+                        "sql_dialect" => {
+                            let dialect = access.next_value::<String>()?;
+                            sql_dialect =
+                                Some(SqlDialect::from_str(&dialect).map_err(de::Error::custom)?
+                                    as i32);
+                        }
                         "home_dir" => {
                             home_dir = access.next_value::<String>()?;
                         }
@@ -145,6 +179,9 @@ impl<'de> Deserialize<'de> for Config {
                     endpoints,
                     home_dir,
                     flags,
+                    udfs,
+                    sql,
+                    sql_dialect,
                 })
             }
         }
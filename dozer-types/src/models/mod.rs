@@ -5,3 +5,5 @@ pub mod app_config;
 pub mod connection;
 pub mod flags;
 pub mod source;
+pub mod sql_dialect;
+pub mod udf_config;
@@ -26,6 +26,10 @@ pub struct Source {
     #[serde(default = "default_refresh_config")]
     /// setting for how to refresh the data; Default: RealTime
     pub refresh_config: Option<RefreshConfig>,
+    #[prost(oneof = "HistoryType", tags = "8, 9")]
+    #[serde(default)]
+    /// how the history of the records is kept; Default: records are overwritten
+    pub history_type: Option<HistoryType>,
 }
 fn default_refresh_config() -> Option<RefreshConfig> {
     Some(RefreshConfig::default())
@@ -35,7 +39,7 @@ impl Serialize for Source {
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("Source", 5)?;
+        let mut state = serializer.serialize_struct("Source", 6)?;
         state.serialize_field("name", &self.name)?;
         state.serialize_field("table_name", &self.table_name)?;
         state.serialize_field("columns", &self.columns)?;
@@ -44,6 +48,9 @@ impl Serialize for Source {
             &Value::Ref(self.connection.to_owned().unwrap_or_default().name),
         )?;
         state.serialize_field("refresh_config", &self.refresh_config)?;
+        if self.history_type.is_some() {
+            state.serialize_field("history_type", &self.history_type)?;
+        }
         state.end()
     }
 }
@@ -52,28 +59,71 @@ pub enum Value {
     Ref(String),
 }
 
-#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof)]
 pub enum HistoryType {
+    #[prost(message, tag = "8")]
     Master(MasterHistoryConfig),
+    #[prost(message, tag = "9")]
     Transactional(TransactionalHistoryConfig),
 }
-#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
-pub enum MasterHistoryConfig {
-    AppendOnly {
-        unique_key_field: String,
-        open_date_field: String,
-        closed_date_field: String,
-    },
-    Overwrite,
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct MasterHistoryConfig {
+    #[prost(oneof = "MasterHistoryPolicy", tags = "1, 2")]
+    #[serde(flatten)]
+    pub policy: Option<MasterHistoryPolicy>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
-pub enum TransactionalHistoryConfig {
-    RetainPartial {
-        timestamp_field: String,
-        retention_period: u32,
-    },
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof)]
+pub enum MasterHistoryPolicy {
+    #[prost(message, tag = "1")]
+    /// keeps every version of a record, closing the previous one on updates and deletes
+    AppendOnly(AppendOnlyConfig),
+    #[prost(message, tag = "2")]
+    /// keeps only the latest version of a record
+    Overwrite(OverwriteConfig),
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct AppendOnlyConfig {
+    #[prost(string, tag = "1")]
+    /// field identifying a record across its versions; Type: String
+    pub unique_key_field: String,
+    #[prost(string, tag = "2")]
+    /// timestamp field added to hold when a version became current; Type: String
+    pub open_date_field: String,
+    #[prost(string, tag = "3")]
+    /// timestamp field added to hold when a version was superseded or deleted, null while current; Type: String
+    pub closed_date_field: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct OverwriteConfig {}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct TransactionalHistoryConfig {
+    #[prost(oneof = "TransactionalHistoryPolicy", tags = "1")]
+    #[serde(flatten)]
+    pub policy: Option<TransactionalHistoryPolicy>,
 }
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof)]
+pub enum TransactionalHistoryPolicy {
+    #[prost(message, tag = "1")]
+    /// deletes the records older than the retention period
+    RetainPartial(RetainPartialConfig),
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct RetainPartialConfig {
+    #[prost(string, tag = "1")]
+    /// timestamp or date field the age of a record is measured on; Type: String
+    pub timestamp_field: String,
+    #[prost(uint32, tag = "2")]
+    /// age in seconds after which a record is deleted; Type: u32
+    pub retention_period: u32,
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Oneof)]
 pub enum RefreshConfig {
     // Hour { minute: u32 },
@@ -0,0 +1,75 @@
+use serde::{
+    de::Deserializer,
+    ser::{self, Serializer},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Hash, ::prost::Enumeration)]
+#[repr(i32)]
+/// SQL dialect used to parse the queries of the endpoints
+pub enum SqlDialect {
+    Ansi = 0,
+    Postgres = 1,
+}
+
+impl SqlDialect {
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            SqlDialect::Ansi => "ansi",
+            SqlDialect::Postgres => "postgres",
+        }
+    }
+}
+
+impl Display for SqlDialect {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_str_name())
+    }
+}
+
+impl FromStr for SqlDialect {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<SqlDialect, Self::Err> {
+        match s {
+            "Ansi" | "ansi" | "ANSI" => Ok(SqlDialect::Ansi),
+            "Postgres" | "postgres" | "PostgreSQL" | "postgresql" => Ok(SqlDialect::Postgres),
+            _ => Err("Not match any value in Enum SqlDialect"),
+        }
+    }
+}
+
+pub(crate) fn serialize_sql_dialect_i32_as_string<S>(
+    input: &Option<i32>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match input {
+        Some(input) => {
+            let dialect = SqlDialect::from_i32(*input)
+                .ok_or_else(|| ser::Error::custom("SqlDialect enum not match"))?;
+            serializer.serialize_some(dialect.as_str_name())
+        }
+        None => serializer.serialize_none(),
+    }
+}
+
+pub(crate) fn deserialize_sql_dialect_str_as_i32<'de, D>(
+    deserializer: D,
+) -> Result<Option<i32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|dialect| {
+            SqlDialect::from_str(&dialect)
+                .map(|dialect| dialect as i32)
+                .map_err(serde::de::Error::custom)
+        })
+        .transpose()
+}
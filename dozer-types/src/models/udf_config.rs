@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, ::prost::Message)]
+pub struct UdfConfig {
+    #[prost(string, tag = "1")]
+    /// name of the function in SQL queries; Type: String
+    pub name: String,
+    #[prost(string, tag = "2")]
+    /// path of the WebAssembly module implementing the function; Type: String
+    pub module: String,
+    #[prost(string, optional, tag = "3")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// name of the function exported by the module; Default: name
+    pub function: Option<String>,
+}
@@ -1,4 +1,9 @@
 use crate::models::app_config::Config;
+use crate::models::source::{
+    AppendOnlyConfig, HistoryType, MasterHistoryPolicy, RetainPartialConfig,
+    TransactionalHistoryPolicy,
+};
+use crate::models::sql_dialect::SqlDialect;
 
 #[test]
 fn error_wrong_reference_connection_name() {
@@ -167,3 +172,109 @@ fn error_missing_field_inner() {
         .to_string()
         .starts_with("connections[0].authentication: missing field `password`"));
 }
+
+#[test]
+fn deserialize_sql_dialect() {
+    let input_config = r#"
+    app_name: working_app
+    sql_dialect: postgres
+    connections: []
+    sources: []
+    endpoints:
+    - name: users
+      path: /users
+      sql: select id::text from users;
+    - name: ansi_users
+      path: /ansi_users
+      sql: select id from users;
+      sql_dialect: ansi
+  "#;
+    let config = serde_yaml::from_str::<Config>(input_config).unwrap();
+    assert_eq!(config.sql_dialect(), SqlDialect::Postgres);
+    assert_eq!(
+        config.endpoints[0].get_sql_dialect(config.sql_dialect()),
+        SqlDialect::Postgres
+    );
+    assert_eq!(
+        config.endpoints[1].get_sql_dialect(config.sql_dialect()),
+        SqlDialect::Ansi
+    );
+
+    let serialized = serde_yaml::to_string(&config).unwrap();
+    assert!(serialized.contains("sql_dialect: postgres"));
+    assert!(serialized.contains("sql_dialect: ansi"));
+
+    let input_config = r#"
+    app_name: working_app
+    sql_dialect: mysql
+  "#;
+    assert!(serde_yaml::from_str::<Config>(input_config).is_err());
+}
+
+#[test]
+fn deserialize_history_type() {
+    let input_config = r#"
+    app_name: working_app
+    connections:
+    - authentication: !Events {}
+      db_type: Events
+      name: events
+    sources:
+    - name: customers
+      table_name: customers
+      columns:
+      - id
+      - name
+      connection: !Ref events
+      history_type: !Master
+        AppendOnly:
+          unique_key_field: id
+          open_date_field: valid_from
+          closed_date_field: valid_to
+    - name: orders
+      table_name: orders
+      columns:
+      - id
+      connection: !Ref events
+      history_type: !Transactional
+        RetainPartial:
+          timestamp_field: created_at
+          retention_period: 2592000
+    - name: products
+      table_name: products
+      columns:
+      - id
+      connection: !Ref events
+  "#;
+    let config = serde_yaml::from_str::<Config>(input_config).unwrap();
+    let history_type = config.sources[0].history_type.clone();
+    let Some(HistoryType::Master(master)) = history_type else {
+        panic!("expected a master history type");
+    };
+    assert_eq!(
+        master.policy,
+        Some(MasterHistoryPolicy::AppendOnly(AppendOnlyConfig {
+            unique_key_field: "id".to_string(),
+            open_date_field: "valid_from".to_string(),
+            closed_date_field: "valid_to".to_string(),
+        }))
+    );
+    let history_type = config.sources[1].history_type.clone();
+    let Some(HistoryType::Transactional(transactional)) = history_type else {
+        panic!("expected a transactional history type");
+    };
+    assert_eq!(
+        transactional.policy,
+        Some(TransactionalHistoryPolicy::RetainPartial(
+            RetainPartialConfig {
+                timestamp_field: "created_at".to_string(),
+                retention_period: 30 * 24 * 60 * 60,
+            }
+        ))
+    );
+    assert_eq!(config.sources[2].history_type, None);
+
+    let serialized = serde_yaml::to_string(&config).unwrap();
+    let deserialized = serde_yaml::from_str::<Config>(&serialized).unwrap();
+    assert_eq!(deserialized.sources, config.sources);
+}